@@ -0,0 +1,163 @@
+use {
+  bitcoin::{
+    absolute::LockTime,
+    blockdata::{constants::genesis_block, opcodes, script},
+    hash_types::TxMerkleNode,
+    hashes::Hash,
+    Block, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+  },
+  criterion::{criterion_group, criterion_main, BatchSize, Criterion},
+  ord::{index::block_source::MemoryBlockSource, parse_ord_server_args, Index},
+  ordinals::{Etching, Runestone},
+  std::sync::Arc,
+  tempfile::TempDir,
+};
+
+const BLOCKS: u32 = 20;
+const TRANSACTIONS: u32 = 250;
+const BODY_SIZE: usize = 4096;
+
+// a chain in which every transaction reveals an inscription and etches a
+// reserved rune, spending an output of the previous block's coinbase
+fn chain() -> Arc<MemoryBlockSource> {
+  let block_source = MemoryBlockSource::default();
+
+  let genesis = genesis_block(Network::Regtest);
+
+  let mut prev_blockhash = genesis.block_hash();
+  let mut prev_coinbase = None;
+
+  block_source.push_block(0, genesis);
+
+  for height in 1..=BLOCKS {
+    let coinbase = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: script::Builder::new().push_int(height.into()).into_script(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+      }],
+      output: (0..TRANSACTIONS)
+        .map(|_| TxOut {
+          value: 10_000,
+          script_pubkey: ScriptBuf::new(),
+        })
+        .collect(),
+    };
+
+    let mut txdata = vec![coinbase.clone()];
+
+    if let Some(prev_coinbase) = prev_coinbase.replace(coinbase.txid()) {
+      for vout in 0..TRANSACTIONS {
+        txdata.push(Transaction {
+          version: 2,
+          lock_time: LockTime::ZERO,
+          input: vec![TxIn {
+            previous_output: OutPoint {
+              txid: prev_coinbase,
+              vout,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: envelope(height, vout),
+          }],
+          output: vec![
+            TxOut {
+              value: 10_000,
+              script_pubkey: ScriptBuf::new(),
+            },
+            TxOut {
+              value: 0,
+              script_pubkey: Runestone {
+                etching: Some(Etching {
+                  divisibility: Some(2),
+                  premine: Some(1000),
+                  symbol: Some('$'),
+                  ..Default::default()
+                }),
+                pointer: Some(0),
+                ..Default::default()
+              }
+              .encipher(),
+            },
+          ],
+        });
+      }
+    }
+
+    let block = Block {
+      header: bitcoin::block::Header {
+        version: bitcoin::block::Version::TWO,
+        prev_blockhash,
+        merkle_root: TxMerkleNode::all_zeros(),
+        time: height,
+        bits: bitcoin::CompactTarget::from_consensus(0x207fffff),
+        nonce: 0,
+      },
+      txdata,
+    };
+
+    prev_blockhash = block.block_hash();
+
+    block_source.push_block(height, block);
+  }
+
+  Arc::new(block_source)
+}
+
+fn envelope(height: u32, vout: u32) -> Witness {
+  let mut builder = script::Builder::new()
+    .push_opcode(opcodes::OP_FALSE)
+    .push_opcode(opcodes::all::OP_IF)
+    .push_slice(b"ord")
+    .push_slice([1])
+    .push_slice(b"text/plain;charset=utf-8")
+    .push_slice([]);
+
+  let body = format!("{height}:{vout}:").repeat(BODY_SIZE / 8);
+
+  for chunk in body.as_bytes().chunks(520) {
+    builder = builder.push_slice::<&script::PushBytes>(chunk.try_into().unwrap());
+  }
+
+  let mut witness = Witness::new();
+  witness.push(builder.push_opcode(opcodes::all::OP_ENDIF).into_script());
+  witness.push([]);
+  witness
+}
+
+fn index(c: &mut Criterion) {
+  let block_source = chain();
+
+  let mut group = c.benchmark_group("index");
+
+  group.sample_size(10);
+
+  for threads in [0, 1, 4] {
+    group.bench_function(format!("index_threads_{threads}"), |b| {
+      b.iter_batched(
+        || {
+          let tempdir = TempDir::new().unwrap();
+
+          let (settings, _server) = parse_ord_server_args(&format!(
+            "ord --regtest --datadir {} --index-runes --index-threads {threads} server",
+            tempdir.path().display(),
+          ));
+
+          let index = Index::open_with_block_source(&settings, block_source.clone()).unwrap();
+
+          (tempdir, index)
+        },
+        |(_tempdir, index)| index.update().unwrap(),
+        BatchSize::PerIteration,
+      );
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, index);
+criterion_main!(benches);
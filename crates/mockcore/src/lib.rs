@@ -19,13 +19,14 @@ use {
   bitcoincore_rpc::json::{
     Bip125Replaceable, CreateRawTransactionInput, Descriptor, EstimateMode, FeeRatePercentiles,
     FinalizePsbtResult, GetBalancesResult, GetBalancesResultEntry, GetBlockHeaderResult,
-    GetBlockStatsResult, GetBlockchainInfoResult, GetDescriptorInfoResult, GetNetworkInfoResult,
-    GetRawTransactionResult, GetRawTransactionResultVout, GetRawTransactionResultVoutScriptPubKey,
-    GetTransactionResult, GetTransactionResultDetail, GetTransactionResultDetailCategory,
-    GetTxOutResult, GetWalletInfoResult, ImportDescriptors, ImportMultiResult,
-    ListDescriptorsResult, ListTransactionResult, ListUnspentResultEntry, ListWalletDirItem,
-    ListWalletDirResult, LoadWalletResult, SignRawTransactionInput, SignRawTransactionResult,
-    Timestamp, WalletProcessPsbtResult, WalletTxInfo,
+    GetBlockStatsResult, GetBlockchainInfoResult, GetDescriptorInfoResult, GetMempoolEntryResult,
+    GetMempoolEntryResultFees, GetNetworkInfoResult, GetRawTransactionResult,
+    GetRawTransactionResultVout, GetRawTransactionResultVoutScriptPubKey, GetTransactionResult,
+    GetTransactionResultDetail, GetTransactionResultDetailCategory, GetTxOutResult,
+    GetWalletInfoResult, ImportDescriptors, ImportMultiResult, ListDescriptorsResult,
+    ListTransactionResult, ListUnspentResultEntry, ListWalletDirItem, ListWalletDirResult,
+    LoadWalletResult, SignRawTransactionInput, SignRawTransactionResult, Timestamp,
+    WalletProcessPsbtResult, WalletTxInfo,
   },
   jsonrpc_core::{IoHandler, Value},
   jsonrpc_http_server::{CloseHandle, ServerBuilder},
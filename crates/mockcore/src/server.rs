@@ -286,7 +286,9 @@ impl Api for Server {
   }
 
   fn get_wallet_info(&self) -> Result<GetWalletInfoResult, jsonrpc_core::Error> {
-    if let Some(wallet_name) = self.state().loaded_wallets.first().cloned() {
+    let state = self.state();
+
+    if let Some(wallet_name) = state.loaded_wallets.first().cloned() {
       Ok(GetWalletInfoResult {
         avoid_reuse: None,
         balance: Amount::from_sat(0),
@@ -296,7 +298,7 @@ impl Api for Server {
         keypool_size: 0,
         keypool_size_hd_internal: 0,
         pay_tx_fee: Amount::from_sat(0),
-        private_keys_enabled: true,
+        private_keys_enabled: !state.watch_only_wallets.contains(&wallet_name),
         scanning: None,
         tx_count: 0,
         unconfirmed_balance: Amount::from_sat(0),
@@ -346,12 +348,19 @@ impl Api for Server {
   fn create_wallet(
     &self,
     name: String,
-    _disable_private_keys: Option<bool>,
+    disable_private_keys: Option<bool>,
     _blank: Option<bool>,
     _passphrase: Option<String>,
     _avoid_reuse: Option<bool>,
   ) -> Result<LoadWalletResult, jsonrpc_core::Error> {
-    self.state().wallets.insert(name.clone());
+    let mut state = self.state();
+
+    state.wallets.insert(name.clone());
+
+    if disable_private_keys.unwrap_or_default() {
+      state.watch_only_wallets.insert(name.clone());
+    }
+
     Ok(LoadWalletResult {
       name,
       warning: None,
@@ -504,6 +513,59 @@ impl Api for Server {
     )
   }
 
+  fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error> {
+    Ok(self.state().mempool.iter().map(Transaction::txid).collect())
+  }
+
+  fn get_mempool_entry(&self, txid: Txid) -> Result<GetMempoolEntryResult, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let Some(tx) = state.mempool.iter().find(|tx| tx.txid() == txid) else {
+      return Err(jsonrpc_core::Error {
+        code: jsonrpc_core::types::error::ErrorCode::ServerError(-5),
+        message: "Transaction not in mempool".into(),
+        data: None,
+      });
+    };
+
+    let input_value = tx
+      .input
+      .iter()
+      .map(|input| {
+        state.transactions[&input.previous_output.txid].output
+          [usize::try_from(input.previous_output.vout).unwrap()]
+        .value
+      })
+      .sum::<u64>();
+
+    let fee =
+      Amount::from_sat(input_value - tx.output.iter().map(|output| output.value).sum::<u64>());
+
+    let vsize = u64::try_from(tx.vsize()).unwrap();
+
+    Ok(GetMempoolEntryResult {
+      vsize,
+      weight: Some(tx.weight().to_wu()),
+      time: 0,
+      height: u64::try_from(state.hashes.len() - 1).unwrap(),
+      descendant_count: 1,
+      descendant_size: vsize,
+      ancestor_count: 1,
+      ancestor_size: vsize,
+      wtxid: Txid::from_raw_hash(tx.wtxid().to_raw_hash()),
+      fees: GetMempoolEntryResultFees {
+        base: fee,
+        modified: fee,
+        ancestor: fee,
+        descendant: fee,
+      },
+      depends: Vec::new(),
+      spent_by: Vec::new(),
+      bip125_replaceable: true,
+      unbroadcast: None,
+    })
+  }
+
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error> {
     let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
 
@@ -530,6 +592,14 @@ impl Api for Server {
       }
     }
 
+    state.mempool.retain(|mempool_tx| {
+      !mempool_tx.input.iter().any(|mempool_input| {
+        tx.input
+          .iter()
+          .any(|input| input.previous_output == mempool_input.previous_output)
+      })
+    });
+
     state.mempool.push(tx.clone());
 
     Ok(tx.txid().to_string())
@@ -667,7 +737,7 @@ impl Api for Server {
 
     let tx_height = state.txid_to_block_height.get(&txid);
 
-    let confirmations = tx_height.map(|tx_height| current_height - tx_height);
+    let confirmations = tx_height.map(|tx_height| current_height - tx_height + 1);
 
     let blockhash = tx_height.map(|tx_height| state.hashes[usize::try_from(*tx_height).unwrap()]);
 
@@ -711,7 +781,11 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match state.transactions.get(&txid) {
+      match state
+        .transactions
+        .get(&txid)
+        .or_else(|| state.mempool.iter().find(|tx| tx.txid() == txid))
+      {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
@@ -1000,16 +1074,19 @@ impl Api for Server {
     psbt: String,
     _extract: Option<bool>,
   ) -> Result<FinalizePsbtResult, jsonrpc_core::Error> {
-    let mut transaction = Psbt::deserialize(
+    let psbt = Psbt::deserialize(
       &base64::engine::general_purpose::STANDARD
         .decode(psbt)
         .unwrap(),
     )
-    .unwrap()
-    .unsigned_tx;
+    .unwrap();
 
-    for input in &mut transaction.input {
-      if input.witness.is_empty() {
+    let mut transaction = psbt.unsigned_tx;
+
+    for (input, psbt_input) in transaction.input.iter_mut().zip(psbt.inputs) {
+      if let Some(witness) = psbt_input.final_script_witness {
+        input.witness = witness;
+      } else if input.witness.is_empty() {
         input.witness = Witness::from_slice(&[&[0; 64]]);
       }
     }
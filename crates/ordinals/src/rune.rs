@@ -84,6 +84,29 @@ impl Rune {
     Rune(start - ((start - end) * remainder / u128::from(INTERVAL)))
   }
 
+  /// First height at which `self` may be etched, or `None` if it is
+  /// reserved and can never be etched by name.
+  pub fn unlock_height(self, network: Network) -> Option<Height> {
+    if self.is_reserved() {
+      return None;
+    }
+
+    let mut low = Self::first_rune_height(network);
+    let mut high = low + SUBSIDY_HALVING_INTERVAL;
+
+    while low < high {
+      let mid = low + (high - low) / 2;
+
+      if Self::minimum_at_height(network, Height(mid)) <= self {
+        high = mid;
+      } else {
+        low = mid + 1;
+      }
+    }
+
+    Some(Height(low))
+  }
+
   pub fn is_reserved(self) -> bool {
     self.0 >= Self::RESERVED
   }
@@ -353,6 +376,34 @@ mod tests {
     case(Network::Regtest, 1, "ZZXZUDIVTVQA");
   }
 
+  #[test]
+  fn unlock_height() {
+    #[track_caller]
+    fn case(network: Network, rune: &str, height: u32) {
+      let rune = rune.parse::<Rune>().unwrap();
+      assert_eq!(rune.unlock_height(network), Some(Height(height)));
+      assert!(Rune::minimum_at_height(network, Height(height)) <= rune);
+      if height > Rune::first_rune_height(network) {
+        assert!(Rune::minimum_at_height(network, Height(height - 1)) > rune);
+      }
+    }
+
+    const START: u32 = SUBSIDY_HALVING_INTERVAL * 4;
+
+    case(Network::Bitcoin, "AAAAAAAAAAAAA", START);
+    case(Network::Bitcoin, "ZZYZXBRKWXVA", START);
+    case(Network::Bitcoin, "ZZXZUDIVTVQA", START + 1);
+    case(
+      Network::Bitcoin,
+      "AAAAAAAAAAAA",
+      START + SUBSIDY_HALVING_INTERVAL / 12 - 1,
+    );
+    case(Network::Bitcoin, "A", START + SUBSIDY_HALVING_INTERVAL - 1);
+    case(Network::Regtest, "ZZXZUDIVTVQA", 1);
+
+    assert_eq!(Rune(Rune::RESERVED).unlock_height(Network::Bitcoin), None);
+  }
+
   #[test]
   fn serde() {
     let rune = Rune(0);
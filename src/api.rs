@@ -162,6 +162,19 @@ impl Output {
   }
 }
 
+// @br-indexer: a page of unspent outputs of an address and what they hold.
+// Cardinal sats are those in outputs without inscriptions or runes. Balances
+// are those of the page's outputs, the next page starts after `next`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressInfo {
+  pub outputs: Vec<OutPoint>,
+  pub inscriptions: Vec<InscriptionId>,
+  pub sat_balance: u64,
+  pub cardinal_sats: u64,
+  pub runes_balances: Vec<(SpacedRune, Pile)>,
+  pub next: Option<OutPoint>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Sat {
   pub block: u32,
@@ -1,11 +1,13 @@
 use {
   self::{
+    block_source::{BlockSource, RpcBlockSource},
     entry::{
       Entry, HeaderValue, InscriptionEntry, InscriptionEntryValue, InscriptionIdValue,
       OutPointValue, RuneEntryValue, RuneIdValue, SatPointValue, SatRange, TxidValue,
     },
-    event::Event,
+    event::{Event, EventId, Events},
     lot::Lot,
+    migration::Migration,
     reorg::Reorg,
     updater::Updater,
   },
@@ -13,7 +15,7 @@ use {
   crate::{
     runes::MintError,
     subcommand::{find::FindRangeOutput, server::query},
-    templates::StatusHtml,
+    templates::{RuneAuditJson, RuneCheckJson, RunesEntriesJson, RunesEventsJson, StatusHtml},
   },
   bitcoin::block::Header,
   bitcoincore_rpc::{
@@ -31,31 +33,47 @@ use {
   std::{
     collections::HashMap,
     io::{BufWriter, Write},
+    ops::Bound,
     sync::Once,
   },
 };
 
 use crate::indexer::rune_event::{BlockId, BlockIdValue, RuneChanges, RuneChangesValue};
 use crate::indexer::{
+  constants::IndexerHeight,
   function::IndexerFunction,
   inscription_transfer::{InscriptionTransfer, InscriptionTransferValue},
-  rune_event::{RuneEventEntry, RuneEventEntryValue},
+  reorg_log::{ReorgLogEntry, ReorgLogEntryValue},
+  rune_event::{
+    Direction, RuneEvent, RuneEventEntry, RuneEventEntryValue, RuneEventResponse, RuneEventTotals,
+    RuneEventTotalsValue,
+  },
+  script_pubkey_value::{script_pubkey_hash, ScriptPubkeyHash},
 };
 
 pub use self::entry::RuneEntry;
 
+mod block_files;
+pub mod block_source;
 pub(crate) mod entry;
 pub mod event;
+pub mod export;
 mod fetcher;
 mod lot;
+mod migration;
 mod reorg;
 mod rtx;
+pub mod snapshot;
 mod updater;
+pub(crate) mod webhooks;
 
 #[cfg(test)]
 pub(crate) mod testing;
 
-const SCHEMA_VERSION: u64 = 25;
+const SCHEMA_VERSION: u64 = 37;
+
+// @br-indexer: blocks whose events are kept in the event log
+const EVENT_LOG_BLOCKS: u32 = 1_000;
 
 define_multimap_table! { SATPOINT_TO_SEQUENCE_NUMBER, &SatPointValue, u32 }
 define_multimap_table! { SAT_TO_SEQUENCE_NUMBER, u64, u32 }
@@ -81,8 +99,19 @@ define_table! { TRANSACTION_ID_TO_TRANSACTION, &TxidValue, &[u8] }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u32, u128 }
 define_table! { SEQUENCE_NUMBER_TO_INSCRIPTION_TRANSFER, u32, InscriptionTransferValue } // @todo: br-indexer: add this table
 define_table! { BLOCK_ID_TO_RUNE_EVENT, BlockIdValue, RuneEventEntryValue } // @todo: br-indexer: add this table
-define_table! { BLOCK_ID_TO_RUNE_SPENT, BlockIdValue, RuneEventEntryValue } // @todo: br-indexer: add this table
 define_table! { LAST_BLOCK_ID_TO_RUNE_CHANGES, BlockIdValue, RuneChangesValue } // @todo: br-indexer: add this table
+define_table! { OUTPOINT_TO_SCRIPT_PUBKEY, &OutPointValue, &[u8] } // @br-indexer
+define_table! { SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT, (&ScriptPubkeyHash, BlockIdValue), () } // @br-indexer
+define_table! { RUNE_ID_TO_RUNE_EVENT, (RuneIdValue, BlockIdValue), () } // @br-indexer
+define_table! { SEQUENCE_NUMBER_TO_REORG, u32, ReorgLogEntryValue } // @br-indexer
+define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], &OutPointValue } // @br-indexer
+define_table! { OUTPOINT_TO_TXOUT, &OutPointValue, &[u8] } // @br-indexer
+define_table! { RUNE_ID_TO_HOLDER_BALANCE, (RuneIdValue, &[u8]), u128 } // @br-indexer
+define_table! { RUNE_ID_TO_HOLDER_COUNT, RuneIdValue, u64 } // @br-indexer
+define_table! { EVENT_ID_TO_EVENT, (u32, u32), &[u8] } // @br-indexer
+define_table! { WEBHOOK_DEAD_LETTER, u64, &[u8] } // @br-indexer
+define_table! { WEBHOOK_OUTBOX, (u32, u64), &[u8] } // @br-indexer
+define_table! { RUNE_ID_TO_EVENT_TOTALS, RuneIdValue, RuneEventTotalsValue } // @br-indexer
 
 #[derive(Copy, Clone)]
 pub(crate) enum Statistic {
@@ -101,6 +130,10 @@ pub(crate) enum Statistic {
   IndexTransactions = 12,
   IndexSpentSats = 13,
   InitialSyncTime = 14,
+  IndexAddresses = 15,
+  EventLogStart = 16,
+  NextWebhookDelivery = 17,
+  IndexEvents = 18,
 }
 
 impl Statistic {
@@ -192,6 +225,7 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
 }
 
 pub struct Index {
+  block_source: Arc<dyn BlockSource>,
   pub(crate) client: Client,
   database: Database,
   durability: redb::Durability,
@@ -200,14 +234,18 @@ pub struct Index {
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u32>,
+  index_addresses: bool,
+  index_events: bool,
   index_runes: bool,
   index_sats: bool,
   index_spent_sats: bool,
   index_transactions: bool,
+  mempool_etchings: Mutex<HashMap<Txid, Option<Rune>>>,
   settings: Settings,
   path: PathBuf,
   started: DateTime<Utc>,
   unrecoverably_reorged: AtomicBool,
+  webhooks: Vec<webhooks::Webhook>,
 }
 
 impl Index {
@@ -219,8 +257,34 @@ impl Index {
     settings: &Settings,
     event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
   ) -> Result<Self> {
-    let client = settings.bitcoin_rpc_client(None)?;
+    Index::open_with(
+      settings,
+      event_sender,
+      settings.bitcoin_rpc_client(None)?,
+      Arc::new(RpcBlockSource::new(settings)?),
+    )
+  }
+
+  /// Opens an index which is updated from `block_source` instead of Bitcoin
+  /// Core.
+  pub fn open_with_block_source(
+    settings: &Settings,
+    block_source: Arc<dyn BlockSource>,
+  ) -> Result<Self> {
+    Index::open_with(
+      settings,
+      None,
+      Client::new(&settings.bitcoin_rpc_url(None), bitcoincore_rpc::Auth::None)?,
+      block_source,
+    )
+  }
 
+  fn open_with(
+    settings: &Settings,
+    event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
+    client: Client,
+    block_source: Arc<dyn BlockSource>,
+  ) -> Result<Self> {
     let path = settings.index().to_owned();
 
     if let Err(err) = fs::create_dir_all(path.parent().unwrap()) {
@@ -230,6 +294,12 @@ impl Index {
       );
     }
 
+    let webhooks = webhooks::Webhook::load(settings)?;
+
+    if settings.index_addresses() {
+      Self::require_address_index_from_genesis(settings)?;
+    }
+
     let index_cache_size = settings.index_cache_size();
 
     log::info!("Setting index cache size to {} bytes", index_cache_size);
@@ -279,6 +349,8 @@ impl Index {
             .unwrap_or(0);
 
           match schema_version.cmp(&SCHEMA_VERSION) {
+            cmp::Ordering::Less if Migration::can_migrate(schema_version) =>
+              Migration::migrate(&database, durability, schema_version)?,
             cmp::Ordering::Less =>
               bail!(
                 "index at `{}` appears to have been built with an older, incompatible version of ord, consider deleting and rebuilding the index: index schema {schema_version}, ord schema {SCHEMA_VERSION}",
@@ -328,6 +400,18 @@ impl Index {
         tx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_TRANSFER)?; // @br-indexer: add this table
         tx.open_table(BLOCK_ID_TO_RUNE_EVENT)?; // @br-indexer: add this table
         tx.open_table(LAST_BLOCK_ID_TO_RUNE_CHANGES)?; // @br-indexer: add this table
+        tx.open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?; // @br-indexer
+        tx.open_table(SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT)?; // @br-indexer
+        tx.open_table(RUNE_ID_TO_RUNE_EVENT)?; // @br-indexer
+        tx.open_table(SEQUENCE_NUMBER_TO_REORG)?; // @br-indexer
+        tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?; // @br-indexer
+        tx.open_table(OUTPOINT_TO_TXOUT)?; // @br-indexer
+        tx.open_table(RUNE_ID_TO_HOLDER_BALANCE)?; // @br-indexer
+        tx.open_table(RUNE_ID_TO_HOLDER_COUNT)?; // @br-indexer
+        tx.open_table(EVENT_ID_TO_EVENT)?; // @br-indexer
+        tx.open_table(WEBHOOK_DEAD_LETTER)?; // @br-indexer
+        tx.open_table(WEBHOOK_OUTBOX)?; // @br-indexer
+        tx.open_table(RUNE_ID_TO_EVENT_TOTALS)?; // @br-indexer
 
         {
           let mut outpoint_to_sat_ranges = tx.open_table(OUTPOINT_TO_SAT_RANGES)?;
@@ -337,6 +421,18 @@ impl Index {
             outpoint_to_sat_ranges.insert(&OutPoint::null().store(), [].as_slice())?;
           }
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexAddresses,
+            u64::from(settings.index_addresses()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexEvents,
+            u64::from(settings.index_events()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexRunes,
@@ -415,6 +511,8 @@ impl Index {
       Err(error) => bail!("failed to open index: {error}"),
     };
 
+    let index_addresses;
+    let index_events;
     let index_runes;
     let index_sats;
     let index_spent_sats;
@@ -423,17 +521,24 @@ impl Index {
     {
       let tx = database.begin_read()?;
       let statistics = tx.open_table(STATISTIC_TO_COUNT)?;
+      index_addresses = Self::is_statistic_set(&statistics, Statistic::IndexAddresses)?;
+      index_events = Self::is_statistic_set(&statistics, Statistic::IndexEvents)?;
       index_runes = Self::is_statistic_set(&statistics, Statistic::IndexRunes)?;
       index_sats = Self::is_statistic_set(&statistics, Statistic::IndexSats)?;
       index_spent_sats = Self::is_statistic_set(&statistics, Statistic::IndexSpentSats)?;
       index_transactions = Self::is_statistic_set(&statistics, Statistic::IndexTransactions)?;
     }
 
+    if index_addresses {
+      Self::require_address_index_from_genesis(settings)?;
+    }
+
     let genesis_block_coinbase_transaction =
       settings.chain().genesis_block().coinbase().unwrap().clone();
 
     Ok(Self {
       genesis_block_coinbase_txid: genesis_block_coinbase_transaction.txid(),
+      block_source,
       client,
       database,
       durability,
@@ -441,17 +546,39 @@ impl Index {
       first_inscription_height: settings.first_inscription_height(),
       genesis_block_coinbase_transaction,
       height_limit: settings.height_limit(),
+      index_addresses,
+      index_events,
       index_runes,
       index_sats,
       index_spent_sats,
       index_transactions,
+      mempool_etchings: Mutex::new(HashMap::new()),
       settings: settings.clone(),
       path,
       started: Utc::now(),
       unrecoverably_reorged: AtomicBool::new(false),
+      webhooks,
     })
   }
 
+  // @br-indexer: the address index only sees outputs of indexed blocks, so
+  // it would silently miss outputs of blocks below the first indexed height
+  fn require_address_index_from_genesis(settings: &Settings) -> Result {
+    let first_height = IndexerHeight::get_first_height(
+      settings.chain(),
+      settings.index_inscriptions(),
+      settings.first_inscription_height(),
+      settings.first_rune_height(),
+    );
+
+    ensure!(
+      first_height == 0,
+      "--index-addresses requires indexing from genesis, but blocks below height {first_height} would not be indexed, consider passing --first-inscription-height=0"
+    );
+
+    Ok(())
+  }
+
   #[cfg(test)]
   fn set_durability(&mut self, durability: redb::Durability) {
     self.durability = durability;
@@ -468,6 +595,14 @@ impl Index {
     )
   }
 
+  pub(crate) fn has_address_index(&self) -> bool {
+    self.index_addresses
+  }
+
+  pub(crate) fn has_event_index(&self) -> bool {
+    self.index_events
+  }
+
   pub(crate) fn has_rune_index(&self) -> bool {
     self.index_runes
   }
@@ -762,6 +897,95 @@ impl Index {
     Ok(())
   }
 
+  // @br-indexer: make an event reachable from its owner's script pubkey
+  pub(crate) fn index_rune_event_script_pubkey(
+    script_pubkey_hash_to_rune_event: &mut Table<(&'static ScriptPubkeyHash, BlockIdValue), ()>,
+    script_pubkey: &Script,
+    id: BlockId,
+  ) -> Result {
+    // burns have no owner
+    if !script_pubkey.is_empty() && !script_pubkey.is_op_return() {
+      script_pubkey_hash_to_rune_event
+        .insert((&script_pubkey_hash(script_pubkey), id.store()), ())?;
+    }
+
+    Ok(())
+  }
+
+  // @br-indexer: apply a balance change event to its owner's holding
+  pub(crate) fn update_rune_holder(
+    rune_id_to_holder_balance: &mut Table<(RuneIdValue, &'static [u8]), u128>,
+    rune_id_to_holder_count: &mut Table<RuneIdValue, u64>,
+    entry: &RuneEventEntry,
+  ) -> Result {
+    let Some(direction) = entry.event.direction() else {
+      return Ok(());
+    };
+
+    let key = (entry.rune_id.store(), entry.script_pubkey.as_bytes());
+
+    let balance = rune_id_to_holder_balance
+      .get(key)?
+      .map(|balance| balance.value());
+
+    // balance changes of an outpoint always net out, so a holder balance out
+    // of range is a bug, which shouldn't stop the rest of the index from
+    // being updated
+    let updated = match direction {
+      Direction::Credit => balance.unwrap_or_default().checked_add(entry.amount),
+      Direction::Debit => balance.unwrap_or_default().checked_sub(entry.amount),
+    }
+    .unwrap_or_else(|| {
+      log::error!(
+        "holder balance of rune {} out of range for {}, saturating",
+        entry.rune_id,
+        entry.script_pubkey
+      );
+
+      match direction {
+        Direction::Credit => u128::MAX,
+        Direction::Debit => 0,
+      }
+    });
+
+    let count = rune_id_to_holder_count
+      .get(entry.rune_id.store())?
+      .map(|count| count.value())
+      .unwrap_or_default();
+
+    if updated == 0 {
+      if balance.is_some() {
+        rune_id_to_holder_balance.remove(key)?;
+        rune_id_to_holder_count.insert(entry.rune_id.store(), count - 1)?;
+      }
+    } else {
+      rune_id_to_holder_balance.insert(key, updated)?;
+
+      if balance.is_none() {
+        rune_id_to_holder_count.insert(entry.rune_id.store(), count + 1)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  // @br-indexer: add an event to the running totals of its rune
+  pub(crate) fn update_rune_event_totals(
+    rune_id_to_event_totals: &mut Table<RuneIdValue, RuneEventTotalsValue>,
+    entry: &RuneEventEntry,
+  ) -> Result {
+    let mut totals = rune_id_to_event_totals
+      .get(entry.rune_id.store())?
+      .map(|totals| RuneEventTotals::load(totals.value()))
+      .unwrap_or_default();
+
+    totals.add(entry);
+
+    rune_id_to_event_totals.insert(entry.rune_id.store(), totals.store())?;
+
+    Ok(())
+  }
+
   pub(crate) fn is_statistic_set(
     statistics: &ReadOnlyTable<u64, u64>,
     statistic: Statistic,
@@ -921,6 +1145,102 @@ impl Index {
     Ok(Some((RuneId::load(id), entry, parent)))
   }
 
+  // @br-indexer: whether `rune` can be etched in the next block, and what an
+  // etching of it must commit to. Pending wallet etchings are not known to
+  // the index, so `pending_etching` is left for the caller to fill in.
+  pub(crate) fn rune_check(&self, spaced_rune: SpacedRune) -> Result<RuneCheckJson> {
+    let rune = spaced_rune.rune;
+
+    let network = self.settings.chain().network();
+
+    let height = self.block_count()?;
+
+    let minimum = Rune::minimum_at_height(network, Height(height));
+
+    let reserved = rune.is_reserved();
+
+    let etched = self.rune(rune)?.map(|(id, _entry, _parent)| id);
+
+    let unlock_height = rune.unlock_height(network).map(|height| height.n());
+
+    // the commit must be mined no earlier than the next block, and the reveal
+    // can be mined once the commit has enough confirmations
+    let reveal_height = unlock_height.map(|unlock_height| {
+      unlock_height.max(height + u32::from(Runestone::COMMIT_CONFIRMATIONS) - 1)
+    });
+
+    let unlocked = !reserved && rune >= minimum && height >= Rune::first_rune_height(network);
+
+    let mempool = self
+      .mempool_etchings()?
+      .into_iter()
+      .filter(|(_txid, etching)| *etching == rune)
+      .map(|(txid, _etching)| txid)
+      .collect::<Vec<Txid>>();
+
+    Ok(RuneCheckJson {
+      rune: spaced_rune,
+      available: unlocked && etched.is_none() && mempool.is_empty(),
+      reserved,
+      etched,
+      height,
+      minimum,
+      unlocked,
+      unlock_height,
+      commitment: hex::encode(rune.commitment()),
+      commit_confirmations: Runestone::COMMIT_CONFIRMATIONS,
+      reveal_height,
+      mempool,
+      pending_etching: None,
+      tip_hash: self.block_hash(None)?,
+    })
+  }
+
+  // @br-indexer: runes etched by mempool transactions. Mempool transactions
+  // don't change, so each is only fetched the first time it's seen, and
+  // transactions that have left the mempool are forgotten.
+  fn mempool_etchings(&self) -> Result<Vec<(Txid, Rune)>> {
+    let mempool = self.client.get_raw_mempool()?;
+
+    let mut cache = self.mempool_etchings.lock().unwrap();
+
+    let mut retained = HashMap::with_capacity(mempool.len());
+
+    let mut etchings = Vec::new();
+
+    for txid in mempool {
+      let etching = match cache.remove(&txid) {
+        Some(etching) => etching,
+        None => {
+          // the transaction may have been mined or evicted since the
+          // mempool was listed
+          let Some(transaction) = self.client.get_raw_transaction(&txid, None).into_option()?
+          else {
+            continue;
+          };
+
+          match Runestone::decipher(&transaction) {
+            Some(Artifact::Runestone(runestone)) => {
+              runestone.etching.and_then(|etching| etching.rune)
+            }
+            Some(Artifact::Cenotaph(cenotaph)) => cenotaph.etching,
+            None => None,
+          }
+        }
+      };
+
+      if let Some(rune) = etching {
+        etchings.push((txid, rune));
+      }
+
+      retained.insert(txid, etching);
+    }
+
+    *cache = retained;
+
+    Ok(etchings)
+  }
+
   pub(crate) fn runes(&self) -> Result<Vec<(RuneId, RuneEntry)>> {
     let mut entries = Vec::new();
 
@@ -1816,13 +2136,9 @@ impl Index {
     &self,
     block_height: u64,
     is_spent: bool,
-  ) -> Result<(Vec<(BlockId, RuneEventEntry)>, u64)> {
-    let rtx = self.database.begin_read()?;
-    let mut block_id_to_rune_event_entry = rtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
-    if is_spent {
-      block_id_to_rune_event_entry = rtx.open_table(BLOCK_ID_TO_RUNE_SPENT)?;
-    }
-    let total = block_id_to_rune_event_entry.len().unwrap_or(0);
+  ) -> Result<(Vec<(BlockId, RuneEventEntry)>, u64, Option<BlockHash>)> {
+    let rtx = self.begin_read()?;
+    let block_id_to_rune_event_entry = rtx.0.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
 
     let min_id = BlockId {
       block: block_height,
@@ -1844,18 +2160,25 @@ impl Index {
           )
         })
       })
+      .filter(|result| {
+        // @br-indexer: spent events are served separately
+        result.as_ref().map_or(true, |(_, entry)| {
+          (entry.event == RuneEvent::Spent) == is_spent
+        })
+      })
       .collect::<Result<Vec<(BlockId, RuneEventEntry)>, StorageError>>()?;
 
-    Ok((runes_events, total))
+    let total = u64::try_from(runes_events.len()).unwrap();
+
+    Ok((runes_events, total, rtx.block_hash_at(block_height)?))
   }
 
   pub(crate) fn get_runes_changes_by_height(
     &self,
     block_height: u64,
-  ) -> Result<(Vec<RuneChanges>, u64)> {
-    let rtx = self.database.begin_read()?;
-    let last_block_id_to_rune_event_entry = rtx.open_table(LAST_BLOCK_ID_TO_RUNE_CHANGES)?;
-    let total = last_block_id_to_rune_event_entry.len().unwrap_or(0);
+  ) -> Result<(Vec<RuneChanges>, u64, Option<BlockHash>)> {
+    let rtx = self.begin_read()?;
+    let last_block_id_to_rune_event_entry = rtx.0.open_table(LAST_BLOCK_ID_TO_RUNE_CHANGES)?;
 
     let min_id = BlockId {
       block: block_height,
@@ -1872,20 +2195,24 @@ impl Index {
       .map(|result| result.map(|(_, entry)| RuneChanges::load(entry.value())))
       .collect::<Result<Vec<RuneChanges>, StorageError>>()?;
 
-    Ok((runes_changes, total))
+    let total = u64::try_from(runes_changes.len()).unwrap();
+
+    Ok((runes_changes, total, rtx.block_hash_at(block_height)?))
   }
 
+  // @br-indexer: a page of every rune event, with the block hashes of the
+  // events and the index tip read from the same transaction
   pub(crate) fn get_runes_events_paginated(
     &self,
     page_size: u32,
     page_index: u32,
-  ) -> Result<(Vec<(BlockId, RuneEventEntry)>, u64)> {
-    let rtx = self.database.begin_read()?;
+  ) -> Result<RunesEventsJson> {
+    let rtx = self.begin_read()?;
 
-    let block_id_to_rune_event_entry = rtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
+    let block_id_to_rune_event_entry = rtx.0.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
     let total = block_id_to_rune_event_entry.len().unwrap_or(0);
 
-    let mut entries = Vec::new();
+    let mut events = Vec::new();
 
     for result in block_id_to_rune_event_entry
       .iter()?
@@ -1893,179 +2220,594 @@ impl Index {
       .take(usize::try_from(page_size.saturating_add(1)).unwrap_or_default())
     {
       let (id, entry) = result?;
-      entries.push((
+      events.push((
         BlockId::load(id.value()),
-        RuneEventEntry::load(entry.value()),
+        RuneEventResponse::from(RuneEventEntry::load(entry.value())),
       ));
     }
 
-    let more = entries.len() > usize::try_from(page_size).unwrap_or_default();
+    let more = events.len() > usize::try_from(page_size).unwrap_or_default();
     if more {
-      entries.pop();
+      events.pop();
     }
-    Ok((entries, total))
+
+    Ok(RunesEventsJson {
+      block_hashes: rtx.block_hashes(
+        events
+          .iter()
+          .map(|(block_id, _)| u32::try_from(block_id.block).unwrap()),
+      )?,
+      total,
+      block: rtx.block_count()?,
+      tip_hash: rtx.block_hash(None)?,
+      size: u32::try_from(events.len()).unwrap(),
+      events,
+    })
   }
 
-  pub(crate) fn get_inscriptions_transfers_paginated(
+  // @br-indexer: events whose owner is `script_pubkey`, oldest first, starting
+  // after `cursor`. Returns at most `limit` events and the cursor of the next
+  // page, if any.
+  pub(crate) fn get_runes_events_by_script_pubkey(
     &self,
-    page_size: u32,
-    page_index: u32,
-  ) -> Result<(Vec<(u32, InscriptionTransfer)>, u32, bool)> {
+    script_pubkey: &Script,
+    cursor: Option<BlockId>,
+    limit: usize,
+    rune_id: Option<RuneId>,
+    event: Option<RuneEvent>,
+  ) -> Result<(Vec<(BlockId, RuneEventEntry)>, Option<BlockId>)> {
     let rtx = self.database.begin_read()?;
-    let sequence_number_to_inscriptions_transfers =
-      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_TRANSFER)?;
 
-    let total = IndexerFunction::count_seq_data(sequence_number_to_inscriptions_transfers.iter()?);
+    let script_pubkey_hash_to_rune_event = rtx.open_table(SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT)?;
+    let block_id_to_rune_event = rtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
 
-    let start = page_size.saturating_mul(page_index);
+    let hash = script_pubkey_hash(script_pubkey);
 
-    let end = start.saturating_add(page_size);
+    let lower = match cursor {
+      Some(cursor) => Bound::Excluded((&hash, cursor.store())),
+      None => Bound::Included((&hash, (0, 0))),
+    };
 
-    let mut inscriptions_transfers = sequence_number_to_inscriptions_transfers
-      .range(start..=end)?
-      .rev()
-      .map(|result| {
-        result.map(|(number, entry)| (number.value(), InscriptionTransfer::load(entry.value())))
-      })
-      .collect::<Result<Vec<(u32, InscriptionTransfer)>, StorageError>>()?;
+    let mut entries = Vec::new();
 
-    let more = u32::try_from(inscriptions_transfers.len()).unwrap_or(u32::MAX) > page_size;
+    for result in script_pubkey_hash_to_rune_event.range::<(&ScriptPubkeyHash, BlockIdValue)>((
+      lower,
+      Bound::Included((&hash, (u64::MAX, u32::MAX))),
+    ))? {
+      let (key, _) = result?;
+      let id = BlockId::load(key.value().1);
+
+      let Some(entry) = block_id_to_rune_event
+        .get(id.store())?
+        .map(|entry| RuneEventEntry::load(entry.value()))
+      else {
+        continue;
+      };
 
-    if more {
-      inscriptions_transfers.remove(0);
+      if entry.script_pubkey.as_script() != script_pubkey
+        || rune_id.is_some_and(|rune_id| entry.rune_id != rune_id)
+        || event.is_some_and(|event| entry.event != event)
+      {
+        continue;
+      }
+
+      if entries.len() == limit {
+        let next = entries.last().map(|(id, _)| *id);
+        return Ok((entries, next));
+      }
+
+      entries.push((id, entry));
     }
 
-    Ok((inscriptions_transfers, total, more))
+    Ok((entries, None))
   }
 
-  pub(crate) fn get_runes_entries_in_block(
+  // @br-indexer: events of `rune_id` between `from_height` and `to_height`
+  // inclusive, oldest first, starting after `cursor`. Returns at most `limit`
+  // events and the cursor of the next page, if any.
+  pub(crate) fn get_rune_events(
     &self,
-    block_height: u64,
-  ) -> Result<Vec<(RuneId, RuneEntry)>> {
+    rune_id: RuneId,
+    from_height: Option<u64>,
+    to_height: Option<u64>,
+    cursor: Option<BlockId>,
+    limit: usize,
+    event: Option<RuneEvent>,
+  ) -> Result<(Vec<(BlockId, RuneEventEntry)>, Option<BlockId>)> {
     let rtx = self.database.begin_read()?;
 
-    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let rune_id_to_rune_event = rtx.open_table(RUNE_ID_TO_RUNE_EVENT)?;
+    let block_id_to_rune_event = rtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
 
-    let min_id = RuneId {
-      block: block_height,
-      tx: 0,
+    let start = BlockId {
+      block: from_height.unwrap_or(0),
+      index: 0,
     };
 
-    let max_id = RuneId {
-      block: block_height,
-      tx: u32::MAX,
+    let end = BlockId {
+      block: to_height.unwrap_or(u64::MAX),
+      index: u32::MAX,
     };
 
-    let runes = rune_id_to_rune_entry
-      .range(min_id.store()..=max_id.store())?
-      .map(|result| {
-        result.map(|(id, entry)| (RuneId::load(id.value()), RuneEntry::load(entry.value())))
-      })
-      .collect::<Result<Vec<(RuneId, RuneEntry)>, StorageError>>()?;
+    let lower = match cursor {
+      Some(cursor) if cursor.store() >= start.store() => {
+        Bound::Excluded((rune_id.store(), cursor.store()))
+      }
+      _ => Bound::Included((rune_id.store(), start.store())),
+    };
 
-    Ok(runes)
-  }
-  // @br-indexer: add index function --> end
+    let mut entries = Vec::new();
 
-  pub(crate) fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
-    let rtx = self.database.begin_read()?;
+    for result in
+      rune_id_to_rune_event.range((lower, Bound::Included((rune_id.store(), end.store()))))?
+    {
+      let (key, _) = result?;
+      let id = BlockId::load(key.value().1);
+
+      let Some(entry) = block_id_to_rune_event
+        .get(id.store())?
+        .map(|entry| RuneEventEntry::load(entry.value()))
+      else {
+        continue;
+      };
 
-    let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
-    let sequence_number_to_inscription_entry =
-      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+      if event.is_some_and(|event| entry.event != event) {
+        continue;
+      }
 
-    let Some(newest_sequence_number) = height_to_last_sequence_number
-      .get(&block_height)?
-      .map(|ag| ag.value())
-    else {
-      return Ok(Vec::new());
-    };
+      if entries.len() == limit {
+        let next = entries.last().map(|(id, _)| *id);
+        return Ok((entries, next));
+      }
 
-    let oldest_sequence_number = height_to_last_sequence_number
-      .get(block_height.saturating_sub(1))?
-      .map(|ag| ag.value())
-      .unwrap_or(0);
+      entries.push((id, entry));
+    }
 
-    (oldest_sequence_number..newest_sequence_number)
-      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
-        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
-        Ok(None) => Err(anyhow!(
-          "could not find inscription for inscription number {num}"
-        )),
-        Err(err) => Err(anyhow!(err)),
-      })
-      .collect::<Result<Vec<InscriptionId>>>()
+    Ok((entries, None))
   }
 
-  pub(crate) fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
+  // @br-indexer: unspent outputs locked by `script_pubkey` after `cursor`, in
+  // outpoint byte order. Returns at most `limit` outputs and the cursor of the
+  // next page, if any.
+  pub(crate) fn get_address_outputs(
+    &self,
+    script_pubkey: &Script,
+    cursor: Option<OutPoint>,
+    limit: usize,
+  ) -> Result<(Vec<(OutPoint, TxOut)>, Option<OutPoint>)> {
     let rtx = self.database.begin_read()?;
 
-    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let script_pubkey_to_outpoint = rtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+    let outpoint_to_txout = rtx.open_table(OUTPOINT_TO_TXOUT)?;
 
-    let min_id = RuneId {
-      block: block_height,
-      tx: 0,
-    };
+    let cursor = cursor.map(|cursor| cursor.store());
 
-    let max_id = RuneId {
-      block: block_height,
-      tx: u32::MAX,
-    };
+    let mut outputs = Vec::new();
 
-    let runes = rune_id_to_rune_entry
-      .range(min_id.store()..=max_id.store())?
-      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
-      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+    for result in script_pubkey_to_outpoint.get(script_pubkey.as_bytes())? {
+      let value = *result?.value();
 
-    Ok(runes)
-  }
+      if cursor.is_some_and(|cursor| value <= cursor) {
+        continue;
+      }
 
-  pub(crate) fn get_highest_paying_inscriptions_in_block(
-    &self,
-    block_height: u32,
-    n: usize,
-  ) -> Result<(Vec<InscriptionId>, usize)> {
-    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+      if outputs.len() == limit {
+        let next = outputs
+          .last()
+          .map(|(outpoint, _): &(OutPoint, TxOut)| *outpoint);
+        return Ok((outputs, next));
+      }
 
-    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
-    for id in &inscription_ids {
-      inscription_to_fee.push((
-        *id,
-        self
-          .get_inscription_entry(*id)?
-          .ok_or_else(|| anyhow!("could not get entry for inscription {id}"))?
-          .fee,
-      ));
-    }
+      let outpoint = OutPoint::load(value);
 
-    inscription_to_fee.sort_by_key(|(_, fee)| *fee);
+      let tx_out = outpoint_to_txout
+        .get(&outpoint.store())?
+        .ok_or_else(|| anyhow!("address index is missing output {outpoint}"))?;
 
-    Ok((
-      inscription_to_fee
-        .iter()
-        .map(|(id, _)| *id)
-        .rev()
-        .take(n)
-        .collect(),
-      inscription_ids.len(),
-    ))
+      outputs.push((outpoint, consensus::encode::deserialize(tx_out.value())?));
+    }
+
+    Ok((outputs, None))
   }
 
-  pub(crate) fn get_home_inscriptions(&self) -> Result<Vec<InscriptionId>> {
+  // @br-indexer: number of script pubkeys holding `rune_id`
+  pub(crate) fn get_rune_holder_count(&self, rune_id: RuneId) -> Result<u64> {
     Ok(
       self
         .database
         .begin_read()?
-        .open_table(HOME_INSCRIPTIONS)?
-        .iter()?
-        .rev()
-        .flat_map(|result| result.map(|(_number, id)| InscriptionId::load(id.value())))
-        .collect(),
+        .open_table(RUNE_ID_TO_HOLDER_COUNT)?
+        .get(rune_id.store())?
+        .map(|count| count.value())
+        .unwrap_or_default(),
     )
   }
 
-  pub(crate) fn get_feed_inscriptions(&self, n: usize) -> Result<Vec<(u32, InscriptionId)>> {
-    Ok(
+  // @br-indexer: holders of `rune_id` and their balances, ordered by script
+  // pubkey, starting after `cursor`. Returns at most `limit` holders and the
+  // cursor of the next page, if any.
+  pub(crate) fn get_rune_holders(
+    &self,
+    rune_id: RuneId,
+    cursor: Option<&Script>,
+    limit: usize,
+  ) -> Result<(Vec<(ScriptBuf, u128)>, Option<ScriptBuf>)> {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_holder_balance = rtx.open_table(RUNE_ID_TO_HOLDER_BALANCE)?;
+
+    let lower = match cursor {
+      Some(cursor) => Bound::Excluded((rune_id.store(), cursor.as_bytes())),
+      None => Bound::Included((rune_id.store(), [].as_slice())),
+    };
+
+    let mut holders = Vec::new();
+
+    for result in
+      rune_id_to_holder_balance.range::<(RuneIdValue, &[u8])>((lower, Bound::Unbounded))?
+    {
+      let (key, balance) = result?;
+      let (id, script_pubkey) = key.value();
+
+      if id != rune_id.store() {
+        break;
+      }
+
+      if holders.len() == limit {
+        let next = holders
+          .last()
+          .map(|(script_pubkey, _): &(ScriptBuf, u128)| script_pubkey.clone());
+        return Ok((holders, next));
+      }
+
+      holders.push((
+        ScriptBuf::from_bytes(script_pubkey.to_vec()),
+        balance.value(),
+      ));
+    }
+
+    Ok((holders, None))
+  }
+
+  // @br-indexer: reconcile the rune entry of `rune_id` with the running
+  // totals of its events
+  pub(crate) fn get_rune_audit(&self, rune_id: RuneId) -> Result<Option<RuneAuditJson>> {
+    let rtx = self.begin_read()?;
+
+    let Some(entry) = rtx
+      .0
+      .open_table(RUNE_ID_TO_RUNE_ENTRY)?
+      .get(&rune_id.store())?
+      .map(|entry| RuneEntry::load(entry.value()))
+    else {
+      return Ok(None);
+    };
+
+    let totals = rtx
+      .0
+      .open_table(RUNE_ID_TO_EVENT_TOTALS)?
+      .get(rune_id.store())?
+      .map(|totals| RuneEventTotals::load(totals.value()))
+      .unwrap_or_default();
+
+    let supply = entry.supply();
+    let minted = supply - entry.premine;
+    let outstanding = totals.outstanding();
+
+    Ok(Some(RuneAuditJson {
+      rune_id,
+      spaced_rune: entry.spaced_rune,
+      supply: supply.to_string(),
+      burned: entry.burned.to_string(),
+      minted: minted.to_string(),
+      events: totals.events,
+      event_minted: totals.minted.to_string(),
+      event_burned: totals.burned.to_string(),
+      credited: totals.credited.to_string(),
+      debited: totals.debited.to_string(),
+      outstanding: outstanding.map(|outstanding| outstanding.to_string()),
+      balanced: outstanding == supply.checked_sub(entry.burned)
+        && totals.minted == minted
+        && totals.burned == entry.burned,
+      tip_hash: rtx.block_hash(None)?,
+    }))
+  }
+
+  // @br-indexer: hashes of the indexed blocks at `heights`, heights above the
+  // index tip are left out
+  pub(crate) fn block_hashes(
+    &self,
+    heights: impl IntoIterator<Item = u32>,
+  ) -> Result<BTreeMap<u32, BlockHash>> {
+    self.begin_read()?.block_hashes(heights)
+  }
+
+  // @br-indexer: logged events after `after`, oldest first, or the whole log
+  // if `after` is `None`. Returns at most `limit` events, the reorg that
+  // orphaned the block of `after`, or `Events::Unavailable` if events after
+  // `after` have aged out of the log or `after` is from an unknown block.
+  pub(crate) fn get_events(&self, after: Option<EventId>, limit: usize) -> Result<Events> {
+    let rtx = self.begin_read()?;
+
+    let log_start = rtx
+      .0
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::EventLogStart.key())?
+      .map(|start| start.value())
+      .unwrap_or_default();
+
+    let lower = match after {
+      Some(after) => {
+        if rtx.block_hash(Some(after.height))? != Some(after.block_hash) {
+          for result in rtx.0.open_table(SEQUENCE_NUMBER_TO_REORG)?.iter()?.rev() {
+            let entry = ReorgLogEntry::load(result?.1.value());
+
+            if entry
+              .orphaned_blocks
+              .contains(&(after.height, after.block_hash))
+            {
+              return Ok(Events::Reorged(entry));
+            }
+          }
+
+          return Ok(Events::Unavailable);
+        }
+
+        let next_height = if after.index == u32::MAX {
+          u64::from(after.height) + 1
+        } else {
+          after.height.into()
+        };
+
+        if next_height < log_start {
+          return Ok(Events::Unavailable);
+        }
+
+        Bound::Excluded((after.height, after.index))
+      }
+      None => {
+        if log_start > 0 {
+          return Ok(Events::Unavailable);
+        }
+
+        Bound::Unbounded
+      }
+    };
+
+    let mut events = Vec::new();
+
+    for result in rtx
+      .0
+      .open_table(EVENT_ID_TO_EVENT)?
+      .range::<(u32, u32)>((lower, Bound::Unbounded))?
+      .take(limit)
+    {
+      let (key, event) = result?;
+      let (height, index) = key.value();
+
+      let Some(block_hash) = rtx.block_hash(Some(height))? else {
+        break;
+      };
+
+      events.push((
+        EventId {
+          height,
+          index,
+          block_hash,
+        },
+        serde_json::from_slice(event.value())?,
+      ));
+    }
+
+    Ok(Events::Page(events))
+  }
+
+  // @br-indexer: id of a position after every event of the indexed chain tip
+  pub(crate) fn get_event_tip(&self) -> Result<Option<EventId>> {
+    let rtx = self.begin_read()?;
+
+    Ok(
+      rtx
+        .block_height()?
+        .zip(rtx.block_hash(None)?)
+        .map(|(height, block_hash)| EventId::after_block(height.n(), block_hash)),
+    )
+  }
+
+  // @br-indexer: reorgs after `cursor`, oldest first. Returns at most `limit`
+  // reorgs, the total number logged and the cursor of the next page, if any.
+  pub(crate) fn get_reorgs(
+    &self,
+    cursor: Option<u32>,
+    limit: usize,
+  ) -> Result<(Vec<(u32, ReorgLogEntry)>, u64, Option<u32>)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_reorg = rtx.open_table(SEQUENCE_NUMBER_TO_REORG)?;
+
+    let lower = match cursor {
+      Some(cursor) => Bound::Excluded(cursor),
+      None => Bound::Unbounded,
+    };
+
+    let mut reorgs = Vec::new();
+
+    for result in sequence_number_to_reorg.range::<u32>((lower, Bound::Unbounded))? {
+      let (sequence_number, entry) = result?;
+
+      if reorgs.len() == limit {
+        let next = reorgs.last().map(|(sequence_number, _)| *sequence_number);
+        return Ok((reorgs, sequence_number_to_reorg.len()?, next));
+      }
+
+      reorgs.push((sequence_number.value(), ReorgLogEntry::load(entry.value())));
+    }
+
+    Ok((reorgs, sequence_number_to_reorg.len()?, None))
+  }
+
+  pub(crate) fn get_inscriptions_transfers_paginated(
+    &self,
+    page_size: u32,
+    page_index: u32,
+  ) -> Result<(Vec<(u32, InscriptionTransfer)>, u32, bool)> {
+    let rtx = self.database.begin_read()?;
+    let sequence_number_to_inscriptions_transfers =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_TRANSFER)?;
+
+    let total = IndexerFunction::count_seq_data(sequence_number_to_inscriptions_transfers.iter()?);
+
+    let start = page_size.saturating_mul(page_index);
+
+    let end = start.saturating_add(page_size);
+
+    let mut inscriptions_transfers = sequence_number_to_inscriptions_transfers
+      .range(start..=end)?
+      .rev()
+      .map(|result| {
+        result.map(|(number, entry)| (number.value(), InscriptionTransfer::load(entry.value())))
+      })
+      .collect::<Result<Vec<(u32, InscriptionTransfer)>, StorageError>>()?;
+
+    let more = u32::try_from(inscriptions_transfers.len()).unwrap_or(u32::MAX) > page_size;
+
+    if more {
+      inscriptions_transfers.remove(0);
+    }
+
+    Ok((inscriptions_transfers, total, more))
+  }
+
+  pub(crate) fn get_runes_entries_in_block(&self, block_height: u64) -> Result<RunesEntriesJson> {
+    let rtx = self.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.0.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let min_id = RuneId {
+      block: block_height,
+      tx: 0,
+    };
+
+    let max_id = RuneId {
+      block: block_height,
+      tx: u32::MAX,
+    };
+
+    let runes = rune_id_to_rune_entry
+      .range(min_id.store()..=max_id.store())?
+      .map(|result| {
+        result.map(|(id, entry)| (RuneId::load(id.value()), RuneEntry::load(entry.value())))
+      })
+      .collect::<Result<Vec<(RuneId, RuneEntry)>, StorageError>>()?;
+
+    Ok(RunesEntriesJson {
+      runes,
+      block: u32::try_from(block_height).unwrap_or(u32::MAX),
+      block_hash: rtx.block_hash_at(block_height)?,
+      tip_hash: rtx.block_hash(None)?,
+    })
+  }
+  // @br-indexer: add index function --> end
+
+  pub(crate) fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let Some(newest_sequence_number) = height_to_last_sequence_number
+      .get(&block_height)?
+      .map(|ag| ag.value())
+    else {
+      return Ok(Vec::new());
+    };
+
+    let oldest_sequence_number = height_to_last_sequence_number
+      .get(block_height.saturating_sub(1))?
+      .map(|ag| ag.value())
+      .unwrap_or(0);
+
+    (oldest_sequence_number..newest_sequence_number)
+      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
+        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
+        Ok(None) => Err(anyhow!(
+          "could not find inscription for inscription number {num}"
+        )),
+        Err(err) => Err(anyhow!(err)),
+      })
+      .collect::<Result<Vec<InscriptionId>>>()
+  }
+
+  pub(crate) fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let min_id = RuneId {
+      block: block_height,
+      tx: 0,
+    };
+
+    let max_id = RuneId {
+      block: block_height,
+      tx: u32::MAX,
+    };
+
+    let runes = rune_id_to_rune_entry
+      .range(min_id.store()..=max_id.store())?
+      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
+      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+
+    Ok(runes)
+  }
+
+  pub(crate) fn get_highest_paying_inscriptions_in_block(
+    &self,
+    block_height: u32,
+    n: usize,
+  ) -> Result<(Vec<InscriptionId>, usize)> {
+    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+
+    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
+    for id in &inscription_ids {
+      inscription_to_fee.push((
+        *id,
+        self
+          .get_inscription_entry(*id)?
+          .ok_or_else(|| anyhow!("could not get entry for inscription {id}"))?
+          .fee,
+      ));
+    }
+
+    inscription_to_fee.sort_by_key(|(_, fee)| *fee);
+
+    Ok((
+      inscription_to_fee
+        .iter()
+        .map(|(id, _)| *id)
+        .rev()
+        .take(n)
+        .collect(),
+      inscription_ids.len(),
+    ))
+  }
+
+  pub(crate) fn get_home_inscriptions(&self) -> Result<Vec<InscriptionId>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(HOME_INSCRIPTIONS)?
+        .iter()?
+        .rev()
+        .flat_map(|result| result.map(|(_number, id)| InscriptionId::load(id.value())))
+        .collect(),
+    )
+  }
+
+  pub(crate) fn get_feed_inscriptions(&self, n: usize) -> Result<Vec<(u32, InscriptionId)>> {
+    Ok(
       self
         .database
         .begin_read()?
@@ -2497,17 +3239,207 @@ mod tests {
   }
 
   #[test]
-  fn inscriptions_below_first_inscription_height_are_skipped() {
-    let inscription = inscription("text/plain;charset=utf-8", "hello");
-    let template = TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription.to_witness())],
-      ..default()
+  fn index_from_blk_files() {
+    let inscribe = |block: &Block| Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: block.txdata[0].txid(),
+          vout: 0,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: inscription("text/plain", "hello").to_witness(),
+      }],
+      output: vec![TxOut {
+        value: 50 * COIN_VALUE,
+        script_pubkey: ScriptBuf::new(),
+      }],
     };
 
-    {
-      let context = Context::builder().build();
-      context.mine_blocks(1);
-      let txid = context.core.broadcast_tx(template.clone());
+    let mut blocks = vec![Chain::Regtest.genesis_block()];
+
+    for time in 1..4 {
+      let parent = blocks.last().unwrap();
+
+      let transactions = if time == 1 {
+        Vec::new()
+      } else {
+        vec![inscribe(parent)]
+      };
+
+      blocks.push(block_files::block(parent.block_hash(), time, transactions));
+    }
+
+    let first = InscriptionId {
+      txid: blocks[2].txdata[1].txid(),
+      index: 0,
+    };
+
+    let second = InscriptionId {
+      txid: blocks[3].txdata[1].txid(),
+      index: 0,
+    };
+
+    let truncated = |block: &Block| {
+      let mut block = block.clone();
+      block.txdata.truncate(1);
+      block
+    };
+
+    // the block source is missing the first inscription, so indexing it
+    // shows that its block was read from the blk files
+    let block_source = Arc::new(block_source::MemoryBlockSource::default());
+
+    for (height, block) in blocks.iter().enumerate() {
+      block_source.push_block(
+        height.try_into().unwrap(),
+        if height == 2 {
+          truncated(block)
+        } else {
+          block.clone()
+        },
+      );
+    }
+
+    // the blk files are missing the second inscription, so its block doesn't
+    // match its header, and indexing it shows that it was fetched from the
+    // block source instead
+    let bitcoin_data_dir = tempfile::TempDir::new().unwrap();
+
+    block_files::write(
+      &bitcoin_data_dir.path().join("regtest").join("blocks"),
+      Network::Regtest,
+      [1, 2, 3, 4, 5, 6, 7, 8],
+      &[
+        blocks[0].clone(),
+        blocks[1].clone(),
+        blocks[2].clone(),
+        truncated(&blocks[3]),
+      ],
+    );
+
+    let tempdir = tempfile::TempDir::new().unwrap();
+
+    let settings = Settings::from_options(
+      Options::try_parse_from([
+        "ord",
+        "--regtest",
+        "--datadir",
+        tempdir.path().to_str().unwrap(),
+        "--index-from-blk-files",
+        "--bitcoin-data-dir",
+        bitcoin_data_dir.path().to_str().unwrap(),
+      ])
+      .unwrap(),
+    )
+    .or_defaults()
+    .unwrap();
+
+    let index = Index::open_with_block_source(&settings, block_source).unwrap();
+
+    index.update().unwrap();
+
+    assert_eq!(index.block_count().unwrap(), 4);
+
+    assert_eq!(
+      index.block_hash(None).unwrap(),
+      Some(blocks[3].block_hash()),
+    );
+
+    assert!(index
+      .get_inscription_satpoint_by_id(first)
+      .unwrap()
+      .is_some());
+    assert!(index
+      .get_inscription_satpoint_by_id(second)
+      .unwrap()
+      .is_some());
+  }
+
+  #[test]
+  fn index_from_block_source() {
+    let core = mockcore::builder().network(Network::Regtest).build();
+
+    core.mine_blocks(1);
+
+    let txid = core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    core.mine_blocks(2);
+
+    let blocks = {
+      let state = core.state();
+      state
+        .hashes
+        .iter()
+        .map(|hash| state.blocks[hash].clone())
+        .collect::<Vec<Block>>()
+    };
+
+    drop(core);
+
+    let block_source = Arc::new(block_source::MemoryBlockSource::default());
+
+    for (height, block) in blocks.iter().take(3).enumerate() {
+      block_source.push_block(height.try_into().unwrap(), block.clone());
+    }
+
+    let tempdir = tempfile::TempDir::new().unwrap();
+
+    let settings = Settings::from_options(
+      Options::try_parse_from([
+        "ord",
+        "--regtest",
+        "--datadir",
+        tempdir.path().to_str().unwrap(),
+      ])
+      .unwrap(),
+    )
+    .or_defaults()
+    .unwrap();
+
+    let index = Index::open_with_block_source(&settings, block_source.clone()).unwrap();
+
+    index.update().unwrap();
+
+    assert_eq!(index.block_count().unwrap(), 3);
+
+    index.assert_inscription_location(
+      InscriptionId { txid, index: 0 },
+      SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      Some(50 * COIN_VALUE),
+    );
+
+    block_source.push_block(3, blocks[3].clone());
+
+    index.update().unwrap();
+
+    assert_eq!(index.block_count().unwrap(), 4);
+    assert_eq!(
+      index.block_hash(None).unwrap(),
+      Some(blocks[3].block_hash())
+    );
+  }
+
+  #[test]
+  fn inscriptions_below_first_inscription_height_are_skipped() {
+    let inscription = inscription("text/plain;charset=utf-8", "hello");
+    let template = TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription.to_witness())],
+      ..default()
+    };
+
+    {
+      let context = Context::builder().build();
+      context.mine_blocks(1);
+      let txid = context.core.broadcast_tx(template.clone());
       let inscription_id = InscriptionId { txid, index: 0 };
       context.mine_blocks(1);
 
@@ -3583,6 +4515,33 @@ mod tests {
       format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with a newer, incompatible version of ord, consider updating ord: index schema {}, ord schema {SCHEMA_VERSION}", path.display(), u64::MAX));
   }
 
+  #[test]
+  fn schema_30_indexes_must_be_rebuilt() {
+    let tempdir = {
+      let context = Context::builder().build();
+
+      let wtx = context.index.database.begin_write().unwrap();
+
+      wtx
+        .open_table(STATISTIC_TO_COUNT)
+        .unwrap()
+        .insert(&Statistic::Schema.key(), &30)
+        .unwrap();
+
+      wtx.commit().unwrap();
+
+      context.tempdir
+    };
+
+    let path = tempdir.path().to_owned();
+
+    let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+    assert_eq!(
+      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with an older, incompatible version of ord, consider deleting and rebuilding the index: index schema 30, ord schema {SCHEMA_VERSION}", path.display()));
+  }
+
   #[test]
   fn inscriptions_on_output() {
     for context in Context::configurations() {
@@ -4720,6 +5679,108 @@ mod tests {
     }
   }
 
+  #[test]
+  fn reorgs_are_logged_and_sent() {
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let mut context = Context::builder().event_sender(event_sender).build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    for _ in 0..3 {
+      context.mine_blocks(1);
+    }
+
+    let orphaned = context.index.block_hash(Some(3)).unwrap().unwrap();
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    let (reorgs, total, next) = context.index.get_reorgs(None, 100).unwrap();
+
+    assert_eq!(reorgs.len(), 1);
+    assert_eq!(total, 1);
+    assert_eq!(next, None);
+
+    let (sequence_number, entry) = &reorgs[0];
+
+    assert_eq!(*sequence_number, 0);
+    assert_eq!(entry.height, 4);
+    assert_eq!(entry.depth, 2);
+    assert_eq!(entry.end_height, 3);
+    assert_eq!(entry.orphaned_blocks, [(3, orphaned)]);
+    assert_eq!(entry.start_height, 3);
+
+    assert_eq!(
+      event_receiver.blocking_recv().unwrap(),
+      Event::Reorg {
+        block_height: entry.height,
+        depth: entry.depth,
+        end_height: entry.end_height,
+        orphaned_blocks: entry.orphaned_blocks.clone(),
+        start_height: entry.start_height,
+      }
+    );
+
+    assert_eq!(context.index.block_count().unwrap(), 5);
+    assert_ne!(
+      context.index.block_hash(Some(3)).unwrap().unwrap(),
+      orphaned
+    );
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    assert_eq!(
+      context
+        .index
+        .get_reorgs(None, 100)
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|(sequence_number, _)| sequence_number)
+        .collect::<Vec<u32>>(),
+      [0, 1],
+    );
+
+    let (first, total, next) = context.index.get_reorgs(None, 1).unwrap();
+
+    assert_eq!(first[0].0, 0);
+    assert_eq!(total, 2);
+    assert_eq!(next, Some(0));
+
+    let (second, _, next) = context.index.get_reorgs(Some(0), 1).unwrap();
+
+    assert_eq!(second[0].0, 1);
+    assert_eq!(next, None);
+  }
+
+  #[test]
+  fn reorg_log_lists_every_rolled_back_block() {
+    let mut context = Context::builder().build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    for _ in 0..12 {
+      context.mine_blocks(1);
+    }
+
+    let hashes = (0..13)
+      .map(|height| context.index.block_hash(Some(height)).unwrap().unwrap())
+      .collect::<Vec<BlockHash>>();
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    let (_, entry) = context.index.get_reorgs(None, 100).unwrap().0.remove(0);
+
+    assert!(entry.start_height < entry.height - entry.depth + 1);
+    assert_eq!(entry.end_height, 12);
+    assert_eq!(
+      entry.orphaned_blocks,
+      (entry.start_height..=entry.end_height)
+        .map(|height| (height, hashes[usize::try_from(height).unwrap()]))
+        .collect::<Vec<(u32, BlockHash)>>(),
+    );
+  }
+
   #[test]
   fn recover_from_3_block_deep_and_consecutive_reorg() {
     for mut context in Context::configurations() {
@@ -6521,6 +7582,115 @@ mod tests {
     }
   }
 
+  #[test]
+  fn events_are_logged() {
+    let mut context = Context::builder().arg("--index-events").build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let Events::Page(events) = context.index.get_events(None, 100).unwrap() else {
+      panic!("event log should be available");
+    };
+
+    assert_eq!(events.len(), 1);
+
+    let (id, event) = events[0].clone();
+
+    assert_eq!(
+      id,
+      EventId {
+        height: 2,
+        index: 0,
+        block_hash: context.index.block_hash(Some(2)).unwrap().unwrap(),
+      }
+    );
+
+    assert!(matches!(
+      event,
+      Event::InscriptionCreated { inscription_id, .. }
+        if inscription_id == InscriptionId { txid, index: 0 }
+    ));
+
+    assert_eq!(
+      context.index.get_events(Some(id), 100).unwrap(),
+      Events::Page(Vec::new()),
+    );
+
+    assert_eq!(
+      context.index.get_event_tip().unwrap(),
+      Some(EventId::after_block(2, id.block_hash)),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_events(
+          Some(EventId {
+            block_hash: BlockHash::all_zeros(),
+            ..id
+          }),
+          100
+        )
+        .unwrap(),
+      Events::Unavailable,
+    );
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    let Events::Reorged(entry) = context.index.get_events(Some(id), 100).unwrap() else {
+      panic!("reorg should be detected");
+    };
+
+    assert!(entry.orphaned_blocks.contains(&(2, id.block_hash)));
+  }
+
+  #[test]
+  fn event_log_only_holds_recent_blocks() {
+    let context = Context::builder().arg("--index-events").build();
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let Events::Page(events) = context.index.get_events(None, 100).unwrap() else {
+      panic!("event log should be available");
+    };
+
+    let id = events[0].0;
+
+    context.mine_blocks(EVENT_LOG_BLOCKS.into());
+
+    assert_eq!(
+      context.index.get_events(None, 100).unwrap(),
+      Events::Unavailable
+    );
+    assert_eq!(
+      context.index.get_events(Some(id), 100).unwrap(),
+      Events::Unavailable
+    );
+    assert_eq!(
+      context
+        .index
+        .get_events(context.index.get_event_tip().unwrap(), 100)
+        .unwrap(),
+      Events::Page(Vec::new()),
+    );
+  }
+
   #[test]
   fn inscription_event_sender_channel() {
     let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
@@ -6598,37 +7768,96 @@ mod tests {
   }
 
   #[test]
-  fn rune_event_sender_channel() {
-    const RUNE: u128 = 99246114928149462;
+  fn events_are_not_logged_without_event_index() {
+    let context = Context::builder().build();
 
-    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
-    let context = Context::builder()
-      .arg("--index-runes")
-      .event_sender(event_sender)
-      .build();
+    context.mine_blocks(1);
 
-    let (txid0, id) = context.etch(
-      Runestone {
-        etching: Some(Etching {
-          rune: Some(Rune(RUNE)),
-          terms: Some(Terms {
-            amount: Some(1000),
-            cap: Some(100),
-            ..default()
-          }),
-          ..default()
-        }),
-        ..default()
-      },
-      1,
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert!(!context.index.has_event_index());
+    assert_eq!(
+      context.index.get_events(None, 100).unwrap(),
+      Events::Page(Vec::new()),
     );
+  }
 
-    context.assert_runes(
-      [(
-        id,
-        RuneEntry {
-          block: id.block,
-          etching: txid0,
+  #[test]
+  fn event_sender_waits_for_full_channel() {
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1);
+
+    let receiver = thread::spawn(move || {
+      let mut events = Vec::new();
+      while let Some(event) = event_receiver.blocking_recv() {
+        events.push(event);
+      }
+      events
+    });
+
+    let context = Context::builder().event_sender(event_sender).build();
+
+    context.mine_blocks(2);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (1, 0, 0, inscription("text/plain", "foo").to_witness()),
+        (2, 0, 0, inscription("text/plain", "bar").to_witness()),
+      ],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    drop(context);
+
+    assert_eq!(
+      receiver
+        .join()
+        .unwrap()
+        .into_iter()
+        .filter(|event| matches!(event, Event::InscriptionCreated { .. }))
+        .count(),
+      2,
+    );
+  }
+
+  #[test]
+  fn rune_event_sender_channel() {
+    const RUNE: u128 = 99246114928149462;
+
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let context = Context::builder()
+      .arg("--index-runes")
+      .event_sender(event_sender)
+      .build();
+
+    let (txid0, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    context.assert_runes(
+      [(
+        id,
+        RuneEntry {
+          block: id.block,
+          etching: txid0,
           spaced_rune: SpacedRune {
             rune: Rune(RUNE),
             spacers: 0,
@@ -6833,4 +8062,915 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn rune_events_record_owner_script_pubkeys() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (txid0, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          terms: Some(Terms {
+            amount: Some(10),
+            cap: Some(10),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let events = |is_spent| {
+      let height = context.index.block_count().unwrap() - 1;
+      context
+        .index
+        .get_runes_events_by_height(height.into(), is_spent)
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect::<Vec<RuneEventEntry>>()
+    };
+
+    let script_pubkey = |txid: Txid, vout: usize| {
+      context.core.tx_by_id(txid).output[vout]
+        .script_pubkey
+        .clone()
+    };
+
+    let txid1 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          mint: Some(id),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let mint = events(false)
+      .into_iter()
+      .find(|event| event.event == RuneEvent::Mint)
+      .unwrap();
+
+    assert!(mint.script_pubkey.is_empty());
+    assert_eq!(mint.txid, txid1);
+    assert_eq!(mint.vout, -1);
+    assert_eq!(mint.amount, 10);
+
+    let transfer = events(false)
+      .into_iter()
+      .find(|event| event.event == RuneEvent::Transfer)
+      .unwrap();
+
+    assert_eq!(transfer.script_pubkey, script_pubkey(txid1, 0));
+    assert_eq!(transfer.amount, 10);
+
+    let txid2 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let spent = events(true);
+
+    assert_eq!(spent.len(), 1);
+    assert_eq!(spent[0].event, RuneEvent::Spent);
+    assert_eq!(spent[0].txid, txid0);
+    assert_eq!(spent[0].vout, 0);
+    assert_eq!(spent[0].source, txid2);
+    assert_eq!(spent[0].amount, 1000);
+    assert_eq!(spent[0].script_pubkey, script_pubkey(txid0, 0));
+
+    let transfer = events(false);
+
+    assert_eq!(transfer.len(), 1);
+    assert_eq!(
+      transfer[0].event,
+      crate::indexer::rune_event::RuneEvent::Transfer
+    );
+    assert_eq!(transfer[0].script_pubkey, script_pubkey(txid2, 0));
+
+    let txid3 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        usize::try_from(context.index.block_count().unwrap()).unwrap() - 1,
+        1,
+        0,
+        Witness::new(),
+      )],
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 1000,
+            output: 3,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let burn = events(false)
+      .into_iter()
+      .find(|event| event.event == RuneEvent::Burn)
+      .unwrap();
+
+    assert_eq!(burn.amount, 1000);
+    assert_eq!(burn.txid, txid2);
+    assert_eq!(burn.source, txid3);
+    assert_eq!(burn.vout, 0);
+    assert_eq!(burn.script_pubkey, script_pubkey(txid2, 0));
+  }
+
+  #[test]
+  fn rune_burns_are_attributed_to_spent_outpoints_in_input_order() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (_txid0, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          terms: Some(Terms {
+            amount: Some(10),
+            cap: Some(10),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let txid1 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      outputs: 2,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 400,
+            output: 1,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let height = context.index.block_count().unwrap() - 1;
+
+    let txid2 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (height.try_into().unwrap(), 1, 0, Witness::new()),
+        (height.try_into().unwrap(), 1, 1, Witness::new()),
+      ],
+      outputs: 1,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 1010,
+            output: 1,
+          }],
+          mint: Some(id),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let burns = context
+      .index
+      .get_runes_events_by_height((height + 1).into(), false)
+      .unwrap()
+      .0
+      .into_iter()
+      .map(|(_, event)| event)
+      .filter(|event| event.event == RuneEvent::Burn)
+      .map(|event| (event.txid, event.vout, event.script_pubkey, event.amount))
+      .collect::<Vec<(Txid, i32, ScriptBuf, u128)>>();
+
+    let transaction = context.core.tx_by_id(txid1);
+
+    assert_eq!(
+      burns,
+      [
+        (txid1, 0, transaction.output[0].script_pubkey.clone(), 600),
+        (txid1, 1, transaction.output[1].script_pubkey.clone(), 400),
+        (txid2, -1, ScriptBuf::new(), 10),
+      ],
+    );
+
+    let audit = context.index.get_rune_audit(id).unwrap().unwrap();
+
+    assert_eq!(audit.burned, "1010");
+    assert_eq!(audit.event_burned, "1010");
+  }
+
+  #[test]
+  fn rune_holder_balances_saturate() {
+    let context = Context::builder().arg("--index-runes").build();
+
+    let id = RuneId { block: 1, tx: 1 };
+
+    let entry = |event, amount| RuneEventEntry {
+      seq_no: 0,
+      rune_id: id,
+      network: Network::Regtest,
+      event,
+      source: Txid::all_zeros(),
+      txid: Txid::all_zeros(),
+      height: 1,
+      script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+      amount,
+      timestamp: 0,
+      vout: 0,
+    };
+
+    let wtx = context.index.begin_write().unwrap();
+
+    {
+      let mut rune_id_to_holder_balance = wtx.open_table(RUNE_ID_TO_HOLDER_BALANCE).unwrap();
+      let mut rune_id_to_holder_count = wtx.open_table(RUNE_ID_TO_HOLDER_COUNT).unwrap();
+
+      for (event, amount) in [
+        (RuneEvent::Transfer, 10),
+        (RuneEvent::Spent, 20),
+        (RuneEvent::Transfer, u128::MAX),
+        (RuneEvent::Transfer, 1),
+      ] {
+        Index::update_rune_holder(
+          &mut rune_id_to_holder_balance,
+          &mut rune_id_to_holder_count,
+          &entry(event, amount),
+        )
+        .unwrap();
+      }
+    }
+
+    wtx.commit().unwrap();
+
+    assert_eq!(context.index.get_rune_holder_count(id).unwrap(), 1);
+    assert_eq!(
+      context.index.get_rune_holders(id, None, 100).unwrap().0,
+      [(ScriptBuf::from_bytes(vec![0x51]), u128::MAX)],
+    );
+  }
+
+  #[test]
+  fn rune_holders_follow_balance_changes() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (txid0, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let holders = || {
+      let (holders, next) = context.index.get_rune_holders(id, None, 10).unwrap();
+      assert_eq!(next, None);
+      assert_eq!(
+        context.index.get_rune_holder_count(id).unwrap(),
+        u64::try_from(holders.len()).unwrap()
+      );
+      holders.into_iter().collect::<BTreeMap<ScriptBuf, u128>>()
+    };
+
+    assert_eq!(
+      holders(),
+      [(
+        context.core.tx_by_id(txid0).output[0].script_pubkey.clone(),
+        1000
+      )]
+      .into()
+    );
+
+    let txid1 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      outputs: 2,
+      p2tr: true,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 400,
+            output: 0,
+          }],
+          pointer: Some(1),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let tx1 = context.core.tx_by_id(txid1);
+
+    assert_eq!(
+      holders(),
+      [
+        (tx1.output[0].script_pubkey.clone(), 400),
+        (tx1.output[1].script_pubkey.clone(), 600),
+      ]
+      .into()
+    );
+
+    let (page, next) = context.index.get_rune_holders(id, None, 1).unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(next.as_ref(), Some(&page[0].0));
+
+    let (rest, next) = context
+      .index
+      .get_rune_holders(id, next.as_deref(), 1)
+      .unwrap();
+    assert_eq!(rest.len(), 1);
+    assert_ne!(rest[0].0, page[0].0);
+    assert_eq!(next, None);
+
+    let height = usize::try_from(context.index.block_count().unwrap()).unwrap() - 1;
+
+    let txid2 = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (height, 1, 0, Witness::new()),
+        (height, 1, 1, Witness::new()),
+      ],
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      holders(),
+      [(
+        context.core.tx_by_id(txid2).output[0].script_pubkey.clone(),
+        1000
+      )]
+      .into()
+    );
+  }
+
+  #[test]
+  fn rune_events_are_indexed_by_script_pubkey() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (_txid, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let script_pubkey = context.core.tx_by_id(txid).output[0].script_pubkey.clone();
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        usize::try_from(context.index.block_count().unwrap()).unwrap() - 1,
+        1,
+        0,
+        Witness::new(),
+      )],
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let events = |cursor, limit, rune_id, event| {
+      context
+        .index
+        .get_runes_events_by_script_pubkey(&script_pubkey, cursor, limit, rune_id, event)
+        .unwrap()
+    };
+
+    let (all, next) = events(None, 100, None, None);
+
+    assert_eq!(next, None);
+    assert_eq!(
+      all
+        .iter()
+        .map(|(_, event)| (event.event, event.txid))
+        .collect::<Vec<(RuneEvent, Txid)>>(),
+      [(RuneEvent::Transfer, txid), (RuneEvent::Spent, txid)],
+    );
+
+    let (first, next) = events(None, 1, None, None);
+
+    assert_eq!(first, all[..1]);
+    assert_eq!(next, Some(all[0].0));
+
+    let (second, next) = events(Some(all[0].0), 1, None, None);
+
+    assert_eq!(second, all[1..]);
+    assert_eq!(next, None);
+
+    assert_eq!(events(None, 100, None, Some(RuneEvent::Spent)).0, all[1..]);
+    assert_eq!(events(None, 100, Some(id), None).0, all);
+    assert!(events(None, 100, Some(RuneId { block: 1, tx: 1 }), None)
+      .0
+      .is_empty());
+  }
+
+  #[test]
+  fn schema_33_rune_event_script_pubkeys_are_migrated() {
+    const RUNE: u128 = 99246114928149462;
+
+    let (tempdir, script_pubkey, events) = {
+      let context = Context::builder().arg("--index-runes").build();
+
+      let (txid, _id) = context.etch(
+        Runestone {
+          etching: Some(Etching {
+            rune: Some(Rune(RUNE)),
+            premine: Some(1000),
+            ..default()
+          }),
+          ..default()
+        },
+        1,
+      );
+
+      let script_pubkey = context.core.tx_by_id(txid).output[0].script_pubkey.clone();
+
+      let (events, _) = context
+        .index
+        .get_runes_events_by_script_pubkey(&script_pubkey, None, 100, None, None)
+        .unwrap();
+
+      assert!(!events.is_empty());
+
+      let wtx = context.index.database.begin_write().unwrap();
+
+      wtx.delete_table(SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT).unwrap();
+
+      {
+        let mut legacy = wtx
+          .open_multimap_table(
+            MultimapTableDefinition::<&ScriptPubkeyHash, BlockIdValue>::new(
+              "SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT",
+            ),
+          )
+          .unwrap();
+
+        for (id, _) in &events {
+          legacy
+            .insert(&script_pubkey_hash(&script_pubkey), id.store())
+            .unwrap();
+        }
+      }
+
+      wtx
+        .open_table(STATISTIC_TO_COUNT)
+        .unwrap()
+        .insert(&Statistic::Schema.key(), &33)
+        .unwrap();
+
+      wtx.commit().unwrap();
+
+      (context.tempdir, script_pubkey, events)
+    };
+
+    let context = Context::builder()
+      .arg("--index-runes")
+      .tempdir(tempdir)
+      .build();
+
+    assert_eq!(context.index.statistic(Statistic::Schema), SCHEMA_VERSION);
+
+    assert_eq!(
+      context
+        .index
+        .get_runes_events_by_script_pubkey(&script_pubkey, None, 100, None, None)
+        .unwrap()
+        .0,
+      events,
+    );
+  }
+
+  #[test]
+  fn schema_36_rune_event_totals_are_migrated() {
+    const RUNE: u128 = 99246114928149462;
+
+    let (tempdir, id, audit) = {
+      let context = Context::builder().arg("--index-runes").build();
+
+      let (_txid, id) = context.etch(
+        Runestone {
+          etching: Some(Etching {
+            rune: Some(Rune(RUNE)),
+            premine: Some(1000),
+            ..default()
+          }),
+          ..default()
+        },
+        1,
+      );
+
+      let audit = context.index.get_rune_audit(id).unwrap().unwrap();
+
+      assert!(audit.events > 0);
+      assert!(audit.balanced);
+
+      let wtx = context.index.database.begin_write().unwrap();
+
+      wtx.delete_table(RUNE_ID_TO_EVENT_TOTALS).unwrap();
+
+      wtx
+        .open_table(STATISTIC_TO_COUNT)
+        .unwrap()
+        .insert(&Statistic::Schema.key(), &36)
+        .unwrap();
+
+      wtx.commit().unwrap();
+
+      (context.tempdir, id, audit)
+    };
+
+    let context = Context::builder()
+      .arg("--index-runes")
+      .tempdir(tempdir)
+      .build();
+
+    assert_eq!(context.index.statistic(Statistic::Schema), SCHEMA_VERSION);
+
+    assert_eq!(context.index.get_rune_audit(id).unwrap().unwrap(), audit);
+  }
+
+  #[test]
+  fn rune_events_are_indexed_by_rune_id() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (_txid, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let (_txid, other) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE + 1)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let mut heights = Vec::new();
+
+    for input in [2, 3] {
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(input, 0, 0, Witness::new())],
+        op_return: Some(
+          Runestone {
+            mint: Some(id),
+            ..default()
+          }
+          .encipher(),
+        ),
+        ..default()
+      });
+
+      context.mine_blocks(1);
+
+      heights.push(u64::from(context.index.block_count().unwrap() - 1));
+    }
+
+    let events = |from_height, to_height, cursor, limit, event| {
+      context
+        .index
+        .get_rune_events(id, from_height, to_height, cursor, limit, event)
+        .unwrap()
+    };
+
+    let (all, next) = events(None, None, None, 100, None);
+
+    assert_eq!(next, None);
+    assert!(all.iter().all(|(_, event)| event.rune_id == id));
+    assert_eq!(
+      all
+        .iter()
+        .map(|(block_id, event)| (block_id.block, event.event))
+        .collect::<Vec<(u64, RuneEvent)>>(),
+      [
+        (heights[0], RuneEvent::Mint),
+        (heights[0], RuneEvent::Transfer),
+        (heights[1], RuneEvent::Mint),
+        (heights[1], RuneEvent::Transfer),
+      ],
+    );
+
+    assert_eq!(
+      events(None, None, None, 100, Some(RuneEvent::Mint)).0,
+      [all[0].clone(), all[2].clone()],
+    );
+
+    assert_eq!(events(Some(heights[1]), None, None, 100, None).0, all[2..]);
+    assert_eq!(events(None, Some(heights[0]), None, 100, None).0, all[..2]);
+
+    let (page, next) = events(None, None, None, 3, None);
+
+    assert_eq!(page, all[..3]);
+    assert_eq!(next, Some(all[2].0));
+    assert_eq!(events(None, None, next, 3, None), (all[3..].to_vec(), None));
+
+    assert_eq!(
+      context
+        .index
+        .get_rune_events(other, None, None, None, 100, None)
+        .unwrap()
+        .0
+        .len(),
+      1,
+    );
+  }
+
+  #[test]
+  fn address_index_tracks_unspent_outputs() {
+    let context = Context::builder().arg("--index-addresses").build();
+
+    context.mine_blocks(1);
+
+    let coinbase = context.core.tx(1, 0);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Witness::new())],
+      outputs: 2,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let transaction = context.core.tx_by_id(txid);
+    let script_pubkey = transaction.output[0].script_pubkey.clone();
+
+    assert_eq!(transaction.output[1].script_pubkey, script_pubkey);
+
+    assert!(context
+      .index
+      .get_address_outputs(&coinbase.output[0].script_pubkey, None, 100)
+      .unwrap()
+      .0
+      .is_empty());
+
+    let outputs = [
+      (OutPoint { txid, vout: 0 }, transaction.output[0].clone()),
+      (OutPoint { txid, vout: 1 }, transaction.output[1].clone()),
+    ];
+
+    assert_eq!(
+      context
+        .index
+        .get_address_outputs(&script_pubkey, None, 100)
+        .unwrap(),
+      (outputs.to_vec(), None),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_address_outputs(&script_pubkey, None, 1)
+        .unwrap(),
+      (outputs[..1].to_vec(), Some(outputs[0].0)),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_address_outputs(&script_pubkey, Some(outputs[0].0), 1)
+        .unwrap(),
+      (outputs[1..].to_vec(), None),
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      p2tr: true,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context
+        .index
+        .get_address_outputs(&script_pubkey, None, 100)
+        .unwrap(),
+      (outputs[1..].to_vec(), None),
+    );
+  }
+
+  #[test]
+  fn address_index_requires_indexing_from_genesis() {
+    assert_eq!(
+      Context::builder()
+        .chain(Chain::Mainnet)
+        .arg("--index-addresses")
+        .try_build()
+        .err()
+        .unwrap()
+        .to_string(),
+      "--index-addresses requires indexing from genesis, but blocks below height 767430 would not be indexed, consider passing --first-inscription-height=0",
+    );
+
+    Context::builder()
+      .chain(Chain::Mainnet)
+      .arg("--index-addresses")
+      .arg("--first-inscription-height=0")
+      .build();
+  }
+
+  #[test]
+  fn inscription_transfers_match_transferred_events() {
+    for index_sats in [false, true] {
+      let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+      let mut builder = Context::builder().event_sender(event_sender);
+      if index_sats {
+        builder = builder.arg("--index-sats");
+      }
+      let context = builder.build();
+
+      context.mine_blocks(1);
+
+      let create_txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      });
+
+      context.mine_blocks(1);
+
+      let transfer_txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 1, 0, Default::default())],
+        outputs: 2,
+        ..default()
+      });
+
+      context.mine_blocks(1);
+
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(3, 1, 0, Default::default())],
+        fee: 25 * COIN_VALUE,
+        ..default()
+      });
+
+      let coinbase_txid = context.mine_blocks(1)[0].txdata[0].txid();
+
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(4, 0, 0, Default::default())],
+        fee: 75 * COIN_VALUE,
+        ..default()
+      });
+
+      context.mine_blocks_with_subsidy(1, 0);
+
+      let mut events = Vec::new();
+      while let Ok(event) = event_receiver.try_recv() {
+        if let Event::InscriptionTransferred {
+          block_height,
+          inscription_id,
+          new_location,
+          ..
+        } = event
+        {
+          events.push((block_height, inscription_id, new_location));
+        }
+      }
+
+      assert_eq!(events.len(), 3);
+      assert_eq!(events[0].2.outpoint.txid, transfer_txid);
+      assert_eq!(events[1].2.outpoint.txid, coinbase_txid);
+      assert_eq!(events[2].2.outpoint, OutPoint::null());
+
+      let (mut transfers, total, more) = context
+        .index
+        .get_inscriptions_transfers_paginated(100, 0)
+        .unwrap();
+
+      transfers.sort_by_key(|(sequence_number, _)| *sequence_number);
+
+      assert_eq!(total, 3);
+      assert!(!more);
+      assert_eq!(transfers.len(), events.len());
+
+      for (i, ((sequence_number, transfer), (block_height, inscription_id, new_location))) in
+        transfers.into_iter().zip(events).enumerate()
+      {
+        assert_eq!(sequence_number, u32::try_from(i).unwrap());
+        assert_eq!(
+          inscription_id,
+          InscriptionId {
+            txid: create_txid,
+            index: 0
+          }
+        );
+
+        let (to_script_pubkey, output_value) = if new_location.outpoint == OutPoint::null() {
+          (ScriptBuf::new(), 0)
+        } else {
+          let tx_out = context.core.tx_by_id(new_location.outpoint.txid).output
+            [new_location.outpoint.vout.into_usize()]
+          .clone();
+          (tx_out.script_pubkey, tx_out.value)
+        };
+
+        pretty_assert_eq!(
+          transfer,
+          InscriptionTransfer {
+            inscription_id,
+            network: Network::Regtest,
+            txid: new_location.outpoint.txid,
+            to_script_pubkey,
+            output_value,
+            height: block_height,
+            index: 0,
+            vout: new_location.outpoint.vout,
+          }
+        );
+      }
+    }
+  }
 }
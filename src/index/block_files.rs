@@ -0,0 +1,572 @@
+use {
+  super::*,
+  bitcoin::consensus::Decodable,
+  std::{
+    fs::File,
+    io::{BufReader, Read},
+  },
+};
+
+mod leveldb;
+
+const BLOCK_HAVE_DATA: u64 = 8;
+const BLOCK_HAVE_UNDO: u64 = 16;
+const HEADER_SIZE: u32 = 80;
+
+#[derive(Debug, Clone, Copy)]
+struct Location {
+  file: u32,
+  hash: BlockHash,
+  offset: u64,
+}
+
+/// Reads blocks directly from Bitcoin Core's `blocks/blk*.dat` files,
+/// undoing the XOR obfuscation keyed by `blocks/xor.dat`. Blocks are found
+/// with Core's block index in `blocks/index`, walking back from the tip, and
+/// are checked against their headers when read, so that a block which
+/// doesn't match can be fetched from the block source instead.
+pub(crate) struct BlockFiles {
+  chain: Vec<Location>,
+  dir: PathBuf,
+  key: [u8; 8],
+  magic: [u8; 4],
+  reader: Option<(u32, XorReader)>,
+}
+
+impl BlockFiles {
+  pub(crate) fn open(dir: &Path, network: Network, tip: BlockHash) -> Result<Self> {
+    let key = match fs::read(dir.join("xor.dat")) {
+      Ok(key) => key
+        .try_into()
+        .map_err(|key: Vec<u8>| anyhow!("xor.dat is {} bytes, expected 8", key.len()))?,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => [0; 8],
+      Err(err) => return Err(err.into()),
+    };
+
+    let index = dir.join("index");
+
+    let mut blocks = HashMap::new();
+
+    for (key, value) in leveldb::read(&index, b"b")? {
+      let hash = BlockHash::from_slice(&key[1..])
+        .with_context(|| format!("invalid block index key in `{}`", index.display()))?;
+
+      let entry = Self::decode_index_entry(&value)
+        .with_context(|| format!("invalid block index entry for block {hash}"))?;
+
+      blocks.insert(hash, entry);
+    }
+
+    let mut chain = Vec::new();
+    let mut hash = tip;
+
+    loop {
+      let (height, prev, location) = blocks
+        .get(&hash)
+        .with_context(|| format!("block {hash} not found in `{}`", index.display()))?;
+
+      let (file, offset) =
+        location.with_context(|| format!("block {hash} not stored in `{}`", dir.display()))?;
+
+      chain.push(Location { file, hash, offset });
+
+      if *height == 0 {
+        break;
+      }
+
+      hash = *prev;
+    }
+
+    chain.reverse();
+
+    Ok(Self {
+      chain,
+      dir: dir.into(),
+      key,
+      magic: network.magic().to_bytes(),
+      reader: None,
+    })
+  }
+
+  pub(crate) fn height(&self) -> u32 {
+    self.chain.len().try_into().unwrap()
+  }
+
+  pub(crate) fn get(&mut self, height: u32, header_only: bool) -> Result<Option<Block>> {
+    let Some(location) = self.chain.get(usize::try_from(height).unwrap()).copied() else {
+      return Ok(None);
+    };
+
+    let path = Self::path(&self.dir, location.file);
+
+    let reader = match &mut self.reader {
+      Some((file, reader)) if *file == location.file => reader,
+      reader => {
+        &mut reader
+          .insert((location.file, XorReader::open(&path, self.key)?))
+          .1
+      }
+    };
+
+    // each block is preceded by the network magic and its size
+    reader.seek(
+      location
+        .offset
+        .checked_sub(8)
+        .with_context(|| format!("block {height} has invalid position"))?,
+    )?;
+
+    let mut prefix = [0; 8];
+
+    ensure!(
+      reader.read_all(&mut prefix)? && prefix[..4] == self.magic,
+      "block {height} not found at its position in `{}`",
+      path.display(),
+    );
+
+    let mut buffer = vec![
+      0;
+      if header_only {
+        HEADER_SIZE as usize
+      } else {
+        u32::from_le_bytes(prefix[4..].try_into().unwrap()).try_into()?
+      }
+    ];
+
+    ensure!(
+      reader.read_all(&mut buffer)?,
+      "block {height} truncated in `{}`",
+      path.display(),
+    );
+
+    let block = if header_only {
+      Block {
+        header: Header::consensus_decode(&mut buffer.as_slice())?,
+        txdata: Vec::new(),
+      }
+    } else {
+      Block::consensus_decode(&mut buffer.as_slice())?
+    };
+
+    ensure!(
+      block.block_hash() == location.hash,
+      "block {height} in `{}` is not block {}",
+      path.display(),
+      location.hash,
+    );
+
+    ensure!(
+      header_only || (block.check_merkle_root() && block.check_witness_commitment()),
+      "block {height} in `{}` does not match its header",
+      path.display(),
+    );
+
+    Ok(Some(block))
+  }
+
+  // height, previous block hash, and file and position of a block index entry,
+  // which Core serializes as `CDiskBlockIndex`
+  #[allow(clippy::type_complexity)]
+  fn decode_index_entry(mut entry: &[u8]) -> Result<(u32, BlockHash, Option<(u32, u64)>)> {
+    let _version = Self::decode_varint(&mut entry)?;
+    let height = Self::decode_varint(&mut entry)?.try_into()?;
+    let status = Self::decode_varint(&mut entry)?;
+    let _transactions = Self::decode_varint(&mut entry)?;
+
+    let file = if status & (BLOCK_HAVE_DATA | BLOCK_HAVE_UNDO) != 0 {
+      Some(Self::decode_varint(&mut entry)?.try_into()?)
+    } else {
+      None
+    };
+
+    let offset = if status & BLOCK_HAVE_DATA != 0 {
+      Some(Self::decode_varint(&mut entry)?)
+    } else {
+      None
+    };
+
+    if status & BLOCK_HAVE_UNDO != 0 {
+      Self::decode_varint(&mut entry)?;
+    }
+
+    let header = Header::consensus_decode(&mut entry)?;
+
+    Ok((height, header.prev_blockhash, file.zip(offset)))
+  }
+
+  // Core's `VARINT`, which is big-endian, and unlike LEB128 has no redundant
+  // encodings
+  fn decode_varint(data: &mut &[u8]) -> Result<u64> {
+    let mut n = 0u64;
+
+    loop {
+      let (byte, rest) = data.split_first().context("varint is truncated")?;
+
+      *data = rest;
+
+      n = n.checked_mul(128).context("varint is too large")? | u64::from(byte & 0x7f);
+
+      if byte & 0x80 == 0 {
+        return Ok(n);
+      }
+
+      n = n.checked_add(1).context("varint is too large")?;
+    }
+  }
+
+  fn path(dir: &Path, file: u32) -> PathBuf {
+    dir.join(format!("blk{file:05}.dat"))
+  }
+}
+
+struct XorReader {
+  inner: BufReader<File>,
+  key: [u8; 8],
+  position: u64,
+}
+
+impl XorReader {
+  fn open(path: &Path, key: [u8; 8]) -> Result<Self> {
+    Ok(Self {
+      inner: BufReader::new(
+        File::open(path).with_context(|| format!("failed to open `{}`", path.display()))?,
+      ),
+      key,
+      position: 0,
+    })
+  }
+
+  // returns false if the file ends before `buffer` is filled
+  fn read_all(&mut self, buffer: &mut [u8]) -> Result<bool> {
+    match self.inner.read_exact(buffer) {
+      Ok(()) => {}
+      Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+      Err(err) => return Err(err.into()),
+    }
+
+    for (i, byte) in buffer.iter_mut().enumerate() {
+      *byte ^= self.key[usize::try_from((self.position + i as u64) % 8).unwrap()];
+    }
+
+    self.position += buffer.len() as u64;
+
+    Ok(true)
+  }
+
+  fn seek(&mut self, position: u64) -> Result {
+    self
+      .inner
+      .seek_relative(i64::try_from(position)? - i64::try_from(self.position)?)?;
+    self.position = position;
+    Ok(())
+  }
+}
+
+/// Block at `time` after `prev` containing `transactions`, with a valid
+/// merkle root, and a witness commitment if any of them have witnesses.
+#[cfg(test)]
+pub(crate) fn block(prev: BlockHash, time: u32, transactions: Vec<Transaction>) -> Block {
+  let mut block = Block {
+    header: Header {
+      version: bitcoin::block::Version::ONE,
+      prev_blockhash: prev,
+      merkle_root: TxMerkleNode::all_zeros(),
+      time,
+      bits: bitcoin::CompactTarget::from_consensus(0),
+      nonce: 0,
+    },
+    txdata: [Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: script::Builder::new().push_int(time.into()).into_script(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        value: 50 * COIN_VALUE,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    }]
+    .into_iter()
+    .chain(transactions)
+    .collect(),
+  };
+
+  if block.txdata[1..].iter().any(|transaction| {
+    transaction
+      .input
+      .iter()
+      .any(|txin| !txin.witness.is_empty())
+  }) {
+    let commitment = Block::compute_witness_commitment(&block.witness_root().unwrap(), &[0; 32]);
+
+    let mut data = [0; 36];
+    data[..4].copy_from_slice(&[0xaa, 0x21, 0xa9, 0xed]);
+    data[4..].copy_from_slice(commitment.as_byte_array());
+
+    let coinbase = &mut block.txdata[0];
+    coinbase.input[0].witness = Witness::from_slice(&[[0; 32]]);
+    coinbase.output.push(TxOut {
+      value: 0,
+      script_pubkey: script::Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .push_slice(data)
+        .into_script(),
+    });
+  }
+
+  block.header.merkle_root = block.compute_merkle_root().unwrap();
+
+  block
+}
+
+/// Writes `blocks`, which must form a tree rooted at a genesis block, to blk
+/// files in `dir`, along with a block index that locates them.
+#[cfg(test)]
+pub(crate) fn write(dir: &Path, network: Network, key: [u8; 8], blocks: &[Block]) {
+  use bitcoin::consensus::Encodable;
+
+  // Core's `VARINT`
+  fn put_varint(buffer: &mut Vec<u8>, mut n: u64) {
+    let mut bytes = vec![u8::try_from(n & 0x7f).unwrap()];
+
+    while n > 0x7f {
+      n = (n >> 7) - 1;
+      bytes.push(u8::try_from(n & 0x7f).unwrap() | 0x80);
+    }
+
+    buffer.extend(bytes.iter().rev());
+  }
+
+  fs::create_dir_all(dir).unwrap();
+
+  if key != [0; 8] {
+    fs::write(dir.join("xor.dat"), key).unwrap();
+  }
+
+  let mut heights = HashMap::from([(BlockHash::all_zeros(), -1)]);
+
+  while heights.len() <= blocks.len() {
+    for block in blocks {
+      if let Some(height) = heights.get(&block.header.prev_blockhash).copied() {
+        heights.insert(block.block_hash(), height + 1);
+      }
+    }
+  }
+
+  let mut entries = Vec::new();
+
+  // two blocks per file, so reads cross file boundaries
+  for (file, blocks) in blocks.chunks(2).enumerate() {
+    let mut data = Vec::new();
+
+    for block in blocks {
+      let mut serialized = Vec::new();
+      block.consensus_encode(&mut serialized).unwrap();
+      data.extend(network.magic().to_bytes());
+      data.extend(u32::try_from(serialized.len()).unwrap().to_le_bytes());
+
+      let height = heights[&block.block_hash()];
+
+      let mut entry = Vec::new();
+      put_varint(&mut entry, 260000);
+      put_varint(&mut entry, height.try_into().unwrap());
+      if height == 0 {
+        put_varint(&mut entry, 5 | BLOCK_HAVE_DATA);
+      } else {
+        put_varint(&mut entry, 5 | BLOCK_HAVE_DATA | BLOCK_HAVE_UNDO);
+      }
+      put_varint(&mut entry, block.txdata.len().try_into().unwrap());
+      put_varint(&mut entry, file.try_into().unwrap());
+      put_varint(&mut entry, data.len().try_into().unwrap());
+      if height > 0 {
+        put_varint(&mut entry, 1000);
+      }
+      block.header.consensus_encode(&mut entry).unwrap();
+
+      let mut key = vec![b'b'];
+      key.extend(block.block_hash().as_byte_array());
+
+      entries.push((key, Some(entry)));
+
+      data.extend(serialized);
+    }
+
+    data.extend([0; 64]);
+
+    for (i, byte) in data.iter_mut().enumerate() {
+      *byte ^= key[i % 8];
+    }
+
+    fs::write(BlockFiles::path(dir, file.try_into().unwrap()), data).unwrap();
+  }
+
+  leveldb::write(&dir.join("index"), &entries);
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, tempfile::TempDir};
+
+  const KEY: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+
+  // genesis block followed by `n` blocks
+  fn chain(n: u32) -> Vec<Block> {
+    let mut blocks = vec![Chain::Regtest.genesis_block()];
+
+    for time in 1..=n {
+      blocks.push(block(blocks.last().unwrap().block_hash(), time, Vec::new()));
+    }
+
+    blocks
+  }
+
+  fn case(key: [u8; 8]) {
+    let mut blocks = chain(3);
+
+    let stale = blocks.pop().unwrap();
+
+    blocks.push(block(blocks[2].block_hash(), 4, Vec::new()));
+    blocks.push(block(blocks[3].block_hash(), 5, Vec::new()));
+
+    let tempdir = TempDir::new().unwrap();
+
+    // ordering blocks by hash shuffles the chain, like blocks received out of
+    // order, and puts the stale block among them
+    let mut written = blocks
+      .iter()
+      .cloned()
+      .chain([stale])
+      .collect::<Vec<Block>>();
+
+    written.sort_by_key(Block::block_hash);
+
+    write(tempdir.path(), Network::Regtest, key, &written);
+
+    let mut block_files = BlockFiles::open(
+      tempdir.path(),
+      Network::Regtest,
+      blocks.last().unwrap().block_hash(),
+    )
+    .unwrap();
+
+    assert_eq!(block_files.height(), 5);
+
+    for (height, block) in blocks.iter().enumerate() {
+      let height = height.try_into().unwrap();
+
+      assert_eq!(block_files.get(height, false).unwrap().unwrap(), *block);
+
+      let header = block_files.get(height, true).unwrap().unwrap();
+      assert_eq!(header.block_hash(), block.block_hash());
+      assert!(header.txdata.is_empty());
+    }
+
+    assert_eq!(block_files.get(5, false).unwrap(), None);
+  }
+
+  #[test]
+  fn read_blocks() {
+    case([0; 8]);
+  }
+
+  #[test]
+  fn read_obfuscated_blocks() {
+    case(KEY);
+  }
+
+  #[test]
+  fn missing_tip_is_an_error() {
+    let mut blocks = chain(2);
+
+    let tip = blocks.pop().unwrap();
+
+    let tempdir = TempDir::new().unwrap();
+
+    write(tempdir.path(), Network::Regtest, KEY, &blocks);
+
+    assert_eq!(
+      BlockFiles::open(tempdir.path(), Network::Regtest, tip.block_hash())
+        .err()
+        .unwrap()
+        .to_string(),
+      format!(
+        "block {} not found in `{}`",
+        tip.block_hash(),
+        tempdir.path().join("index").display()
+      ),
+    );
+  }
+
+  #[test]
+  fn wrong_key_is_an_error() {
+    let blocks = chain(1);
+
+    let tempdir = TempDir::new().unwrap();
+
+    write(tempdir.path(), Network::Regtest, KEY, &blocks);
+
+    fs::write(tempdir.path().join("xor.dat"), [0; 8]).unwrap();
+
+    let mut block_files =
+      BlockFiles::open(tempdir.path(), Network::Regtest, blocks[1].block_hash()).unwrap();
+
+    assert_eq!(
+      block_files.get(1, false).unwrap_err().to_string(),
+      format!(
+        "block 1 not found at its position in `{}`",
+        BlockFiles::path(tempdir.path(), 0).display()
+      ),
+    );
+  }
+
+  #[test]
+  fn blocks_that_do_not_match_their_header_are_an_error() {
+    let transaction = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::from_slice(&[[1; 32]]),
+      }],
+      output: Vec::new(),
+    };
+
+    let mut blocks = chain(0);
+
+    blocks.push(block(blocks[0].block_hash(), 1, vec![transaction.clone()]));
+    blocks.push(block(blocks[1].block_hash(), 2, vec![transaction.clone()]));
+
+    // drop a transaction
+    blocks[1].txdata.truncate(1);
+
+    // change a witness, which only the witness commitment covers
+    blocks[2].txdata[1].input[0].witness = Witness::from_slice(&[[2; 32]]);
+
+    let tempdir = TempDir::new().unwrap();
+
+    write(tempdir.path(), Network::Regtest, KEY, &blocks);
+
+    let mut block_files =
+      BlockFiles::open(tempdir.path(), Network::Regtest, blocks[2].block_hash()).unwrap();
+
+    for height in [1, 2] {
+      assert_eq!(
+        block_files.get(height, false).unwrap_err().to_string(),
+        format!(
+          "block {height} in `{}` does not match its header",
+          BlockFiles::path(tempdir.path(), if height == 1 { 0 } else { 1 }).display()
+        ),
+      );
+
+      assert_eq!(
+        block_files.get(height, true).unwrap().unwrap().header,
+        blocks[usize::try_from(height).unwrap()].header,
+      );
+    }
+  }
+}
@@ -0,0 +1,471 @@
+use super::*;
+
+const FOOTER_SIZE: usize = 48;
+const LOG_BLOCK_SIZE: usize = 32768;
+const LOG_HEADER_SIZE: usize = 7;
+const TABLE_MAGIC: u64 = 0xdb4775248b80fb57;
+
+/// Latest value of each key starting with `prefix` in the LevelDB database in
+/// `dir`, read from its table and log files. Keys are resolved by sequence
+/// number, so tables that a compaction has replaced but not yet deleted are
+/// harmless. Bitcoin Core disables LevelDB compression, so compressed tables
+/// are not supported.
+pub(super) fn read(dir: &Path, prefix: &[u8]) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+  let mut entries = Entries {
+    prefix,
+    entries: HashMap::new(),
+  };
+
+  for entry in fs::read_dir(dir).with_context(|| format!("failed to read `{}`", dir.display()))? {
+    let path = entry?.path();
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("ldb" | "sst") => entries
+        .read_table(&fs::read(&path)?)
+        .with_context(|| format!("failed to read table `{}`", path.display()))?,
+      Some("log") => entries
+        .read_log(&fs::read(&path)?)
+        .with_context(|| format!("failed to read log `{}`", path.display()))?,
+      _ => {}
+    }
+  }
+
+  Ok(
+    entries
+      .entries
+      .into_iter()
+      .filter_map(|(key, (_sequence, value))| Some((key, value?)))
+      .collect(),
+  )
+}
+
+struct Entries<'a> {
+  prefix: &'a [u8],
+  entries: HashMap<Vec<u8>, (u64, Option<Vec<u8>>)>,
+}
+
+impl Entries<'_> {
+  fn insert(&mut self, key: &[u8], sequence: u64, value: Option<&[u8]>) {
+    if !key.starts_with(self.prefix) {
+      return;
+    }
+
+    if let Some((latest, _value)) = self.entries.get(key) {
+      if *latest >= sequence {
+        return;
+      }
+    }
+
+    self
+      .entries
+      .insert(key.to_vec(), (sequence, value.map(<[u8]>::to_vec)));
+  }
+
+  fn read_table(&mut self, table: &[u8]) -> Result {
+    ensure!(table.len() >= FOOTER_SIZE, "table is truncated");
+
+    let footer = &table[table.len() - FOOTER_SIZE..];
+
+    ensure!(
+      u64::from_le_bytes(footer[FOOTER_SIZE - 8..].try_into().unwrap()) == TABLE_MAGIC,
+      "table has invalid magic number",
+    );
+
+    let mut handles = &footer[..FOOTER_SIZE - 8];
+
+    let _metaindex = block(table, &mut handles)?;
+
+    for (_key, handle) in entries(block(table, &mut handles)?)? {
+      for (key, value) in entries(block(table, &mut handle.as_slice())?)? {
+        ensure!(key.len() >= 8, "table key is truncated");
+
+        let (key, trailer) = key.split_at(key.len() - 8);
+
+        let trailer = u64::from_le_bytes(trailer.try_into().unwrap());
+
+        match trailer & 0xff {
+          0 => self.insert(key, trailer >> 8, None),
+          1 => self.insert(key, trailer >> 8, Some(value.as_slice())),
+          kind => bail!("table key has invalid type {kind}"),
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  // records that were being written when the log was read end early, and are
+  // skipped
+  fn read_log(&mut self, log: &[u8]) -> Result {
+    let mut record = Vec::new();
+
+    for mut block in log.chunks(LOG_BLOCK_SIZE) {
+      while block.len() >= LOG_HEADER_SIZE {
+        let length = usize::from(u16::from_le_bytes(block[4..6].try_into().unwrap()));
+        let kind = block[6];
+
+        // zeros are left after the last record
+        if kind == 0 {
+          break;
+        }
+
+        let Some(data) = block.get(LOG_HEADER_SIZE..LOG_HEADER_SIZE + length) else {
+          return Ok(());
+        };
+
+        block = &block[LOG_HEADER_SIZE + length..];
+
+        match kind {
+          1 => self.read_batch(data)?,
+          2 => {
+            record.clear();
+            record.extend_from_slice(data);
+          }
+          3 => record.extend_from_slice(data),
+          4 => {
+            record.extend_from_slice(data);
+            self.read_batch(&record)?;
+            record.clear();
+          }
+          _ => bail!("log record has invalid type {kind}"),
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  fn read_batch(&mut self, batch: &[u8]) -> Result {
+    ensure!(batch.len() >= 12, "log batch is truncated");
+
+    let sequence = u64::from_le_bytes(batch[..8].try_into().unwrap());
+    let count = u32::from_le_bytes(batch[8..12].try_into().unwrap());
+
+    let mut data = &batch[12..];
+
+    for sequence in (sequence..).take(count.try_into().unwrap()) {
+      let kind = take(&mut data, 1)?[0];
+
+      let length = varint(&mut data)?;
+      let key = take(&mut data, length)?;
+
+      match kind {
+        0 => self.insert(key, sequence, None),
+        1 => {
+          let length = varint(&mut data)?;
+          let value = take(&mut data, length)?;
+          self.insert(key, sequence, Some(value));
+        }
+        _ => bail!("log batch entry has invalid type {kind}"),
+      }
+    }
+
+    Ok(())
+  }
+}
+
+// contents of the table block whose handle is at the start of `handle`
+fn block<'a>(table: &'a [u8], handle: &mut &[u8]) -> Result<&'a [u8]> {
+  let offset = usize::try_from(varint(handle)?)?;
+  let size = usize::try_from(varint(handle)?)?;
+
+  let (Some(contents), Some(compression)) =
+    (table.get(offset..offset + size), table.get(offset + size))
+  else {
+    bail!("table block is truncated");
+  };
+
+  ensure!(
+    *compression == 0,
+    "table block has unsupported compression type {compression}",
+  );
+
+  Ok(contents)
+}
+
+// key value pairs of a table block, whose keys share prefixes with the keys
+// before them
+fn entries(block: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+  ensure!(block.len() >= 4, "table block is truncated");
+
+  let restarts = u32::from_le_bytes(block[block.len() - 4..].try_into().unwrap());
+
+  let end = usize::try_from(restarts)
+    .ok()
+    .and_then(|restarts| restarts.checked_mul(4))
+    .and_then(|restarts| (block.len() - 4).checked_sub(restarts))
+    .context("table block has too many restarts")?;
+
+  let mut data = &block[..end];
+  let mut key = Vec::new();
+  let mut entries = Vec::new();
+
+  while !data.is_empty() {
+    let shared = usize::try_from(varint(&mut data)?)?;
+    let unshared = varint(&mut data)?;
+    let length = varint(&mut data)?;
+
+    ensure!(shared <= key.len(), "table block key is truncated");
+
+    key.truncate(shared);
+    key.extend_from_slice(take(&mut data, unshared)?);
+
+    entries.push((key.clone(), take(&mut data, length)?.to_vec()));
+  }
+
+  Ok(entries)
+}
+
+fn take<'a>(data: &mut &'a [u8], n: u64) -> Result<&'a [u8]> {
+  let n = usize::try_from(n)?;
+
+  ensure!(data.len() >= n, "entry is truncated");
+
+  let (taken, rest) = data.split_at(n);
+
+  *data = rest;
+
+  Ok(taken)
+}
+
+fn varint(data: &mut &[u8]) -> Result<u64> {
+  let mut n = 0;
+
+  for shift in (0..64).step_by(7) {
+    let byte = take(data, 1)?[0];
+
+    n |= u64::from(byte & 0x7f) << shift;
+
+    if byte & 0x80 == 0 {
+      return Ok(n);
+    }
+  }
+
+  bail!("varint is too long")
+}
+
+/// Writes `entries`, in order of increasing sequence number, to a table
+/// holding the first half, with restarts every 16 keys, and a log holding
+/// the rest. `None` values are deletions.
+#[cfg(test)]
+pub(super) fn write(dir: &Path, entries: &[(Vec<u8>, Option<Vec<u8>>)]) {
+  fn put_varint(buffer: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+      buffer.push(u8::try_from(n & 0x7f).unwrap() | 0x80);
+      n >>= 7;
+    }
+    buffer.push(n.try_into().unwrap());
+  }
+
+  fn put_block(table: &mut Vec<u8>, entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let offset = table.len();
+
+    let mut restarts = Vec::new();
+    let mut previous: &[u8] = &[];
+
+    for (i, (key, value)) in entries.iter().enumerate() {
+      let shared = if i % 16 == 0 {
+        restarts.push(u32::try_from(table.len() - offset).unwrap());
+        0
+      } else {
+        key.iter().zip(previous).take_while(|(a, b)| a == b).count()
+      };
+
+      put_varint(table, shared.try_into().unwrap());
+      put_varint(table, (key.len() - shared).try_into().unwrap());
+      put_varint(table, value.len().try_into().unwrap());
+      table.extend_from_slice(&key[shared..]);
+      table.extend_from_slice(value);
+
+      previous = key;
+    }
+
+    if restarts.is_empty() {
+      restarts.push(0);
+    }
+
+    for restart in &restarts {
+      table.extend(restart.to_le_bytes());
+    }
+
+    table.extend(u32::try_from(restarts.len()).unwrap().to_le_bytes());
+
+    let mut handle = Vec::new();
+    put_varint(&mut handle, offset.try_into().unwrap());
+    put_varint(&mut handle, (table.len() - offset).try_into().unwrap());
+
+    // uncompressed, with an unchecked checksum
+    table.extend([0; 5]);
+
+    handle
+  }
+
+  fs::create_dir_all(dir).unwrap();
+
+  let (tabled, logged) = entries.split_at(entries.len() / 2);
+
+  let mut keys = tabled
+    .iter()
+    .enumerate()
+    .map(|(sequence, (key, value))| {
+      let mut internal = key.clone();
+      internal.extend(
+        ((u64::try_from(sequence).unwrap() << 8) | u64::from(value.is_some())).to_le_bytes(),
+      );
+      (internal, value.clone().unwrap_or_default())
+    })
+    .collect::<Vec<(Vec<u8>, Vec<u8>)>>();
+
+  keys.sort_by(|(a, _), (b, _)| {
+    a[..a.len() - 8]
+      .cmp(&b[..b.len() - 8])
+      .then_with(|| b[b.len() - 8..].cmp(&a[a.len() - 8..]))
+  });
+
+  let mut table = Vec::new();
+
+  let data = put_block(&mut table, &keys);
+  let metaindex = put_block(&mut table, &[]);
+  let index = put_block(
+    &mut table,
+    &[(
+      keys.last().map(|(key, _)| key.clone()).unwrap_or_default(),
+      data,
+    )],
+  );
+
+  let mut footer = Vec::new();
+  footer.extend(metaindex);
+  footer.extend(index);
+  footer.resize(FOOTER_SIZE - 8, 0);
+  footer.extend(TABLE_MAGIC.to_le_bytes());
+
+  table.extend(footer);
+
+  fs::write(dir.join("000005.ldb"), table).unwrap();
+
+  // one batch per entry, so that large values span log blocks
+  let mut log = Vec::new();
+
+  for (i, (key, value)) in logged.iter().enumerate() {
+    let mut batch = Vec::new();
+    batch.extend(u64::try_from(tabled.len() + i).unwrap().to_le_bytes());
+    batch.extend(1u32.to_le_bytes());
+    batch.push(value.is_some().into());
+    put_varint(&mut batch, key.len().try_into().unwrap());
+    batch.extend(key);
+    if let Some(value) = value {
+      put_varint(&mut batch, value.len().try_into().unwrap());
+      batch.extend(value);
+    }
+
+    let mut data = batch.as_slice();
+    let mut first = true;
+
+    loop {
+      let left = LOG_BLOCK_SIZE - log.len() % LOG_BLOCK_SIZE;
+
+      if left < LOG_HEADER_SIZE {
+        log.resize(log.len() + left, 0);
+        continue;
+      }
+
+      let n = data.len().min(left - LOG_HEADER_SIZE);
+      let last = n == data.len();
+
+      log.extend([0; 4]);
+      log.extend(u16::try_from(n).unwrap().to_le_bytes());
+      log.push(match (first, last) {
+        (true, true) => 1,
+        (true, false) => 2,
+        (false, false) => 3,
+        (false, true) => 4,
+      });
+      log.extend(&data[..n]);
+
+      data = &data[n..];
+      first = false;
+
+      if last {
+        break;
+      }
+    }
+  }
+
+  fs::write(dir.join("000006.log"), log).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, tempfile::TempDir};
+
+  #[test]
+  fn latest_values_are_read_from_tables_and_logs() {
+    let tempdir = TempDir::new().unwrap();
+
+    let large = vec![7; LOG_BLOCK_SIZE * 2 + 100];
+
+    let mut entries = (0..40u8)
+      .map(|i| (vec![b'a', i / 4, i], Some(vec![i])))
+      .collect::<Vec<(Vec<u8>, Option<Vec<u8>>)>>();
+
+    entries.extend([
+      (b"b".to_vec(), Some(vec![0])),
+      (vec![b'a', 0, 0], Some(vec![1])),
+      (vec![b'a', 0, 1], None),
+      (vec![b'a', 9, 36], Some(large.clone())),
+    ]);
+
+    entries.extend((40..76u8).map(|i| (vec![b'a', i / 4, i], Some(vec![i]))));
+
+    write(tempdir.path(), &entries);
+
+    let read = read(tempdir.path(), b"a").unwrap();
+
+    assert_eq!(read.len(), 75);
+    assert_eq!(read[&vec![b'a', 0, 0]], [1]);
+    assert!(!read.contains_key(&vec![b'a', 0, 1]));
+    assert_eq!(read[&vec![b'a', 0, 2]], [2]);
+    assert_eq!(read[&vec![b'a', 9, 36]], large);
+    assert_eq!(read[&vec![b'a', 18, 75]], [75]);
+    assert!(!read.contains_key(b"b".as_slice()));
+  }
+
+  #[test]
+  fn compressed_tables_are_an_error() {
+    let tempdir = TempDir::new().unwrap();
+
+    write(
+      tempdir.path(),
+      &[
+        (b"a".to_vec(), Some(vec![0])),
+        (b"b".to_vec(), Some(vec![1])),
+      ],
+    );
+
+    let path = tempdir.path().join("000005.ldb");
+
+    let mut table = fs::read(&path).unwrap();
+
+    let mut handles = &table[table.len() - FOOTER_SIZE..];
+    block(&table, &mut handles).unwrap();
+    let index = block(&table, &mut handles).unwrap();
+
+    let (_key, handle) = entries(index).unwrap().remove(0);
+    let mut handle = handle.as_slice();
+    let offset = varint(&mut handle).unwrap();
+    let size = varint(&mut handle).unwrap();
+
+    table[usize::try_from(offset + size).unwrap()] = 1;
+
+    fs::write(&path, table).unwrap();
+
+    assert_eq!(
+      read(tempdir.path(), b"")
+        .unwrap_err()
+        .root_cause()
+        .to_string(),
+      "table block has unsupported compression type 1",
+    );
+  }
+}
@@ -0,0 +1,579 @@
+use {
+  super::{fetcher::Fetcher, *},
+  bitcoin::consensus::{encode::VarInt, Decodable},
+  std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+  },
+};
+
+/// Where the updater gets blocks and the transactions they spend from.
+/// Bitcoin Core over RPC is the default, while a directory of serialized
+/// blocks or an in-memory fixture allow replaying a range of blocks without
+/// a node.
+pub trait BlockSource: Send + Sync {
+  /// Height of the chain tip.
+  fn block_count(&self) -> Result<u32>;
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>>;
+
+  fn block(&self, height: u32) -> Result<Option<Block>>;
+
+  fn block_header(&self, height: u32) -> Result<Option<Header>> {
+    Ok(self.block(height)?.map(|block| block.header))
+  }
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>>;
+
+  /// Height of the block confirming `txid`, if it is confirmed.
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>>;
+
+  /// Transactions spent by a block, in the order of `txids`.
+  fn transactions(&self, txids: Vec<Txid>) -> Result<Vec<Transaction>> {
+    txids
+      .into_iter()
+      .map(|txid| {
+        self
+          .transaction(txid)?
+          .ok_or_else(|| anyhow!("transaction {txid} not found"))
+      })
+      .collect()
+  }
+}
+
+// Default rpcworkqueue in bitcoind is 16, meaning more than 16 concurrent
+// requests will be rejected. Since blocks are requested on a separate thread,
+// and we don't want to break if anything else runs a request, we keep this to
+// 12.
+const PARALLEL_REQUESTS: usize = 12;
+
+pub struct RpcBlockSource {
+  client: Client,
+  fetcher: Fetcher,
+  runtime: Option<tokio::runtime::Runtime>,
+}
+
+impl RpcBlockSource {
+  pub(crate) fn new(settings: &Settings) -> Result<Self> {
+    Ok(Self {
+      client: settings.bitcoin_rpc_client(None)?,
+      fetcher: Fetcher::new(settings)?,
+      runtime: Some(
+        tokio::runtime::Builder::new_multi_thread()
+          .enable_all()
+          .build()?,
+      ),
+    })
+  }
+}
+
+// the block source may be dropped by an async task, where a runtime may only
+// be shut down in the background
+impl Drop for RpcBlockSource {
+  fn drop(&mut self) {
+    if let Some(runtime) = self.runtime.take() {
+      runtime.shutdown_background();
+    }
+  }
+}
+
+impl BlockSource for RpcBlockSource {
+  fn block_count(&self) -> Result<u32> {
+    Ok(self.client.get_block_count()?.try_into()?)
+  }
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>> {
+    self.client.get_block_hash(height.into()).into_option()
+  }
+
+  fn block(&self, height: u32) -> Result<Option<Block>> {
+    self
+      .block_hash(height)?
+      .map(|hash| Ok(self.client.get_block(&hash)?))
+      .transpose()
+  }
+
+  fn block_header(&self, height: u32) -> Result<Option<Header>> {
+    self
+      .block_hash(height)?
+      .map(|hash| Ok(self.client.get_block_header(&hash)?))
+      .transpose()
+  }
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    self.client.get_raw_transaction(&txid, None).into_option()
+  }
+
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>> {
+    let Some(info) = self
+      .client
+      .get_raw_transaction_info(&txid, None)
+      .into_option()?
+    else {
+      return Ok(None);
+    };
+
+    let Some(hash) = info.blockhash else {
+      return Ok(None);
+    };
+
+    Ok(
+      self
+        .client
+        .get_block_header_info(&hash)
+        .into_option()?
+        .map(|info| info.height.try_into().unwrap()),
+    )
+  }
+
+  // fetched with batched JSON-RPC requests made in parallel, since blocks can
+  // spend thousands of outputs
+  fn transactions(&self, txids: Vec<Txid>) -> Result<Vec<Transaction>> {
+    let chunk_size = txids.len() / PARALLEL_REQUESTS + 1;
+
+    Ok(
+      self
+        .runtime
+        .as_ref()
+        .unwrap()
+        .block_on(futures::future::try_join_all(
+          txids
+            .chunks(chunk_size)
+            .map(|chunk| self.fetcher.get_transactions(chunk.to_vec())),
+        ))?
+        .into_iter()
+        .flatten()
+        .collect(),
+    )
+  }
+}
+
+/// Blocks stored one per file, named `<HEIGHT>.blk`, alongside the
+/// transactions they spend which were confirmed before the first block,
+/// named `<HEIGHT>-<TXID>.tx`. Both are consensus serialized.
+pub struct DirectoryBlockSource {
+  blocks: BTreeMap<u32, PathBuf>,
+  dir: PathBuf,
+  transactions: HashMap<Txid, (u32, TransactionLocation)>,
+}
+
+/// Where a transaction is serialized, so it can be read without decoding the
+/// rest of its block.
+enum TransactionLocation {
+  File(PathBuf),
+  /// Byte offset in the file of the block confirming the transaction.
+  Block(u64),
+}
+
+impl DirectoryBlockSource {
+  pub fn open(dir: &Path) -> Result<Self> {
+    let mut blocks = BTreeMap::new();
+    let mut transactions = HashMap::new();
+
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read `{}`", dir.display()))? {
+      let path = entry?.path();
+
+      let (Some(stem), Some(extension)) = (
+        path.file_stem().and_then(|stem| stem.to_str()),
+        path.extension().and_then(|extension| extension.to_str()),
+      ) else {
+        continue;
+      };
+
+      match extension {
+        "blk" => {
+          let height = stem
+            .parse()
+            .with_context(|| format!("invalid block file name `{}`", path.display()))?;
+          blocks.insert(height, path);
+        }
+        "tx" => {
+          let (height, txid) = stem
+            .split_once('-')
+            .and_then(|(height, txid)| Some((height.parse().ok()?, txid.parse().ok()?)))
+            .with_context(|| format!("invalid transaction file name `{}`", path.display()))?;
+          transactions.insert(txid, (height, TransactionLocation::File(path)));
+        }
+        _ => {}
+      }
+    }
+
+    if let Some((first, last)) = blocks.first_key_value().zip(blocks.last_key_value()) {
+      ensure!(
+        usize::try_from(last.0 - first.0).unwrap() + 1 == blocks.len(),
+        "blocks in `{}` are not contiguous",
+        dir.display(),
+      );
+    }
+
+    for (height, path) in &blocks {
+      let block = fs::read(path).with_context(|| format!("failed to read `{}`", path.display()))?;
+
+      let mut cursor = io::Cursor::new(block.as_slice());
+
+      Header::consensus_decode(&mut cursor)?;
+
+      for _ in 0..VarInt::consensus_decode(&mut cursor)?.0 {
+        let offset = cursor.position();
+        let transaction = Transaction::consensus_decode(&mut cursor)?;
+        transactions.insert(
+          transaction.txid(),
+          (*height, TransactionLocation::Block(offset)),
+        );
+      }
+    }
+
+    Ok(Self {
+      blocks,
+      dir: dir.into(),
+      transactions,
+    })
+  }
+
+  fn read<T: Decodable>(path: &Path) -> Result<T> {
+    Ok(T::consensus_decode(&mut io::BufReader::new(
+      File::open(path).with_context(|| format!("failed to open `{}`", path.display()))?,
+    ))?)
+  }
+}
+
+impl BlockSource for DirectoryBlockSource {
+  fn block_count(&self) -> Result<u32> {
+    self
+      .blocks
+      .last_key_value()
+      .map(|(height, _path)| *height)
+      .with_context(|| format!("no blocks in `{}`", self.dir.display()))
+  }
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>> {
+    Ok(self.block_header(height)?.map(|header| header.block_hash()))
+  }
+
+  fn block(&self, height: u32) -> Result<Option<Block>> {
+    self
+      .blocks
+      .get(&height)
+      .map(|path| Self::read(path))
+      .transpose()
+  }
+
+  fn block_header(&self, height: u32) -> Result<Option<Header>> {
+    let Some(path) = self.blocks.get(&height) else {
+      return Ok(None);
+    };
+
+    let mut header = [0; 80];
+
+    File::open(path)
+      .with_context(|| format!("failed to open `{}`", path.display()))?
+      .read_exact(&mut header)?;
+
+    Ok(Some(Header::consensus_decode(&mut header.as_slice())?))
+  }
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    match self.transactions.get(&txid) {
+      Some((_height, TransactionLocation::File(path))) => Ok(Some(Self::read(path)?)),
+      Some((height, TransactionLocation::Block(offset))) => {
+        let path = &self.blocks[height];
+
+        let mut file =
+          File::open(path).with_context(|| format!("failed to open `{}`", path.display()))?;
+
+        file.seek(SeekFrom::Start(*offset))?;
+
+        Ok(Some(Transaction::consensus_decode(
+          &mut io::BufReader::new(file),
+        )?))
+      }
+      None => Ok(None),
+    }
+  }
+
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>> {
+    Ok(self.transactions.get(&txid).map(|(height, _path)| *height))
+  }
+}
+
+/// Blocks and transactions held in memory, which can be extended while an
+/// index is reading from it.
+#[derive(Default)]
+pub struct MemoryBlockSource {
+  state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+  blocks: BTreeMap<u32, Block>,
+  transactions: HashMap<Txid, (Transaction, Option<u32>)>,
+}
+
+impl MemoryBlockSource {
+  pub fn push_block(&self, height: u32, block: Block) {
+    let mut state = self.state.lock().unwrap();
+
+    for transaction in &block.txdata {
+      state
+        .transactions
+        .insert(transaction.txid(), (transaction.clone(), Some(height)));
+    }
+
+    state.blocks.insert(height, block);
+  }
+
+  /// Adds a transaction spent by the blocks, confirmed at `height`.
+  pub fn insert_transaction(&self, transaction: Transaction, height: Option<u32>) {
+    self
+      .state
+      .lock()
+      .unwrap()
+      .transactions
+      .insert(transaction.txid(), (transaction, height));
+  }
+}
+
+impl BlockSource for MemoryBlockSource {
+  fn block_count(&self) -> Result<u32> {
+    self
+      .state
+      .lock()
+      .unwrap()
+      .blocks
+      .last_key_value()
+      .map(|(height, _block)| *height)
+      .context("no blocks in memory block source")
+  }
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>> {
+    Ok(
+      self
+        .state
+        .lock()
+        .unwrap()
+        .blocks
+        .get(&height)
+        .map(|block| block.block_hash()),
+    )
+  }
+
+  fn block(&self, height: u32) -> Result<Option<Block>> {
+    Ok(self.state.lock().unwrap().blocks.get(&height).cloned())
+  }
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    Ok(
+      self
+        .state
+        .lock()
+        .unwrap()
+        .transactions
+        .get(&txid)
+        .map(|(transaction, _height)| transaction.clone()),
+    )
+  }
+
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>> {
+    Ok(
+      self
+        .state
+        .lock()
+        .unwrap()
+        .transactions
+        .get(&txid)
+        .and_then(|(_transaction, height)| *height),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, tempfile::TempDir};
+
+  #[test]
+  fn directory_block_source() {
+    let core = mockcore::builder().network(Network::Regtest).build();
+
+    let blocks = core.mine_blocks(2);
+
+    let genesis = Chain::Regtest.genesis_block().txdata[0].clone();
+
+    let tempdir = TempDir::new().unwrap();
+
+    for (height, block) in blocks.iter().enumerate() {
+      fs::write(
+        tempdir.path().join(format!("{}.blk", height + 1)),
+        consensus::encode::serialize(block),
+      )
+      .unwrap();
+    }
+
+    fs::write(
+      tempdir.path().join(format!("0-{}.tx", genesis.txid())),
+      consensus::encode::serialize(&genesis),
+    )
+    .unwrap();
+
+    let source = DirectoryBlockSource::open(tempdir.path()).unwrap();
+
+    assert_eq!(source.block_count().unwrap(), 2);
+
+    assert_eq!(source.block(0).unwrap(), None);
+    assert_eq!(source.block(1).unwrap().unwrap(), blocks[0]);
+    assert_eq!(source.block_header(2).unwrap().unwrap(), blocks[1].header);
+    assert_eq!(
+      source.block_hash(2).unwrap().unwrap(),
+      blocks[1].block_hash()
+    );
+
+    let coinbase = blocks[1].txdata[0].clone();
+
+    assert_eq!(
+      source
+        .transactions(vec![coinbase.txid(), genesis.txid()])
+        .unwrap(),
+      [coinbase.clone(), genesis.clone()],
+    );
+
+    assert_eq!(source.transaction_height(coinbase.txid()).unwrap(), Some(2));
+    assert_eq!(source.transaction_height(genesis.txid()).unwrap(), Some(0));
+
+    let missing = blocks[0].block_hash().to_raw_hash().into();
+
+    assert_eq!(source.transaction(missing).unwrap(), None);
+    assert_eq!(source.transaction_height(missing).unwrap(), None);
+    assert_eq!(
+      source.transactions(vec![missing]).unwrap_err().to_string(),
+      format!("transaction {missing} not found"),
+    );
+  }
+
+  #[test]
+  fn directory_block_source_reads_transactions_at_their_offsets() {
+    let core = mockcore::builder().network(Network::Regtest).build();
+
+    let mut blocks = core.mine_blocks(1);
+
+    core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Default::default())],
+      ..default()
+    });
+
+    blocks.extend(core.mine_blocks(1));
+
+    let tempdir = TempDir::new().unwrap();
+
+    for (height, block) in blocks.iter().enumerate() {
+      fs::write(
+        tempdir.path().join(format!("{}.blk", height + 1)),
+        consensus::encode::serialize(block),
+      )
+      .unwrap();
+    }
+
+    let source = DirectoryBlockSource::open(tempdir.path()).unwrap();
+
+    assert_eq!(blocks[1].txdata.len(), 2);
+
+    for (height, block) in blocks.iter().enumerate() {
+      for transaction in &block.txdata {
+        assert_eq!(
+          source.transaction(transaction.txid()).unwrap().as_ref(),
+          Some(transaction),
+        );
+        assert_eq!(
+          source.transaction_height(transaction.txid()).unwrap(),
+          Some(u32::try_from(height).unwrap() + 1),
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn directory_block_source_blocks_must_be_contiguous() {
+    let core = mockcore::builder().network(Network::Regtest).build();
+
+    let blocks = core.mine_blocks(3);
+
+    let tempdir = TempDir::new().unwrap();
+
+    for height in [1, 3] {
+      fs::write(
+        tempdir.path().join(format!("{height}.blk")),
+        consensus::encode::serialize(&blocks[height - 1]),
+      )
+      .unwrap();
+    }
+
+    assert_eq!(
+      DirectoryBlockSource::open(tempdir.path())
+        .err()
+        .unwrap()
+        .to_string(),
+      format!(
+        "blocks in `{}` are not contiguous",
+        tempdir.path().display()
+      ),
+    );
+  }
+
+  #[test]
+  fn rpc_block_source_transactions_are_returned_in_order() {
+    let context = testing::Context::builder().build();
+
+    let blocks = context.core.mine_blocks(PARALLEL_REQUESTS as u64 * 2);
+
+    let txids = blocks
+      .iter()
+      .rev()
+      .map(|block| block.txdata[0].txid())
+      .collect::<Vec<Txid>>();
+
+    for _ in 0..2 {
+      assert_eq!(
+        context
+          .index
+          .block_source
+          .transactions(txids.clone())
+          .unwrap()
+          .iter()
+          .map(Transaction::txid)
+          .collect::<Vec<Txid>>(),
+        txids,
+      );
+    }
+  }
+
+  #[test]
+  fn memory_block_source() {
+    let source = MemoryBlockSource::default();
+
+    assert!(source.block_count().is_err());
+
+    let genesis = Chain::Regtest.genesis_block();
+
+    source.push_block(0, genesis.clone());
+
+    let transaction = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: Vec::new(),
+    };
+
+    source.insert_transaction(transaction.clone(), None);
+
+    assert_eq!(source.block_count().unwrap(), 0);
+    assert_eq!(source.block_hash(0).unwrap(), Some(genesis.block_hash()));
+    assert_eq!(source.block(1).unwrap(), None);
+    assert_eq!(
+      source.transaction_height(genesis.txdata[0].txid()).unwrap(),
+      Some(0)
+    );
+    assert_eq!(
+      source.transaction(transaction.txid()).unwrap(),
+      Some(transaction.clone())
+    );
+    assert_eq!(source.transaction_height(transaction.txid()).unwrap(), None);
+  }
+}
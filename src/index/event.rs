@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Event {
   InscriptionCreated {
     block_height: u32,
@@ -17,6 +18,13 @@ pub enum Event {
     old_location: SatPoint,
     sequence_number: u32,
   },
+  Reorg {
+    block_height: u32,
+    depth: u32,
+    end_height: u32,
+    orphaned_blocks: Vec<(u32, BlockHash)>,
+    start_height: u32,
+  },
   RuneBurned {
     amount: u128,
     block_height: u32,
@@ -42,3 +50,82 @@ pub enum Event {
     txid: Txid,
   },
 }
+
+impl Event {
+  /// `type` of every event, as serialized
+  pub(crate) const KINDS: &'static [&'static str] = &[
+    "inscription_created",
+    "inscription_transferred",
+    "reorg",
+    "rune_burned",
+    "rune_etched",
+    "rune_minted",
+    "rune_transferred",
+  ];
+
+  pub(crate) fn kind(&self) -> &'static str {
+    match self {
+      Self::InscriptionCreated { .. } => "inscription_created",
+      Self::InscriptionTransferred { .. } => "inscription_transferred",
+      Self::Reorg { .. } => "reorg",
+      Self::RuneBurned { .. } => "rune_burned",
+      Self::RuneEtched { .. } => "rune_etched",
+      Self::RuneMinted { .. } => "rune_minted",
+      Self::RuneTransferred { .. } => "rune_transferred",
+    }
+  }
+}
+
+// @br-indexer: position of an event in the event log. The hash of the event's
+// block is part of the id, so that an id from an orphaned block is noticed
+// instead of silently skipping the events that replaced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeserializeFromStr, SerializeDisplay)]
+pub struct EventId {
+  pub height: u32,
+  pub index: u32,
+  pub block_hash: BlockHash,
+}
+
+impl EventId {
+  /// Id of a position after every event of the block at `height`
+  pub(crate) fn after_block(height: u32, block_hash: BlockHash) -> Self {
+    Self {
+      height,
+      index: u32::MAX,
+      block_hash,
+    }
+  }
+}
+
+// @br-indexer: result of reading the event log from an event id
+#[derive(Debug, PartialEq)]
+pub(crate) enum Events {
+  Page(Vec<(EventId, Event)>),
+  Reorged(ReorgLogEntry),
+  Unavailable,
+}
+
+impl Display for EventId {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}:{}:{}", self.height, self.index, self.block_hash)
+  }
+}
+
+impl FromStr for EventId {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parts = s.splitn(3, ':');
+
+    let (Some(height), Some(index), Some(block_hash)) = (parts.next(), parts.next(), parts.next())
+    else {
+      bail!("invalid event id: {s}");
+    };
+
+    Ok(Self {
+      height: height.parse()?,
+      index: index.parse()?,
+      block_hash: block_hash.parse()?,
+    })
+  }
+}
@@ -0,0 +1,668 @@
+use {
+  super::*,
+  crate::indexer::rune_event::RuneEventResponse,
+  serde_json::{Map, Value},
+  std::fs::File,
+};
+
+// rows per row group in columnar exports
+const ROW_GROUP_SIZE: usize = 10_000;
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq)]
+pub(crate) enum ExportFormat {
+  JsonLines,
+  Columnar,
+}
+
+impl ExportFormat {
+  fn extension(self) -> &'static str {
+    match self {
+      Self::JsonLines => "jsonl",
+      Self::Columnar => "columns.jsonl",
+    }
+  }
+}
+
+/// Summary of an export. `tables` maps each table to the number of rows
+/// written to it.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportInfo {
+  pub block_hash: BlockHash,
+  pub from_height: u32,
+  pub tables: BTreeMap<String, u64>,
+  pub to_height: u32,
+}
+
+// u128 amounts are written as strings, since they may not fit in a JSON
+// number
+#[derive(Serialize)]
+struct RuneRow {
+  id: RuneId,
+  block: u64,
+  burned: String,
+  divisibility: u8,
+  etching: Txid,
+  mints: String,
+  number: u64,
+  premine: String,
+  spaced_rune: SpacedRune,
+  symbol: Option<char>,
+  terms: Option<TermsRow>,
+  timestamp: u64,
+  turbo: bool,
+}
+
+impl RuneRow {
+  fn new(id: RuneId, entry: RuneEntry) -> Self {
+    Self {
+      id,
+      block: entry.block,
+      burned: entry.burned.to_string(),
+      divisibility: entry.divisibility,
+      etching: entry.etching,
+      mints: entry.mints.to_string(),
+      number: entry.number,
+      premine: entry.premine.to_string(),
+      spaced_rune: entry.spaced_rune,
+      symbol: entry.symbol,
+      terms: entry.terms.map(|terms| TermsRow {
+        amount: terms.amount.map(|amount| amount.to_string()),
+        cap: terms.cap.map(|cap| cap.to_string()),
+        height: terms.height,
+        offset: terms.offset,
+      }),
+      timestamp: entry.timestamp,
+      turbo: entry.turbo,
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct TermsRow {
+  amount: Option<String>,
+  cap: Option<String>,
+  height: (Option<u64>, Option<u64>),
+  offset: (Option<u64>, Option<u64>),
+}
+
+#[derive(Serialize)]
+struct RuneBalanceRow {
+  outpoint: OutPoint,
+  rune_id: RuneId,
+  amount: String,
+}
+
+#[derive(Serialize)]
+struct InscriptionRow {
+  sequence_number: u32,
+  id: InscriptionId,
+  number: i32,
+  height: u32,
+  timestamp: u32,
+  fee: u64,
+  sat: Option<Sat>,
+  satpoint: SatPoint,
+  charms: Vec<Charm>,
+  parents: Vec<InscriptionId>,
+  content_type: Option<String>,
+  content_length: Option<usize>,
+  metaprotocol: Option<String>,
+  metadata: Option<String>,
+}
+
+impl Index {
+  // @br-indexer: write rune and inscription tables to `<dir>/<table>.<ext>`,
+  // keeping rows from blocks in `from_height..=to_height`. Rune balances have
+  // no height, so only balances of outputs credited in range are written.
+  pub(crate) fn export_tables(
+    &self,
+    dir: &Path,
+    format: ExportFormat,
+    from_height: Option<u32>,
+    to_height: Option<u32>,
+  ) -> Result<ExportInfo> {
+    let rtx = self.database.begin_read()?;
+
+    let Some((height, header)) = rtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .last()?
+      .map(|(height, header)| (height.value(), Header::load(*header.value())))
+    else {
+      bail!("cannot export empty index");
+    };
+
+    let from_height = from_height.unwrap_or_default();
+    let to_height = to_height.unwrap_or(height).min(height);
+
+    ensure!(
+      from_height <= to_height,
+      "from height {from_height} is greater than to height {to_height}",
+    );
+
+    let range = from_height..=to_height;
+
+    let full = from_height == 0 && to_height == height;
+
+    fs::create_dir_all(dir).with_context(|| format!("failed to create `{}`", dir.display()))?;
+
+    let table = |name: &str| -> Result<TableWriter> {
+      let path = dir.join(format!("{name}.{}", format.extension()));
+      log::info!("exporting {name} to {}", path.display());
+      Ok(TableWriter {
+        columns: Map::new(),
+        format,
+        group: 0,
+        inner: BufWriter::new(
+          File::create(&path).with_context(|| format!("failed to create `{}`", path.display()))?,
+        ),
+        rows: 0,
+      })
+    };
+
+    let mut written = Vec::new();
+
+    if self.index_runes {
+      let mut writer = table("runes")?;
+      for result in rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?.iter()? {
+        let (id, entry) = result?;
+        let entry = RuneEntry::load(entry.value());
+        if range.contains(&u32::try_from(entry.block)?) {
+          writer.row(RuneRow::new(RuneId::load(id.value()), entry))?;
+        }
+      }
+      written.push(("runes", writer.finish()?));
+
+      let events = rtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?;
+
+      let events = events.range(
+        BlockId {
+          block: from_height.into(),
+          index: 0,
+        }
+        .store()..=BlockId {
+          block: to_height.into(),
+          index: u32::MAX,
+        }
+        .store(),
+      )?;
+
+      let mut writer = table("rune_events")?;
+      let mut credited = BTreeSet::new();
+      for result in events {
+        let (_id, entry) = result?;
+        let entry = RuneEventEntry::load(entry.value());
+        if !full && entry.event == RuneEvent::Transfer {
+          if let Ok(vout) = u32::try_from(entry.vout) {
+            credited.insert(OutPoint {
+              txid: entry.txid,
+              vout,
+            });
+          }
+        }
+        writer.row(RuneEventResponse::from(entry))?;
+        if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+          break;
+        }
+      }
+      written.push(("rune_events", writer.finish()?));
+
+      let mut writer = table("rune_balances")?;
+      let outpoint_to_rune_balances = rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+      for result in outpoint_to_rune_balances.iter()? {
+        let (outpoint, balances) = result?;
+        let outpoint = OutPoint::load(*outpoint.value());
+
+        if !full && !credited.contains(&outpoint) {
+          continue;
+        }
+
+        let balances = balances.value();
+        let mut i = 0;
+        while i < balances.len() {
+          let ((rune_id, amount), length) = Index::decode_rune_balance(&balances[i..])?;
+          i += length;
+          writer.row(RuneBalanceRow {
+            outpoint,
+            rune_id,
+            amount: amount.to_string(),
+          })?;
+        }
+      }
+      written.push(("rune_balances", writer.finish()?));
+    }
+
+    if self.settings.index_inscriptions() {
+      let sequence_number_to_inscription_entry =
+        rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+      let sequence_number_to_satpoint = rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+
+      // inscription contents are read from reveal transactions, which are in
+      // the index if it was built with `--index-transactions`, and otherwise
+      // are fetched from Bitcoin Core, one request per reveal transaction
+      let transaction_id_to_transaction = if self.index_transactions {
+        Some(rtx.open_table(TRANSACTION_ID_TO_TRANSACTION)?)
+      } else {
+        None
+      };
+
+      // inscriptions revealed together have consecutive sequence numbers, so
+      // the last reveal transaction is kept for the next row
+      let mut reveal: Option<(Txid, Option<Transaction>)> = None;
+
+      let mut writer = table("inscriptions")?;
+      for result in sequence_number_to_inscription_entry.iter()? {
+        let (sequence_number, entry) = result?;
+        let sequence_number = sequence_number.value();
+        let entry = InscriptionEntry::load(entry.value());
+
+        if !range.contains(&entry.height) {
+          continue;
+        }
+
+        let parents = entry
+          .parents
+          .iter()
+          .map(|parent| {
+            Ok(
+              InscriptionEntry::load(
+                sequence_number_to_inscription_entry
+                  .get(parent)?
+                  .unwrap()
+                  .value(),
+              )
+              .id,
+            )
+          })
+          .collect::<Result<Vec<InscriptionId>>>()?;
+
+        let txid = entry.id.txid;
+
+        if reveal.as_ref().map(|(reveal, _)| *reveal) != Some(txid) {
+          let transaction = match &transaction_id_to_transaction {
+            Some(table) => table
+              .get(&txid.store())?
+              .map(|transaction| consensus::encode::deserialize(transaction.value()))
+              .transpose()?,
+            None => None,
+          };
+
+          let transaction = match transaction {
+            Some(transaction) => Some(transaction),
+            None => self.get_transaction(txid)?,
+          };
+
+          reveal = Some((txid, transaction));
+        }
+
+        let inscription = reveal
+          .as_ref()
+          .and_then(|(_, transaction)| transaction.as_ref())
+          .and_then(|transaction| {
+            ParsedEnvelope::from_transaction(transaction)
+              .into_iter()
+              .nth(entry.id.index.try_into().unwrap())
+              .map(|envelope| envelope.payload)
+          });
+
+        writer.row(InscriptionRow {
+          sequence_number,
+          id: entry.id,
+          number: entry.inscription_number,
+          height: entry.height,
+          timestamp: entry.timestamp,
+          fee: entry.fee,
+          sat: entry.sat,
+          satpoint: SatPoint::load(
+            *sequence_number_to_satpoint
+              .get(sequence_number)?
+              .unwrap()
+              .value(),
+          ),
+          charms: Charm::charms(entry.charms),
+          parents,
+          content_type: inscription
+            .as_ref()
+            .and_then(|inscription| inscription.content_type())
+            .map(str::to_string),
+          content_length: inscription
+            .as_ref()
+            .and_then(|inscription| inscription.content_length()),
+          metaprotocol: inscription
+            .as_ref()
+            .and_then(|inscription| inscription.metaprotocol())
+            .map(str::to_string),
+          metadata: inscription
+            .and_then(|inscription| inscription.metadata)
+            .map(hex::encode),
+        })?;
+
+        if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+          break;
+        }
+      }
+      written.push(("inscriptions", writer.finish()?));
+
+      let mut writer = table("inscription_transfers")?;
+      for result in rtx
+        .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_TRANSFER)?
+        .iter()?
+      {
+        let (_sequence_number, transfer) = result?;
+        let transfer = InscriptionTransfer::load(transfer.value());
+        if range.contains(&transfer.height) {
+          writer.row(transfer)?;
+        }
+      }
+      written.push(("inscription_transfers", writer.finish()?));
+    }
+
+    Ok(ExportInfo {
+      block_hash: header.block_hash(),
+      from_height,
+      tables: written
+        .into_iter()
+        .map(|(name, rows)| (name.into(), rows))
+        .collect(),
+      to_height,
+    })
+  }
+}
+
+// writes rows as JSON lines, or buffers them into row groups of
+// `ROW_GROUP_SIZE` rows, each written as one line mapping every column to an
+// array of its values
+struct TableWriter {
+  columns: Map<String, Value>,
+  format: ExportFormat,
+  group: usize,
+  inner: BufWriter<File>,
+  rows: u64,
+}
+
+impl TableWriter {
+  fn row(&mut self, row: impl Serialize) -> Result {
+    self.rows += 1;
+
+    match self.format {
+      ExportFormat::JsonLines => {
+        serde_json::to_writer(&mut self.inner, &row)?;
+        self.inner.write_all(b"\n")?;
+      }
+      ExportFormat::Columnar => {
+        let Value::Object(row) = serde_json::to_value(row)? else {
+          bail!("export row is not an object");
+        };
+
+        for (column, value) in row {
+          self
+            .columns
+            .entry(column)
+            .or_insert_with(|| Value::Array(vec![Value::Null; self.group]))
+            .as_array_mut()
+            .unwrap()
+            .push(value);
+        }
+
+        self.group += 1;
+
+        // columns missing from this row are null
+        for values in self.columns.values_mut() {
+          let values = values.as_array_mut().unwrap();
+          if values.len() < self.group {
+            values.push(Value::Null);
+          }
+        }
+
+        if self.group == ROW_GROUP_SIZE {
+          self.flush_group()?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  fn flush_group(&mut self) -> Result {
+    serde_json::to_writer(
+      &mut self.inner,
+      &serde_json::json!({
+        "rows": self.group,
+        "columns": mem::take(&mut self.columns),
+      }),
+    )?;
+    self.inner.write_all(b"\n")?;
+    self.group = 0;
+    Ok(())
+  }
+
+  fn finish(mut self) -> Result<u64> {
+    if self.group > 0 {
+      self.flush_group()?;
+    }
+
+    self.inner.flush()?;
+
+    Ok(self.rows)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::testing::Context};
+
+  const RUNE: u128 = 99246114928149462;
+
+  fn read(path: &Path) -> Vec<Value> {
+    fs::read_to_string(path)
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn rune_tables_are_exported() {
+    let context = Context::builder()
+      .args(["--index-runes", "--no-index-inscriptions"])
+      .build();
+
+    let (txid, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let dir = context.tempdir.path().join("export");
+
+    let info = context
+      .index
+      .export_tables(&dir, ExportFormat::JsonLines, None, None)
+      .unwrap();
+
+    assert_eq!(info.from_height, 0);
+    assert_eq!(info.to_height, context.index.block_count().unwrap() - 1);
+    assert_eq!(
+      info.tables,
+      [
+        ("rune_balances".into(), 1),
+        ("rune_events".into(), 1),
+        ("runes".into(), 1),
+      ]
+      .into(),
+    );
+
+    let runes = read(&dir.join("runes.jsonl"));
+    assert_eq!(runes[0]["id"], id.to_string());
+    assert_eq!(runes[0]["etching"], txid.to_string());
+
+    let balances = read(&dir.join("rune_balances.jsonl"));
+    assert_eq!(balances[0]["outpoint"], format!("{txid}:0"));
+    assert_eq!(balances[0]["rune_id"], id.to_string());
+
+    let events = read(&dir.join("rune_events.jsonl"));
+    assert_eq!(events[0]["event"], "Transfer");
+    assert_eq!(events[0]["delta"], u128::MAX.to_string());
+
+    context.mine_blocks(1);
+
+    // nothing was etched or transferred after the etching block
+    let info = context
+      .index
+      .export_tables(
+        &dir,
+        ExportFormat::JsonLines,
+        Some(u32::try_from(id.block).unwrap() + 1),
+        None,
+      )
+      .unwrap();
+
+    assert!(info.tables.values().all(|rows| *rows == 0));
+    assert!(read(&dir.join("rune_balances.jsonl")).is_empty());
+  }
+
+  #[test]
+  fn columnar_export_writes_rune_amounts_as_strings() {
+    let context = Context::builder()
+      .args(["--index-runes", "--no-index-inscriptions"])
+      .build();
+
+    let (txid, _id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let dir = context.tempdir.path().join("export");
+
+    context
+      .index
+      .export_tables(&dir, ExportFormat::Columnar, None, None)
+      .unwrap();
+
+    let runes = read(&dir.join("runes.columns.jsonl"));
+    assert_eq!(runes[0]["rows"], 1);
+    assert_eq!(
+      runes[0]["columns"]["premine"],
+      serde_json::json!([u128::MAX.to_string()]),
+    );
+    assert_eq!(runes[0]["columns"]["etching"], serde_json::json!([txid]));
+
+    let balances = read(&dir.join("rune_balances.columns.jsonl"));
+    assert_eq!(
+      balances[0]["columns"]["amount"],
+      serde_json::json!([u128::MAX.to_string()]),
+    );
+  }
+
+  #[test]
+  fn inscriptions_are_exported_from_indexed_transactions() {
+    let Context {
+      index,
+      core,
+      tempdir,
+    } = Context::builder().arg("--index-transactions").build();
+
+    core.mine_blocks(1);
+
+    let txid = core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    core.mine_blocks(1);
+
+    index.update().unwrap();
+
+    // reveal transactions are not fetched from core
+    drop(core);
+
+    let dir = tempdir.path().join("export");
+
+    index
+      .export_tables(&dir, ExportFormat::JsonLines, None, None)
+      .unwrap();
+
+    let inscriptions = read(&dir.join("inscriptions.jsonl"));
+    assert_eq!(
+      inscriptions[0]["id"],
+      InscriptionId { txid, index: 0 }.to_string()
+    );
+    assert_eq!(inscriptions[0]["content_type"], "text/plain");
+    assert_eq!(inscriptions[0]["content_length"], 5);
+  }
+
+  #[test]
+  fn from_height_must_not_exceed_to_height() {
+    let context = Context::builder().arg("--index-runes").build();
+
+    context.mine_blocks(2);
+
+    assert_eq!(
+      context
+        .index
+        .export_tables(
+          &context.tempdir.path().join("export"),
+          ExportFormat::JsonLines,
+          Some(2),
+          Some(1),
+        )
+        .unwrap_err()
+        .to_string(),
+      "from height 2 is greater than to height 1",
+    );
+  }
+
+  #[test]
+  fn columnar_rows_are_grouped_by_column() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+
+    let path = tempdir.path().join("table.columns.jsonl");
+
+    let mut writer = TableWriter {
+      columns: Map::new(),
+      format: ExportFormat::Columnar,
+      group: 0,
+      inner: BufWriter::new(File::create(&path).unwrap()),
+      rows: 0,
+    };
+
+    writer.row(serde_json::json!({ "a": 1 })).unwrap();
+    writer.row(serde_json::json!({ "a": 2, "b": "x" })).unwrap();
+    writer.row(serde_json::json!({ "b": "y" })).unwrap();
+
+    assert_eq!(writer.finish().unwrap(), 3);
+
+    assert_eq!(
+      read(&path),
+      [serde_json::json!({
+        "rows": 3,
+        "columns": {
+          "a": [1, 2, null],
+          "b": [null, "x", "y"],
+        },
+      })],
+    );
+  }
+}
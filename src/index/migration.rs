@@ -0,0 +1,162 @@
+use super::*;
+
+/// Owners of rune events, keyed by script pubkey hash alone before schema 34
+const LEGACY_SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT: MultimapTableDefinition<
+  &ScriptPubkeyHash,
+  BlockIdValue,
+> = MultimapTableDefinition::new("SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT");
+
+pub(crate) struct Migration {}
+
+impl Migration {
+  // schema 31 redefines rune events as exact credits and debits, which can't
+  // be derived from the events of earlier schemas, so older indexes must be
+  // rebuilt
+  pub(crate) fn can_migrate(schema_version: u64) -> bool {
+    (31..SCHEMA_VERSION).contains(&schema_version)
+  }
+
+  pub(crate) fn migrate(
+    database: &Database,
+    durability: redb::Durability,
+    schema_version: u64,
+  ) -> Result {
+    log::info!("migrating index from schema {schema_version} to schema {SCHEMA_VERSION}");
+
+    let mut wtx = database.begin_write()?;
+
+    wtx.set_durability(durability);
+
+    // savepoints hold tables in the old format and can no longer be restored
+    let savepoints = wtx.list_persistent_savepoints()?.collect::<Vec<u64>>();
+
+    if !savepoints.is_empty() {
+      log::warn!(
+        "deleting {} savepoints made with schema {schema_version}, reorgs can't be recovered from until new savepoints are made",
+        savepoints.len(),
+      );
+    }
+
+    for savepoint in savepoints {
+      wtx.delete_persistent_savepoint(savepoint)?;
+    }
+
+    // schema 32 adds the address index tables, which stay empty since the
+    // address index can only be enabled when an index is created
+    wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+    wtx.open_table(OUTPOINT_TO_TXOUT)?;
+
+    if schema_version < 34 {
+      Self::index_rune_event_script_pubkeys(&wtx)?;
+    }
+
+    if schema_version < 33 {
+      Self::index_rune_holders(&wtx)?;
+    }
+
+    // schema 35 adds EVENT_ID_TO_EVENT, which only holds events of blocks
+    // indexed from now on
+    if schema_version < 35 {
+      wtx.open_table(EVENT_ID_TO_EVENT)?;
+
+      let block_count = wtx
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .last()?
+        .map(|(height, _header)| u64::from(height.value()) + 1)
+        .unwrap_or(0);
+
+      Index::set_statistic(
+        &mut wtx.open_table(STATISTIC_TO_COUNT)?,
+        Statistic::EventLogStart,
+        block_count,
+      )?;
+    }
+
+    // schema 36 moves the webhook outbox and dead letters into the index
+    if schema_version < 36 {
+      wtx.open_table(WEBHOOK_DEAD_LETTER)?;
+      wtx.open_table(WEBHOOK_OUTBOX)?;
+    }
+
+    if schema_version < 37 {
+      Self::index_rune_event_totals(&wtx)?;
+    }
+
+    Index::set_statistic(
+      &mut wtx.open_table(STATISTIC_TO_COUNT)?,
+      Statistic::Schema,
+      SCHEMA_VERSION,
+    )?;
+
+    wtx.commit()?;
+
+    log::info!("successfully migrated index to schema {SCHEMA_VERSION}");
+
+    Ok(())
+  }
+
+  // schema 34 keys SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT by script pubkey hash and
+  // block ID, so an owner's events can be read from a cursor onwards
+  fn index_rune_event_script_pubkeys(wtx: &WriteTransaction) -> Result {
+    if wtx
+      .list_multimap_tables()?
+      .any(|handle| handle.name() == LEGACY_SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT.name())
+    {
+      wtx.delete_multimap_table(LEGACY_SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT)?;
+    }
+
+    let mut script_pubkey_hash_to_rune_event = wtx.open_table(SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT)?;
+
+    for result in wtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?.iter()? {
+      let (id, entry) = result?;
+      let entry = RuneEventEntry::load(entry.value());
+
+      Index::index_rune_event_script_pubkey(
+        &mut script_pubkey_hash_to_rune_event,
+        &entry.script_pubkey,
+        BlockId::load(id.value()),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  // schema 33 adds RUNE_ID_TO_HOLDER_BALANCE and RUNE_ID_TO_HOLDER_COUNT,
+  // which are built by replaying balance changes in block order
+  fn index_rune_holders(wtx: &WriteTransaction) -> Result {
+    let mut rune_id_to_holder_balance = wtx.open_table(RUNE_ID_TO_HOLDER_BALANCE)?;
+    let mut rune_id_to_holder_count = wtx.open_table(RUNE_ID_TO_HOLDER_COUNT)?;
+
+    for result in wtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?.iter()? {
+      let (_, entry) = result?;
+      let entry = RuneEventEntry::load(entry.value());
+
+      if entry.event.is_balance_change() {
+        Index::update_rune_holder(
+          &mut rune_id_to_holder_balance,
+          &mut rune_id_to_holder_count,
+          &entry,
+        )?;
+      }
+    }
+
+    Ok(())
+  }
+
+  // schema 37 adds RUNE_ID_TO_EVENT_TOTALS, which is built by summing every
+  // event of each rune
+  fn index_rune_event_totals(wtx: &WriteTransaction) -> Result {
+    let mut rune_id_to_event_totals = wtx.open_table(RUNE_ID_TO_EVENT_TOTALS)?;
+
+    for result in wtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?.iter()? {
+      let (_, entry) = result?;
+
+      Index::update_rune_event_totals(
+        &mut rune_id_to_event_totals,
+        &RuneEventEntry::load(entry.value()),
+      )?;
+    }
+
+    Ok(())
+  }
+}
@@ -1,4 +1,8 @@
-use {super::*, updater::BlockData};
+use {
+  super::*,
+  crate::indexer::reorg_log::{ReorgLogEntry, ReorgLogEntryValue},
+  updater::BlockData,
+};
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum Error {
@@ -38,9 +42,8 @@ impl Reorg {
         for depth in 1..max_recoverable_reorg_depth {
           let index_block_hash = index.block_hash(height.checked_sub(depth))?;
           let bitcoind_block_hash = index
-            .client
-            .get_block_hash(u64::from(height.saturating_sub(depth)))
-            .into_option()?;
+            .block_source
+            .block_hash(height.saturating_sub(depth))?;
 
           if index_block_hash == bitcoind_block_hash {
             return Err(anyhow!(reorg::Error::Recoverable { height, depth }));
@@ -60,16 +63,153 @@ impl Reorg {
       panic!("set index durability to `Durability::Immediate` to test reorg handling");
     }
 
+    // @br-indexer: the index as of before the rollback, to look up the hashes
+    // of rolled back blocks once the restored savepoint shows where they start
+    let rtx = index.begin_read()?;
+
+    let old_block_count = rtx.block_count()?;
+
     let mut wtx = index.begin_write()?;
 
-    let oldest_savepoint =
-      wtx.get_persistent_savepoint(wtx.list_persistent_savepoints()?.min().unwrap())?;
+    // the reorg log is rolled back along with everything else, keep a copy of
+    // it to write back after restoring
+    let reorgs = wtx
+      .open_table(SEQUENCE_NUMBER_TO_REORG)?
+      .iter()?
+      .map(|result| result.map(|(sequence_number, entry)| (sequence_number.value(), entry.value())))
+      .collect::<Result<Vec<(u32, ReorgLogEntryValue)>, StorageError>>()?;
+
+    // @br-indexer: so are webhook deliveries, which must neither be repeated
+    // nor lost
+    let webhook_outbox = wtx
+      .open_table(WEBHOOK_OUTBOX)?
+      .iter()?
+      .map(|result| result.map(|(key, delivery)| (key.value(), delivery.value().to_vec())))
+      .collect::<Result<Vec<((u32, u64), Vec<u8>)>, StorageError>>()?;
+
+    let webhook_dead_letters = wtx
+      .open_table(WEBHOOK_DEAD_LETTER)?
+      .iter()?
+      .map(|result| result.map(|(id, delivery)| (id.value(), delivery.value().to_vec())))
+      .collect::<Result<Vec<(u64, Vec<u8>)>, StorageError>>()?;
+
+    let next_webhook_delivery = wtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::NextWebhookDelivery.key())?
+      .map(|id| id.value())
+      .unwrap_or_default();
+
+    // savepoints are deleted when the index is migrated to a new schema
+    let Some(oldest_savepoint) = wtx.list_persistent_savepoints()?.min() else {
+      bail!("no savepoint to roll back to after reorg at height {height}, consider deleting and rebuilding the index");
+    };
+
+    let oldest_savepoint = wtx.get_persistent_savepoint(oldest_savepoint)?;
 
     wtx.restore_savepoint(&oldest_savepoint)?;
 
+    let start_height = wtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, _header)| height.value() + 1)
+      .unwrap_or(0);
+
+    // every block above the savepoint is rolled back, not just those above
+    // the fork point
+    let mut orphaned_blocks = Vec::new();
+    for result in rtx
+      .0
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .range(start_height..old_block_count)?
+    {
+      let (orphaned_height, header) = result?;
+      orphaned_blocks.push((
+        orphaned_height.value(),
+        Header::load(*header.value()).block_hash(),
+      ));
+    }
+
+    drop(rtx);
+
+    let entry = ReorgLogEntry {
+      height,
+      depth,
+      start_height,
+      end_height: old_block_count.saturating_sub(1),
+      orphaned_blocks,
+      timestamp: SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default(),
+    };
+
+    {
+      let mut sequence_number_to_reorg = wtx.open_table(SEQUENCE_NUMBER_TO_REORG)?;
+
+      for (sequence_number, value) in reorgs {
+        sequence_number_to_reorg.insert(sequence_number, value)?;
+      }
+
+      let next_sequence_number = sequence_number_to_reorg
+        .last()?
+        .map(|(sequence_number, _)| sequence_number.value() + 1)
+        .unwrap_or(0);
+
+      sequence_number_to_reorg.insert(next_sequence_number, entry.clone().store())?;
+    }
+
+    let event = Event::Reorg {
+      block_height: entry.height,
+      depth: entry.depth,
+      end_height: entry.end_height,
+      orphaned_blocks: entry.orphaned_blocks,
+      start_height: entry.start_height,
+    };
+
+    {
+      let mut outbox = wtx.open_table(WEBHOOK_OUTBOX)?;
+
+      outbox.retain(|_, _| false)?;
+
+      for (key, delivery) in webhook_outbox {
+        outbox.insert(key, delivery.as_slice())?;
+      }
+
+      let mut dead_letter = wtx.open_table(WEBHOOK_DEAD_LETTER)?;
+
+      dead_letter.retain(|_, _| false)?;
+
+      for (id, delivery) in webhook_dead_letters {
+        dead_letter.insert(id, delivery.as_slice())?;
+      }
+    }
+
+    {
+      let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+
+      statistic_to_count.insert(
+        &Statistic::NextWebhookDelivery.key(),
+        &next_webhook_delivery,
+      )?;
+
+      index.enqueue_webhook_deliveries(
+        &wtx,
+        &mut statistic_to_count,
+        std::slice::from_ref(&event),
+        |_| None,
+        |_| Ok(Vec::new()),
+      )?;
+    }
+
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
+    if let Some(sender) = index.event_sender.as_ref() {
+      sender.blocking_send(event)?;
+    }
+
     log::info!(
       "successfully rolled back database to height {}",
       index.begin_read()?.block_count()?
@@ -84,16 +224,7 @@ impl Reorg {
     }
 
     if (height < SAVEPOINT_INTERVAL || height % SAVEPOINT_INTERVAL == 0)
-      && u32::try_from(
-        index
-          .settings
-          .bitcoin_rpc_client(None)?
-          .get_blockchain_info()?
-          .headers,
-      )
-      .unwrap()
-      .saturating_sub(height)
-        <= CHAIN_TIP_DISTANCE
+      && index.block_source.block_count()?.saturating_sub(height) <= CHAIN_TIP_DISTANCE
     {
       let wtx = index.begin_write()?;
 
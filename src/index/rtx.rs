@@ -43,4 +43,35 @@ impl Rtx {
       .map(|header| Header::load(*header.value()).block_hash()),
     )
   }
+
+  // @br-indexer: hash of the block at `height`, if indexed
+  pub(crate) fn block_hash_at(&self, height: u64) -> Result<Option<BlockHash>> {
+    match u32::try_from(height) {
+      Ok(height) => self.block_hash(Some(height)),
+      Err(_) => Ok(None),
+    }
+  }
+
+  // @br-indexer: hashes of the indexed blocks at `heights`, heights above the
+  // index tip are left out
+  pub(crate) fn block_hashes(
+    &self,
+    heights: impl IntoIterator<Item = u32>,
+  ) -> Result<BTreeMap<u32, BlockHash>> {
+    let height_to_block_header = self.0.open_table(HEIGHT_TO_BLOCK_HEADER)?;
+
+    let mut block_hashes = BTreeMap::new();
+
+    for height in heights {
+      if block_hashes.contains_key(&height) {
+        continue;
+      }
+
+      if let Some(header) = height_to_block_header.get(height)? {
+        block_hashes.insert(height, Header::load(*header.value()).block_hash());
+      }
+    }
+
+    Ok(block_hashes)
+  }
 }
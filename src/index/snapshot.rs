@@ -0,0 +1,656 @@
+use {
+  super::*,
+  bitcoin::hashes::{sha256, HashEngine},
+  redb::{Key, ReadTransaction, Value},
+  std::{
+    fs::File,
+    io::{BufReader, Read},
+  },
+  tempfile::NamedTempFile,
+};
+
+const MAGIC: &[u8] = b"ord snapshot 1\n";
+
+/// Summary of a snapshot. `hash` commits to the snapshot's chain, height,
+/// block hash and every entry, so nodes can compare snapshots by hash.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+  pub block_hash: BlockHash,
+  pub chain: Chain,
+  pub entries: u64,
+  pub hash: sha256::Hash,
+  pub height: u32,
+}
+
+// everything a rune-only index needs to continue from the next block
+macro_rules! for_each_snapshot_table {
+  ($table:ident, $multimap_table:ident) => {
+    $table!(HEIGHT_TO_BLOCK_HEADER);
+    $table!(STATISTIC_TO_COUNT);
+    $table!(RUNE_ID_TO_RUNE_ENTRY);
+    $table!(RUNE_TO_RUNE_ID);
+    $table!(OUTPOINT_TO_RUNE_BALANCES);
+    $table!(SEQUENCE_NUMBER_TO_RUNE_ID);
+    $table!(TRANSACTION_ID_TO_RUNE);
+    $table!(BLOCK_ID_TO_RUNE_EVENT);
+    $table!(LAST_BLOCK_ID_TO_RUNE_CHANGES);
+    $table!(OUTPOINT_TO_SCRIPT_PUBKEY);
+    $table!(SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT);
+    $table!(RUNE_ID_TO_RUNE_EVENT);
+    $table!(SEQUENCE_NUMBER_TO_REORG);
+    $multimap_table!(SCRIPT_PUBKEY_TO_OUTPOINT);
+    $table!(OUTPOINT_TO_TXOUT);
+    $table!(RUNE_ID_TO_HOLDER_BALANCE);
+    $table!(RUNE_ID_TO_HOLDER_COUNT);
+    $table!(EVENT_ID_TO_EVENT);
+    $table!(RUNE_ID_TO_EVENT_TOTALS);
+  };
+}
+
+impl Index {
+  pub(crate) fn create_snapshot(&self, path: &Path) -> Result<SnapshotInfo> {
+    self.ensure_snapshot_compatible()?;
+
+    let rtx = self.database.begin_read()?;
+
+    let Some((height, header)) = rtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .last()?
+      .map(|(height, header)| (height.value(), Header::load(*header.value())))
+    else {
+      bail!("cannot create snapshot of empty index");
+    };
+
+    // written to a temporary file in the same directory and renamed into
+    // place, so a failed or interrupted create never leaves a partial snapshot
+    // at `path`
+    let dir = path
+      .parent()
+      .filter(|parent| !parent.as_os_str().is_empty())
+      .unwrap_or(Path::new("."));
+
+    let mut writer = Writer {
+      engine: sha256::Hash::engine(),
+      entries: 0,
+      inner: BufWriter::new(
+        NamedTempFile::new_in(dir)
+          .with_context(|| format!("failed to create temporary file in `{}`", dir.display()))?,
+      ),
+    };
+
+    let chain = self.settings.chain();
+
+    writer.write(MAGIC)?;
+    writer.bytes(chain.to_string().as_bytes())?;
+    writer.write(&height.to_le_bytes())?;
+    writer.write(header.block_hash().as_byte_array())?;
+
+    macro_rules! table {
+      ($table:ident) => {
+        writer.table(&rtx, $table)?;
+      };
+    }
+
+    macro_rules! multimap_table {
+      ($table:ident) => {
+        writer.multimap_table(&rtx, $table)?;
+      };
+    }
+
+    for_each_snapshot_table!(table, multimap_table);
+
+    let hash = sha256::Hash::from_engine(writer.engine);
+
+    writer.inner.write_all(hash.as_byte_array())?;
+
+    let file = writer.inner.into_inner()?;
+    file.as_file().sync_all()?;
+    file
+      .persist(path)
+      .with_context(|| format!("failed to create `{}`", path.display()))?;
+
+    Ok(SnapshotInfo {
+      block_hash: header.block_hash(),
+      chain,
+      entries: writer.entries,
+      hash,
+      height,
+    })
+  }
+
+  /// Loads the snapshot at `path`, which must hash to `expected`, the hash
+  /// reported by `create_snapshot` on a trusted index.
+  pub(crate) fn load_snapshot(&self, path: &Path, expected: sha256::Hash) -> Result<SnapshotInfo> {
+    self.ensure_snapshot_compatible()?;
+
+    ensure!(
+      self.block_count()? == 0,
+      "cannot load snapshot into non-empty index at `{}`",
+      self.path.display(),
+    );
+
+    let mut reader = Reader {
+      engine: sha256::Hash::engine(),
+      entries: 0,
+      inner: BufReader::new(
+        File::open(path).with_context(|| format!("failed to open `{}`", path.display()))?,
+      ),
+    };
+
+    ensure!(
+      reader.read::<{ MAGIC.len() }>()? == MAGIC,
+      "`{}` is not an ord snapshot",
+      path.display(),
+    );
+
+    let chain = self.settings.chain();
+
+    let snapshot_chain = String::from_utf8(reader.bytes()?)?;
+
+    ensure!(
+      snapshot_chain == chain.to_string(),
+      "snapshot is for {snapshot_chain} but ord is on {chain}",
+    );
+
+    let height = u32::from_le_bytes(reader.read()?);
+    let block_hash = BlockHash::from_byte_array(reader.read()?);
+
+    let wtx = self.begin_write()?;
+
+    let statistics = |wtx: &WriteTransaction| -> Result<Vec<u64>> {
+      let table = wtx.open_table(STATISTIC_TO_COUNT)?;
+      [
+        Statistic::Schema,
+        Statistic::IndexAddresses,
+        Statistic::IndexEvents,
+        Statistic::IndexRunes,
+        Statistic::IndexSats,
+        Statistic::IndexSpentSats,
+        Statistic::IndexTransactions,
+      ]
+      .into_iter()
+      .map(|statistic| {
+        Ok(
+          table
+            .get(&statistic.key())?
+            .map(|count| count.value())
+            .unwrap_or_default(),
+        )
+      })
+      .collect()
+    };
+
+    let settings = statistics(&wtx)?;
+
+    macro_rules! table {
+      ($table:ident) => {
+        reader.table(&wtx, $table)?;
+      };
+    }
+
+    macro_rules! multimap_table {
+      ($table:ident) => {
+        reader.multimap_table(&wtx, $table)?;
+      };
+    }
+
+    for_each_snapshot_table!(table, multimap_table);
+
+    let hash = sha256::Hash::from_engine(reader.engine);
+
+    ensure!(
+      sha256::Hash::from_byte_array(read_array(&mut reader.inner)?) == hash,
+      "snapshot hash does not match contents, snapshot may be corrupt",
+    );
+
+    ensure!(
+      reader.inner.read(&mut [0])? == 0,
+      "unexpected data after snapshot hash",
+    );
+
+    ensure!(
+      hash == expected,
+      "snapshot hash {hash} does not match expected hash {expected}",
+    );
+
+    ensure!(
+      statistics(&wtx)? == settings,
+      "snapshot was created from an index with different settings or schema",
+    );
+
+    if let Some(hash) = self.client.get_block_hash(height.into()).into_option()? {
+      ensure!(
+        hash == block_hash,
+        "snapshot block {block_hash} at height {height} is not in the active chain",
+      );
+    }
+
+    wtx.commit()?;
+
+    Ok(SnapshotInfo {
+      block_hash,
+      chain,
+      entries: reader.entries,
+      hash,
+      height,
+    })
+  }
+
+  fn ensure_snapshot_compatible(&self) -> Result {
+    ensure!(
+      self.index_runes
+        && !self.settings.index_inscriptions()
+        && !self.index_sats
+        && !self.index_transactions,
+      "snapshots require a rune-only index, built with `--index-runes --no-index-inscriptions`",
+    );
+
+    Ok(())
+  }
+}
+
+struct Writer {
+  engine: sha256::HashEngine,
+  entries: u64,
+  inner: BufWriter<NamedTempFile>,
+}
+
+impl Writer {
+  fn write(&mut self, bytes: &[u8]) -> Result {
+    self.engine.input(bytes);
+    self.inner.write_all(bytes)?;
+    Ok(())
+  }
+
+  fn bytes(&mut self, bytes: &[u8]) -> Result {
+    self.write(&u32::try_from(bytes.len())?.to_le_bytes())?;
+    self.write(bytes)
+  }
+
+  fn entry(&mut self, key: &[u8], value: &[u8]) -> Result {
+    self.bytes(key)?;
+    self.bytes(value)?;
+    self.entries += 1;
+    Ok(())
+  }
+
+  fn table<K: Key + 'static, V: Value + 'static>(
+    &mut self,
+    rtx: &ReadTransaction,
+    definition: TableDefinition<K, V>,
+  ) -> Result {
+    let table = rtx.open_table(definition)?;
+
+    self.bytes(definition.name().as_bytes())?;
+    self.write(&table.len()?.to_le_bytes())?;
+
+    for entry in table.iter()? {
+      let (key, value) = entry?;
+      self.entry(
+        K::as_bytes(&key.value()).as_ref(),
+        V::as_bytes(&value.value()).as_ref(),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  fn multimap_table<K: Key + 'static, V: Key + 'static>(
+    &mut self,
+    rtx: &ReadTransaction,
+    definition: MultimapTableDefinition<K, V>,
+  ) -> Result {
+    let table = rtx.open_multimap_table(definition)?;
+
+    self.bytes(definition.name().as_bytes())?;
+    self.write(&table.len()?.to_le_bytes())?;
+
+    for entry in table.iter()? {
+      let (key, values) = entry?;
+      for value in values {
+        self.entry(
+          K::as_bytes(&key.value()).as_ref(),
+          V::as_bytes(&value?.value()).as_ref(),
+        )?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+struct Reader {
+  engine: sha256::HashEngine,
+  entries: u64,
+  inner: BufReader<File>,
+}
+
+impl Reader {
+  fn read<const N: usize>(&mut self) -> Result<[u8; N]> {
+    let bytes = read_array::<N>(&mut self.inner)?;
+    self.engine.input(&bytes);
+    Ok(bytes)
+  }
+
+  fn bytes(&mut self) -> Result<Vec<u8>> {
+    let len = u32::from_le_bytes(self.read()?);
+    let mut bytes = vec![0; len.try_into().unwrap()];
+    self.inner.read_exact(&mut bytes)?;
+    self.engine.input(&bytes);
+    Ok(bytes)
+  }
+
+  // returns the number of entries in the table
+  fn header(&mut self, name: &str) -> Result<u64> {
+    let table = String::from_utf8(self.bytes()?)?;
+
+    ensure!(
+      table == name,
+      "unexpected table {table} in snapshot, expected {name}",
+    );
+
+    Ok(u64::from_le_bytes(self.read()?))
+  }
+
+  fn table<K: Key + 'static, V: Value + 'static>(
+    &mut self,
+    wtx: &WriteTransaction,
+    definition: TableDefinition<K, V>,
+  ) -> Result {
+    let mut table = wtx.open_table(definition)?;
+
+    for _ in 0..self.header(definition.name())? {
+      let key = self.bytes()?;
+      let value = self.bytes()?;
+      table.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+      self.entries += 1;
+    }
+
+    Ok(())
+  }
+
+  fn multimap_table<K: Key + 'static, V: Key + 'static>(
+    &mut self,
+    wtx: &WriteTransaction,
+    definition: MultimapTableDefinition<K, V>,
+  ) -> Result {
+    let mut table = wtx.open_multimap_table(definition)?;
+
+    for _ in 0..self.header(definition.name())? {
+      let key = self.bytes()?;
+      let value = self.bytes()?;
+      table.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+      self.entries += 1;
+    }
+
+    Ok(())
+  }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+  let mut array = [0; N];
+  reader.read_exact(&mut array)?;
+  Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::testing::Context, tempfile::TempDir};
+
+  const RUNE: u128 = 99246114928149462;
+
+  fn context() -> Context {
+    Context::builder()
+      .args(["--index-runes", "--no-index-inscriptions"])
+      .build()
+  }
+
+  // opens a second, empty index against the same core
+  fn open(context: &Context, tempdir: &TempDir) -> Index {
+    Index::open(
+      &Settings::from_options(
+        Options::try_parse_from([
+          "ord",
+          "--regtest",
+          "--index-runes",
+          "--no-index-inscriptions",
+          "--bitcoin-rpc-url",
+          &context.core.url(),
+          "--cookie-file",
+          context.tempdir.path().join("cookie").to_str().unwrap(),
+          "--datadir",
+          tempdir.path().to_str().unwrap(),
+        ])
+        .unwrap(),
+      )
+      .or_defaults()
+      .unwrap(),
+    )
+    .unwrap()
+  }
+
+  fn etch(context: &Context) -> (Txid, RuneId) {
+    context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    )
+  }
+
+  #[test]
+  fn loaded_snapshot_continues_from_next_block() {
+    let context = context();
+
+    let (_txid, id) = etch(&context);
+
+    let snapshot = context.tempdir.path().join("snapshot");
+
+    let created = context.index.create_snapshot(&snapshot).unwrap();
+
+    assert_eq!(
+      created.height,
+      context.index.block_height().unwrap().unwrap().0
+    );
+    assert_eq!(created.chain, Chain::Regtest);
+
+    let tempdir = TempDir::new().unwrap();
+
+    let index = open(&context, &tempdir);
+
+    assert_eq!(
+      index.load_snapshot(&snapshot, created.hash).unwrap(),
+      created
+    );
+
+    assert_eq!(
+      fs::read_dir(context.tempdir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .filter(|name| name.to_str().unwrap().starts_with(".tmp"))
+        .count(),
+      0,
+    );
+
+    assert_eq!(index.block_count().unwrap(), created.height + 1);
+    assert_eq!(index.runes().unwrap(), context.index.runes().unwrap());
+
+    let transfer = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      outputs: 2,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 1000,
+            output: 1,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    index.update().unwrap();
+
+    assert_eq!(index.block_count().unwrap(), created.height + 2);
+
+    assert_eq!(
+      index.get_rune_balances().unwrap(),
+      [
+        (
+          OutPoint {
+            txid: transfer,
+            vout: 0
+          },
+          vec![(id, u128::MAX - 1000)]
+        ),
+        (
+          OutPoint {
+            txid: transfer,
+            vout: 1
+          },
+          vec![(id, 1000)]
+        ),
+      ]
+    );
+
+    // both indexes are in the same state, so snapshot to the same hash
+    assert_eq!(
+      index
+        .create_snapshot(&tempdir.path().join("snapshot"))
+        .unwrap(),
+      context.index.create_snapshot(&snapshot).unwrap(),
+    );
+  }
+
+  #[test]
+  fn corrupt_snapshot_is_not_loaded() {
+    let context = context();
+
+    etch(&context);
+
+    let snapshot = context.tempdir.path().join("snapshot");
+
+    let created = context.index.create_snapshot(&snapshot).unwrap();
+
+    let mut bytes = fs::read(&snapshot).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    fs::write(&snapshot, bytes).unwrap();
+
+    let tempdir = TempDir::new().unwrap();
+
+    let index = open(&context, &tempdir);
+
+    assert_eq!(
+      index
+        .load_snapshot(&snapshot, created.hash)
+        .unwrap_err()
+        .to_string(),
+      "snapshot hash does not match contents, snapshot may be corrupt",
+    );
+
+    assert_eq!(index.block_count().unwrap(), 0);
+    assert!(index.runes().unwrap().is_empty());
+  }
+
+  #[test]
+  fn tampered_snapshot_with_recomputed_hash_is_not_loaded() {
+    let context = context();
+
+    etch(&context);
+
+    let snapshot = context.tempdir.path().join("snapshot");
+
+    let created = context.index.create_snapshot(&snapshot).unwrap();
+
+    let mut bytes = fs::read(&snapshot).unwrap();
+
+    let rune = Rune(RUNE).store().to_le_bytes();
+
+    let position = bytes
+      .windows(rune.len())
+      .position(|window| window == rune)
+      .unwrap();
+
+    bytes[position] ^= 1;
+
+    let contents = bytes.len() - 32;
+    let hash = sha256::Hash::hash(&bytes[..contents]);
+    bytes[contents..].copy_from_slice(hash.as_byte_array());
+
+    fs::write(&snapshot, bytes).unwrap();
+
+    let tempdir = TempDir::new().unwrap();
+
+    let index = open(&context, &tempdir);
+
+    assert_eq!(
+      index
+        .load_snapshot(&snapshot, created.hash)
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "snapshot hash {hash} does not match expected hash {}",
+        created.hash
+      ),
+    );
+
+    assert_eq!(index.block_count().unwrap(), 0);
+    assert!(index.runes().unwrap().is_empty());
+  }
+
+  #[test]
+  fn snapshot_cannot_be_loaded_into_non_empty_index() {
+    let context = context();
+
+    let snapshot = context.tempdir.path().join("snapshot");
+
+    let created = context.index.create_snapshot(&snapshot).unwrap();
+
+    assert_eq!(
+      context
+        .index
+        .load_snapshot(&snapshot, created.hash)
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "cannot load snapshot into non-empty index at `{}`",
+        context
+          .tempdir
+          .path()
+          .join("regtest")
+          .join("index.redb")
+          .display()
+      ),
+    );
+  }
+
+  #[test]
+  fn snapshots_require_rune_only_index() {
+    let context = Context::builder().arg("--index-runes").build();
+
+    assert_eq!(
+      context
+        .index
+        .create_snapshot(&context.tempdir.path().join("snapshot"))
+        .unwrap_err()
+        .to_string(),
+      "snapshots require a rune-only index, built with `--index-runes --no-index-inscriptions`",
+    );
+  }
+}
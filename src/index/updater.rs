@@ -1,8 +1,7 @@
 use crate::indexer::constants::IndexerHeight;
 use {
   self::{inscription_updater::InscriptionUpdater, rune_updater::RuneUpdater},
-  super::{fetcher::Fetcher, *},
-  futures::future::try_join_all,
+  super::{block_files::BlockFiles, block_source::BlockSource, *},
   std::sync::mpsc,
   tokio::sync::mpsc::{error::TryRecvError, Receiver, Sender},
 };
@@ -13,6 +12,10 @@ mod rune_updater;
 pub(crate) struct BlockData {
   pub(crate) header: Header,
   pub(crate) txdata: Vec<(Transaction, Txid)>,
+  // @br-indexer: filled in by `Updater::preprocess_blocks`, and otherwise
+  // empty, in which case envelopes and artifacts are parsed while indexing
+  pub(crate) envelopes: Vec<Vec<ParsedEnvelope>>,
+  pub(crate) artifacts: Vec<Option<Artifact>>,
 }
 
 impl From<Block> for BlockData {
@@ -27,10 +30,84 @@ impl From<Block> for BlockData {
           (transaction, txid)
         })
         .collect(),
+      envelopes: Vec::new(),
+      artifacts: Vec::new(),
     }
   }
 }
 
+impl BlockData {
+  // parse envelopes and decipher runestones on `threads` threads, splitting
+  // transactions into contiguous chunks
+  fn preprocess(&mut self, envelopes: bool, artifacts: bool, threads: usize) {
+    if !envelopes && !artifacts {
+      return;
+    }
+
+    // not worth spawning threads for small blocks
+    let chunk_size = self.txdata.len().div_ceil(threads).max(64);
+
+    let chunks = thread::scope(|scope| {
+      self
+        .txdata
+        .chunks(chunk_size)
+        .map(|chunk| {
+          scope.spawn(move || {
+            chunk
+              .iter()
+              .map(|(tx, _txid)| {
+                (
+                  if envelopes {
+                    ParsedEnvelope::from_transaction(tx)
+                  } else {
+                    Vec::new()
+                  },
+                  artifacts.then(|| Runestone::decipher(tx)).flatten(),
+                )
+              })
+              .collect::<Vec<_>>()
+          })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>()
+    });
+
+    for (tx_envelopes, tx_artifact) in chunks.into_iter().flatten() {
+      if envelopes {
+        self.envelopes.push(tx_envelopes);
+      }
+
+      if artifacts {
+        self.artifacts.push(tx_artifact);
+      }
+    }
+  }
+
+  fn take_envelopes(
+    envelopes: &mut [Vec<ParsedEnvelope>],
+    i: usize,
+    tx: &Transaction,
+  ) -> Vec<ParsedEnvelope> {
+    envelopes
+      .get_mut(i)
+      .map(mem::take)
+      .unwrap_or_else(|| ParsedEnvelope::from_transaction(tx))
+  }
+
+  fn take_artifact(
+    artifacts: &mut [Option<Artifact>],
+    i: usize,
+    tx: &Transaction,
+  ) -> Option<Artifact> {
+    artifacts
+      .get_mut(i)
+      .map(Option::take)
+      .unwrap_or_else(|| Runestone::decipher(tx))
+  }
+}
+
 pub(crate) struct Updater<'index> {
   pub(super) height: u32,
   pub(super) index: &'index Index,
@@ -44,7 +121,7 @@ pub(crate) struct Updater<'index> {
 impl<'index> Updater<'index> {
   pub(crate) fn update_index(&mut self, mut wtx: WriteTransaction) -> Result {
     let start = Instant::now();
-    let starting_height = u32::try_from(self.index.client.get_block_count()?).unwrap() + 1;
+    let starting_height = self.index.block_source.block_count()? + 1;
     let starting_index_height = self.height;
     // @br-indexer: config height --> start
     if self.height == 0 {
@@ -84,7 +161,10 @@ impl<'index> Updater<'index> {
 
     let rx = Self::fetch_blocks_from(self.index, self.height, self.index.index_sats)?;
 
-    let (mut outpoint_sender, mut value_receiver) = Self::spawn_fetcher(&self.index.settings)?;
+    let rx = Self::preprocess_blocks(self.index, self.height, rx);
+
+    let (mut outpoint_sender, mut value_receiver) =
+      Self::spawn_fetcher(self.index.block_source.clone());
 
     let mut uncommitted = 0;
     let mut value_cache = HashMap::new();
@@ -101,8 +181,8 @@ impl<'index> Updater<'index> {
         progress_bar.inc(1);
 
         if progress_bar.position() > progress_bar.length().unwrap() {
-          if let Ok(count) = self.index.client.get_block_count() {
-            progress_bar.set_length(count + 1);
+          if let Ok(count) = self.index.block_source.block_count() {
+            progress_bar.set_length((count + 1).into());
           } else {
             log::warn!("Failed to fetch latest block height");
           }
@@ -170,29 +250,91 @@ impl<'index> Updater<'index> {
 
     let height_limit = index.height_limit;
 
-    let client = index.settings.bitcoin_rpc_client(None)?;
+    let block_source = index.block_source.clone();
 
     let first_inscription_height = index.first_inscription_height;
 
-    thread::spawn(move || loop {
-      if let Some(height_limit) = height_limit {
-        if height >= height_limit {
-          break;
+    let blocks_dir = index
+      .settings
+      .index_from_blk_files()
+      .then(|| index.settings.blocks_dir());
+
+    let network = index.settings.chain().network();
+
+    thread::spawn(move || {
+      // reading Core's block index only pays off when far behind
+      let blocks_dir = blocks_dir.filter(|_| {
+        block_source
+          .block_count()
+          .is_ok_and(|count| count.saturating_sub(height) >= if cfg!(test) { 1 } else { 1000 })
+      });
+
+      let mut block_files = blocks_dir.and_then(|blocks_dir| {
+        match block_source
+          .block_count()
+          .and_then(|count| block_source.block_hash(count))
+          .and_then(|tip| {
+            BlockFiles::open(
+              &blocks_dir,
+              network,
+              tip.context("chain tip not found in block source")?,
+            )
+          }) {
+          Ok(block_files) => {
+            log::info!(
+              "Reading {} blocks from `{}`",
+              block_files.height(),
+              blocks_dir.display()
+            );
+            Some(block_files)
+          }
+          Err(err) => {
+            log::warn!(
+              "failed to read blocks from `{}`, using block source instead: {err}",
+              blocks_dir.display()
+            );
+            None
+          }
         }
-      }
+      });
 
-      match Self::get_block_with_retries(&client, height, index_sats, first_inscription_height) {
-        Ok(Some(block)) => {
-          if let Err(err) = tx.send(block.into()) {
-            log::info!("Block receiver disconnected: {err}");
+      loop {
+        if let Some(height_limit) = height_limit {
+          if height >= height_limit {
             break;
           }
-          height += 1;
         }
-        Ok(None) => break,
-        Err(err) => {
-          log::error!("failed to fetch block {height}: {err}");
-          break;
+
+        let header_only = !index_sats && height < first_inscription_height;
+
+        let block = match block_files
+          .as_mut()
+          .and_then(|block_files| block_files.get(height, header_only).transpose())
+        {
+          Some(Ok(block)) => Ok(Some(block)),
+          Some(Err(err)) => {
+            log::warn!(
+              "failed to read block {height} from blk files, using block source instead: {err}"
+            );
+            block_files = None;
+            Self::get_block_with_retries(&*block_source, height, header_only)
+          }
+          None => Self::get_block_with_retries(&*block_source, height, header_only),
+        };
+
+        match block {
+          Ok(Some(block)) => {
+            if let Err(err) = tx.send(block.into()) {
+              log::info!("Block receiver disconnected: {err}");
+              break;
+            }
+            height += 1;
+          }
+          Ok(None) => break,
+          Err(err) => {
+            log::error!("failed to fetch block {height}: {err}");
+            break;
+          }
         }
       }
     });
@@ -200,31 +342,63 @@ impl<'index> Updater<'index> {
     Ok(rx)
   }
 
+  // @br-indexer: parse envelopes and decipher runestones of upcoming blocks
+  // while the current block is being indexed
+  fn preprocess_blocks(
+    index: &Index,
+    mut height: u32,
+    blocks: mpsc::Receiver<BlockData>,
+  ) -> mpsc::Receiver<BlockData> {
+    let threads = index.settings.index_threads();
+
+    if threads == 0 {
+      return blocks;
+    }
+
+    let (tx, rx) = mpsc::sync_channel(32);
+
+    let first_inscription_height = index.first_inscription_height;
+    let index_inscriptions = index.settings.index_inscriptions();
+    let first_rune_height = index.settings.first_rune_height();
+    let index_runes = index.index_runes;
+
+    thread::spawn(move || {
+      for mut block in blocks {
+        block.preprocess(
+          index_inscriptions && height >= first_inscription_height,
+          index_runes && height >= first_rune_height,
+          threads,
+        );
+
+        if let Err(err) = tx.send(block) {
+          log::info!("Block receiver disconnected: {err}");
+          break;
+        }
+
+        height += 1;
+      }
+    });
+
+    rx
+  }
+
   fn get_block_with_retries(
-    client: &Client,
+    block_source: &dyn BlockSource,
     height: u32,
-    index_sats: bool,
-    first_inscription_height: u32,
+    header_only: bool,
   ) -> Result<Option<Block>> {
     let mut errors = 0;
     loop {
-      match client
-        .get_block_hash(height.into())
-        .into_option()
-        .and_then(|option| {
-          option
-            .map(|hash| {
-              if index_sats || height >= first_inscription_height {
-                Ok(client.get_block(&hash)?)
-              } else {
-                Ok(Block {
-                  header: client.get_block_header(&hash)?,
-                  txdata: Vec::new(),
-                })
-              }
-            })
-            .transpose()
-        }) {
+      match if header_only {
+        block_source.block_header(height).map(|header| {
+          header.map(|header| Block {
+            header,
+            txdata: Vec::new(),
+          })
+        })
+      } else {
+        block_source.block(height)
+      } {
         Err(err) => {
           if cfg!(test) {
             return Err(err);
@@ -246,9 +420,7 @@ impl<'index> Updater<'index> {
     }
   }
 
-  fn spawn_fetcher(settings: &Settings) -> Result<(Sender<OutPoint>, Receiver<u64>)> {
-    let fetcher = Fetcher::new(settings)?;
-
+  fn spawn_fetcher(block_source: Arc<dyn BlockSource>) -> (Sender<OutPoint>, Receiver<u64>) {
     // Not sure if any block has more than 20k inputs, but none so far after first inscription block
     const CHANNEL_BUFFER_SIZE: usize = 20_000;
     let (outpoint_sender, mut outpoint_receiver) =
@@ -258,61 +430,85 @@ impl<'index> Updater<'index> {
     // Batch 2048 missing inputs at a time. Arbitrarily chosen for now, maybe higher or lower can be faster?
     // Did rudimentary benchmarks with 1024 and 4096 and time was roughly the same.
     const BATCH_SIZE: usize = 2048;
-    // Default rpcworkqueue in bitcoind is 16, meaning more than 16 concurrent requests will be rejected.
-    // Since we are already requesting blocks on a separate thread, and we don't want to break if anything
-    // else runs a request, we keep this to 12.
-    const PARALLEL_REQUESTS: usize = 12;
 
-    thread::spawn(move || {
-      let rt = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap();
-      rt.block_on(async move {
-        loop {
-          let Some(outpoint) = outpoint_receiver.recv().await else {
-            log::debug!("Outpoint channel closed");
+    thread::spawn(move || loop {
+      let Some(outpoint) = outpoint_receiver.blocking_recv() else {
+        log::debug!("Outpoint channel closed");
+        return;
+      };
+      // There's no try_iter on tokio::sync::mpsc::Receiver like std::sync::mpsc::Receiver.
+      // So we just loop until BATCH_SIZE doing try_recv until it returns None.
+      let mut outpoints = vec![outpoint];
+      for _ in 0..BATCH_SIZE - 1 {
+        let Ok(outpoint) = outpoint_receiver.try_recv() else {
+          break;
+        };
+        outpoints.push(outpoint);
+      }
+      let txs =
+        match block_source.transactions(outpoints.iter().map(|outpoint| outpoint.txid).collect()) {
+          Ok(txs) => txs,
+          Err(e) => {
+            log::error!("Couldn't receive txs {e}");
             return;
-          };
-          // There's no try_iter on tokio::sync::mpsc::Receiver like std::sync::mpsc::Receiver.
-          // So we just loop until BATCH_SIZE doing try_recv until it returns None.
-          let mut outpoints = vec![outpoint];
-          for _ in 0..BATCH_SIZE - 1 {
-            let Ok(outpoint) = outpoint_receiver.try_recv() else {
-              break;
-            };
-            outpoints.push(outpoint);
-          }
-          // Break outpoints into chunks for parallel requests
-          let chunk_size = (outpoints.len() / PARALLEL_REQUESTS) + 1;
-          let mut futs = Vec::with_capacity(PARALLEL_REQUESTS);
-          for chunk in outpoints.chunks(chunk_size) {
-            let txids = chunk.iter().map(|outpoint| outpoint.txid).collect();
-            let fut = fetcher.get_transactions(txids);
-            futs.push(fut);
-          }
-          let txs = match try_join_all(futs).await {
-            Ok(txs) => txs,
-            Err(e) => {
-              log::error!("Couldn't receive txs {e}");
-              return;
-            }
-          };
-          // Send all tx output values back in order
-          for (i, tx) in txs.iter().flatten().enumerate() {
-            let Ok(_) = value_sender
-              .send(tx.output[usize::try_from(outpoints[i].vout).unwrap()].value)
-              .await
-            else {
-              log::error!("Value channel closed unexpectedly");
-              return;
-            };
           }
-        }
-      })
+        };
+      // Send all tx output values back in order
+      for (i, tx) in txs.iter().enumerate() {
+        let Ok(_) =
+          value_sender.blocking_send(tx.output[usize::try_from(outpoints[i].vout).unwrap()].value)
+        else {
+          log::error!("Value channel closed unexpectedly");
+          return;
+        };
+      }
     });
 
-    Ok((outpoint_sender, value_receiver))
+    (outpoint_sender, value_receiver)
+  }
+
+  // @br-indexer: moves the outputs a transaction spends out of the address
+  // index, and the outputs it creates in. Transactions must be indexed in
+  // block order, so outputs spent in the block that created them are found.
+  fn index_transaction_addresses(
+    tx: &Transaction,
+    txid: Txid,
+    outpoint_to_txout: &mut Table<&OutPointValue, &[u8]>,
+    script_pubkey_to_outpoint: &mut MultimapTable<&[u8], &OutPointValue>,
+  ) -> Result {
+    for input in &tx.input {
+      let Some(tx_out) = outpoint_to_txout.remove(&input.previous_output.store())? else {
+        continue;
+      };
+
+      let tx_out: TxOut = consensus::encode::deserialize(tx_out.value())?;
+
+      script_pubkey_to_outpoint.remove(
+        tx_out.script_pubkey.as_bytes(),
+        &input.previous_output.store(),
+      )?;
+    }
+
+    for (vout, tx_out) in tx.output.iter().enumerate() {
+      // OP_RETURN outputs are unspendable and have no address
+      if tx_out.script_pubkey.is_op_return() {
+        continue;
+      }
+
+      let outpoint = OutPoint {
+        txid,
+        vout: vout.try_into().unwrap(),
+      };
+
+      outpoint_to_txout.insert(
+        &outpoint.store(),
+        consensus::encode::serialize(tx_out).as_slice(),
+      )?;
+
+      script_pubkey_to_outpoint.insert(tx_out.script_pubkey.as_bytes(), &outpoint.store())?;
+    }
+
+    Ok(())
   }
 
   fn index_block(
@@ -320,7 +516,7 @@ impl<'index> Updater<'index> {
     outpoint_sender: &mut Sender<OutPoint>,
     value_receiver: &mut Receiver<u64>,
     wtx: &mut WriteTransaction,
-    block: BlockData,
+    mut block: BlockData,
     value_cache: &mut HashMap<OutPoint, u64>,
   ) -> Result<()> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
@@ -342,6 +538,9 @@ impl<'index> Updater<'index> {
       return Err(anyhow!("Previous block did not consume all input values"));
     };
 
+    // @br-indexer: events are collected and logged once the block is indexed
+    let mut events = Vec::new();
+
     let mut outpoint_to_value = wtx.open_table(OUTPOINT_TO_VALUE)?;
 
     let index_inscriptions = self.height >= self.index.first_inscription_height
@@ -437,7 +636,7 @@ impl<'index> Updater<'index> {
       chain: self.index.settings.chain(),
       content_type_to_count: &mut content_type_to_count,
       cursed_inscription_count,
-      event_sender: self.index.event_sender.as_ref(),
+      events: &mut events,
       flotsam: Vec::new(),
       height: self.height,
       home_inscription_count,
@@ -459,6 +658,7 @@ impl<'index> Updater<'index> {
       timestamp: block.header.time,
       transaction_buffer: Vec::new(),
       transaction_id_to_transaction: &mut transaction_id_to_transaction,
+      transfers_in_block: 0,
       unbound_inscriptions,
       value_cache,
       value_receiver,
@@ -517,7 +717,8 @@ impl<'index> Updater<'index> {
           &mut sat_ranges_written,
           &mut outputs_in_block,
           &mut inscription_updater,
-          index_inscriptions,
+          index_inscriptions
+            .then(|| BlockData::take_envelopes(&mut block.envelopes, tx_offset, tx)),
         )?;
 
         coinbase_inputs.extend(input_sat_ranges);
@@ -532,7 +733,7 @@ impl<'index> Updater<'index> {
           &mut sat_ranges_written,
           &mut outputs_in_block,
           &mut inscription_updater,
-          index_inscriptions,
+          index_inscriptions.then(|| BlockData::take_envelopes(&mut block.envelopes, 0, tx)),
         )?;
       }
 
@@ -562,8 +763,10 @@ impl<'index> Updater<'index> {
         outpoint_to_sat_ranges.insert(&OutPoint::null().store(), lost_sat_ranges.as_slice())?;
       }
     } else if index_inscriptions {
-      for (tx, txid) in block.txdata.iter().skip(1).chain(block.txdata.first()) {
-        inscription_updater.index_inscriptions(tx, *txid, None)?;
+      let txdata = block.txdata.iter().enumerate();
+      for (tx_offset, (tx, txid)) in txdata.clone().skip(1).chain(txdata.take(1)) {
+        let envelopes = BlockData::take_envelopes(&mut block.envelopes, tx_offset, tx);
+        inscription_updater.index_inscriptions(tx, *txid, envelopes, None)?;
       }
     }
 
@@ -598,13 +801,19 @@ impl<'index> Updater<'index> {
 
     if self.index.index_runes && self.height >= self.index.settings.first_rune_height() {
       let mut outpoint_to_rune_balances = wtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+      let mut outpoint_to_script_pubkey = wtx.open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?; // @br-indexer
+      let mut rune_id_to_rune_event = wtx.open_table(RUNE_ID_TO_RUNE_EVENT)?; // @br-indexer
+      let mut rune_id_to_holder_balance = wtx.open_table(RUNE_ID_TO_HOLDER_BALANCE)?; // @br-indexer
+      let mut rune_id_to_holder_count = wtx.open_table(RUNE_ID_TO_HOLDER_COUNT)?; // @br-indexer
+      let mut rune_id_to_event_totals = wtx.open_table(RUNE_ID_TO_EVENT_TOTALS)?; // @br-indexer
+      let mut script_pubkey_hash_to_rune_event =
+        wtx.open_table(SCRIPT_PUBKEY_HASH_TO_RUNE_EVENT)?; // @br-indexer
       let mut rune_id_to_rune_entry = wtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
       let mut rune_to_rune_id = wtx.open_table(RUNE_TO_RUNE_ID)?;
       let mut sequence_number_to_rune_id = wtx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
       let mut transaction_id_to_rune = wtx.open_table(TRANSACTION_ID_TO_RUNE)?;
 
       let mut block_id_to_rune_event = wtx.open_table(BLOCK_ID_TO_RUNE_EVENT)?; // @br-indexer
-      let mut block_id_to_rune_spent = wtx.open_table(BLOCK_ID_TO_RUNE_SPENT)?; // @br-indexer
       let mut last_block_id_to_rune_changes = wtx.open_table(LAST_BLOCK_ID_TO_RUNE_CHANGES)?; // @br-indexer
 
       let runes = statistic_to_count
@@ -614,11 +823,11 @@ impl<'index> Updater<'index> {
 
       let mut rune_updater = RuneUpdater {
         chain: self.index.settings.chain(), // @br-indexer: config chain
-        event_sender: self.index.event_sender.as_ref(),
+        events: &mut events,
         block_time: block.header.time,
         burned: HashMap::new(),
         mints: HashMap::new(),
-        client: &self.index.client,
+        block_source: &*self.index.block_source,
         height: self.height,
         id_to_entry: &mut rune_id_to_rune_entry,
         inscription_id_to_sequence_number: &mut inscription_id_to_sequence_number,
@@ -627,24 +836,104 @@ impl<'index> Updater<'index> {
           Height(self.height),
         ),
         outpoint_to_balances: &mut outpoint_to_rune_balances,
+        outpoint_to_script_pubkey: &mut outpoint_to_script_pubkey,
+        script_pubkey_hash_to_rune_event: &mut script_pubkey_hash_to_rune_event,
+        rune_id_to_rune_event: &mut rune_id_to_rune_event,
+        rune_id_to_holder_balance: &mut rune_id_to_holder_balance,
+        rune_id_to_holder_count: &mut rune_id_to_holder_count,
+        rune_id_to_event_totals: &mut rune_id_to_event_totals,
         rune_to_id: &mut rune_to_rune_id,
         runes,
         sequence_number_to_rune_id: &mut sequence_number_to_rune_id,
         statistic_to_count: &mut statistic_to_count,
         transaction_id_to_rune: &mut transaction_id_to_rune,
         block_id_to_rune_event: &mut block_id_to_rune_event, // @todo br-indexer: config
-        block_id_to_rune_spent: &mut block_id_to_rune_spent, // @todo br-indexer: config
         last_block_id_to_rune_changes: &mut last_block_id_to_rune_changes, // @todo br-indexer: config
       };
 
       let mut block_index: u32 = 0;
       for (i, (tx, txid)) in block.txdata.iter().enumerate() {
-        rune_updater.index_runes(u32::try_from(i).unwrap(), tx, *txid, &mut block_index)?;
+        let artifact = BlockData::take_artifact(&mut block.artifacts, i, tx);
+        rune_updater.index_runes(
+          u32::try_from(i).unwrap(),
+          tx,
+          *txid,
+          artifact,
+          &mut block_index,
+        )?;
       }
 
       rune_updater.update()?;
     }
 
+    // @br-indexer
+    if self.index.index_addresses {
+      let mut outpoint_to_txout = wtx.open_table(OUTPOINT_TO_TXOUT)?;
+      let mut script_pubkey_to_outpoint = wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+
+      for (tx, txid) in &block.txdata {
+        Self::index_transaction_addresses(
+          tx,
+          *txid,
+          &mut outpoint_to_txout,
+          &mut script_pubkey_to_outpoint,
+        )?;
+      }
+    }
+
+    // @br-indexer
+    if !self.index.webhooks().is_empty() {
+      let outputs = block
+        .txdata
+        .iter()
+        .map(|(tx, txid)| (*txid, &tx.output))
+        .collect::<HashMap<Txid, &Vec<TxOut>>>();
+
+      self.index.enqueue_webhook_deliveries(
+        wtx,
+        &mut statistic_to_count,
+        &events,
+        |outpoint| {
+          outputs
+            .get(&outpoint.txid)
+            .and_then(|outputs| outputs.get(usize::try_from(outpoint.vout).ok()?))
+            .map(|output| output.script_pubkey.clone())
+        },
+        |inscription_id| {
+          let Some(sequence_number) =
+            inscription_id_to_sequence_number.get(&inscription_id.store())?
+          else {
+            return Ok(Vec::new());
+          };
+
+          let entry = InscriptionEntry::load(
+            sequence_number_to_inscription_entry
+              .get(sequence_number.value())?
+              .unwrap()
+              .value(),
+          );
+
+          entry
+            .parents
+            .iter()
+            .map(|parent| {
+              Ok(
+                InscriptionEntry::load(
+                  sequence_number_to_inscription_entry
+                    .get(parent)?
+                    .unwrap()
+                    .value(),
+                )
+                .id,
+              )
+            })
+            .collect()
+        },
+      )?;
+    }
+
+    self.record_events(wtx, &mut statistic_to_count, events)?;
+
     height_to_block_header.insert(&self.height, &block.header.store())?;
 
     self.height += 1;
@@ -658,6 +947,51 @@ impl<'index> Updater<'index> {
     Ok(())
   }
 
+  // @br-indexer: if the index keeps an event log, append the block's events
+  // to it, from which event stream subscribers resume, and drop events that
+  // have aged out of it. Then pass them on to in-process listeners.
+  fn record_events(
+    &self,
+    wtx: &WriteTransaction,
+    statistic_to_count: &mut Table<u64, u64>,
+    events: Vec<Event>,
+  ) -> Result {
+    if self.index.index_events {
+      let mut event_id_to_event = wtx.open_table(EVENT_ID_TO_EVENT)?;
+
+      for (index, event) in events.iter().enumerate() {
+        event_id_to_event.insert(
+          (self.height, u32::try_from(index).unwrap()),
+          serde_json::to_vec(event)?.as_slice(),
+        )?;
+      }
+
+      let log_start = statistic_to_count
+        .get(&Statistic::EventLogStart.key())?
+        .map(|start| start.value())
+        .unwrap_or_default();
+
+      let new_log_start = u64::from((self.height + 1).saturating_sub(EVENT_LOG_BLOCKS));
+
+      if new_log_start > log_start {
+        event_id_to_event.retain_in::<(u32, u32), _>(
+          (u32::try_from(log_start).unwrap(), 0)..(u32::try_from(new_log_start).unwrap(), 0),
+          |_, _| false,
+        )?;
+
+        statistic_to_count.insert(&Statistic::EventLogStart.key(), &new_log_start)?;
+      }
+    }
+
+    if let Some(sender) = &self.index.event_sender {
+      for event in events {
+        sender.blocking_send(event)?;
+      }
+    }
+
+    Ok(())
+  }
+
   fn index_transaction_sats(
     &mut self,
     tx: &Transaction,
@@ -667,10 +1001,10 @@ impl<'index> Updater<'index> {
     sat_ranges_written: &mut u64,
     outputs_traversed: &mut u64,
     inscription_updater: &mut InscriptionUpdater,
-    index_inscriptions: bool,
+    envelopes: Option<Vec<ParsedEnvelope>>,
   ) -> Result {
-    if index_inscriptions {
-      inscription_updater.index_inscriptions(tx, txid, Some(input_sat_ranges))?;
+    if let Some(envelopes) = envelopes {
+      inscription_updater.index_inscriptions(tx, txid, envelopes, Some(input_sat_ranges))?;
     }
 
     for (vout, output) in tx.output.iter().enumerate() {
@@ -770,3 +1104,81 @@ impl<'index> Updater<'index> {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn block(transactions: u32) -> BlockData {
+    Block {
+      header: Chain::Regtest.genesis_block().header,
+      txdata: (0..transactions)
+        .map(|i| Transaction {
+          version: 2,
+          lock_time: LockTime::ZERO,
+          input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: if i % 3 == 0 {
+              inscription("text/plain", i.to_string()).to_witness()
+            } else {
+              Witness::new()
+            },
+          }],
+          output: vec![TxOut {
+            value: 0,
+            script_pubkey: if i % 5 == 0 {
+              Runestone {
+                pointer: Some(i),
+                ..default()
+              }
+              .encipher()
+            } else {
+              ScriptBuf::new()
+            },
+          }],
+        })
+        .collect(),
+    }
+    .into()
+  }
+
+  #[test]
+  fn preprocessing_matches_parsing_while_indexing() {
+    for threads in [1, 2, 7] {
+      let mut preprocessed = block(200);
+      preprocessed.preprocess(true, true, threads);
+
+      assert_eq!(preprocessed.envelopes.len(), 200);
+      assert_eq!(preprocessed.artifacts.len(), 200);
+
+      let mut unprocessed = block(200);
+
+      for (i, (tx, _txid)) in preprocessed.txdata.iter().enumerate() {
+        assert_eq!(
+          BlockData::take_envelopes(&mut preprocessed.envelopes, i, tx),
+          BlockData::take_envelopes(&mut unprocessed.envelopes, i, tx),
+        );
+
+        assert_eq!(
+          BlockData::take_artifact(&mut preprocessed.artifacts, i, tx),
+          BlockData::take_artifact(&mut unprocessed.artifacts, i, tx),
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn preprocessing_only_parses_what_is_indexed() {
+    let mut runes = block(10);
+    runes.preprocess(false, true, 2);
+    assert!(runes.envelopes.is_empty());
+    assert_eq!(runes.artifacts.len(), 10);
+
+    let mut neither = block(10);
+    neither.preprocess(false, false, 2);
+    assert!(neither.envelopes.is_empty());
+    assert!(neither.artifacts.is_empty());
+  }
+}
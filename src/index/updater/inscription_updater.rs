@@ -42,7 +42,7 @@ pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) chain: Chain,
   pub(super) content_type_to_count: &'a mut Table<'tx, Option<&'static [u8]>, u64>,
   pub(super) cursed_inscription_count: u64,
-  pub(super) event_sender: Option<&'a Sender<Event>>,
+  pub(super) events: &'a mut Vec<Event>,
   pub(super) flotsam: Vec<Flotsam>,
   pub(super) height: u32,
   pub(super) home_inscription_count: u64,
@@ -65,6 +65,7 @@ pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) sequence_number_to_entry: &'a mut Table<'tx, u32, InscriptionEntryValue>,
   pub(super) sequence_number_to_satpoint: &'a mut Table<'tx, u32, &'static SatPointValue>,
   pub(super) timestamp: u32,
+  pub(super) transfers_in_block: u32, // @br-indexer
   pub(super) unbound_inscriptions: u64,
   pub(super) value_cache: &'a mut HashMap<OutPoint, u64>,
   pub(super) value_receiver: &'a mut Receiver<u64>,
@@ -75,6 +76,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
     &mut self,
     tx: &Transaction,
     txid: Txid,
+    envelopes: Vec<ParsedEnvelope>,
     input_sat_ranges: Option<&VecDeque<(u64, u64)>>,
   ) -> Result {
     let mut floating_inscriptions = Vec::new();
@@ -84,7 +86,6 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
     let mut total_input_value = 0;
     let total_output_value = tx.output.iter().map(|txout| txout.value).sum::<u64>();
 
-    let envelopes = ParsedEnvelope::from_transaction(tx);
     let inscriptions = !envelopes.is_empty();
     let mut envelopes = envelopes.into_iter().peekable();
 
@@ -348,7 +349,9 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         _ => new_satpoint,
       };
 
-      self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint)?;
+      let tx_out = &tx.output[new_satpoint.outpoint.vout.into_usize()];
+
+      self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint, Some(tx_out))?;
     }
 
     if is_coinbase {
@@ -357,7 +360,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           outpoint: OutPoint::null(),
           offset: self.lost_sats + flotsam.offset - output_value,
         };
-        self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint)?;
+        self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint, None)?;
       }
       self.lost_sats += self.reward - output_value;
       Ok(())
@@ -395,6 +398,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
     input_sat_ranges: Option<&VecDeque<(u64, u64)>>,
     flotsam: Flotsam,
     new_satpoint: SatPoint,
+    tx_out: Option<&TxOut>,
   ) -> Result {
     let inscription_id = flotsam.inscription_id;
     let (unbound, sequence_number) = match flotsam.origin {
@@ -409,15 +413,34 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           .unwrap()
           .value();
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::InscriptionTransferred {
-            block_height: self.height,
+        self.events.push(Event::InscriptionTransferred {
+          block_height: self.height,
+          inscription_id,
+          new_location: new_satpoint,
+          old_location: old_satpoint,
+          sequence_number,
+        });
+
+        // @br-indexer: record inscription transfer --> start
+        self.sequence_number_to_inscription_transfer.insert(
+          self.next_sequence_number_transfer,
+          &InscriptionTransfer {
             inscription_id,
-            new_location: new_satpoint,
-            old_location: old_satpoint,
-            sequence_number,
-          })?;
-        }
+            network: self.chain.network(),
+            txid: new_satpoint.outpoint.txid,
+            to_script_pubkey: tx_out
+              .map(|tx_out| tx_out.script_pubkey.clone())
+              .unwrap_or_default(),
+            output_value: tx_out.map(|tx_out| tx_out.value).unwrap_or_default(),
+            height: self.height,
+            index: self.transfers_in_block,
+            vout: new_satpoint.outpoint.vout,
+          }
+          .store(),
+        )?;
+        self.next_sequence_number_transfer += 1;
+        self.transfers_in_block += 1;
+        // @br-indexer: record inscription transfer --> end
 
         (false, sequence_number)
       }
@@ -501,16 +524,14 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           })
           .collect::<Result<Vec<u32>>>()?;
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::InscriptionCreated {
-            block_height: self.height,
-            charms,
-            inscription_id,
-            location: (!unbound).then_some(new_satpoint),
-            parent_inscription_ids: parents,
-            sequence_number,
-          })?;
-        }
+        self.events.push(Event::InscriptionCreated {
+          block_height: self.height,
+          charms,
+          inscription_id,
+          location: (!unbound).then_some(new_satpoint),
+          parent_inscription_ids: parents,
+          sequence_number,
+        });
 
         self.sequence_number_to_entry.insert(
           sequence_number,
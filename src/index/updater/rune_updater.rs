@@ -1,45 +1,57 @@
 use super::*;
-use crate::indexer::rune_event::{
-  BlockId, BlockIdValue, RuneChanges, RuneEvent, RuneEventEntry, RuneEventEntryValue,
+use crate::indexer::{
+  rune_event::{
+    BlockId, BlockIdValue, RuneChanges, RuneEvent, RuneEventEntry, RuneEventEntryValue,
+    RuneEventTotalsValue,
+  },
+  script_pubkey_value::ScriptPubkeyHash,
 };
 
-pub(super) struct RuneUpdater<'a, 'tx, 'client> {
+// @br-indexer: a spent outpoint, its owner and its balance of a rune
+type RuneOwner = (OutPoint, ScriptBuf, u128);
+
+pub(super) struct RuneUpdater<'a, 'tx, 'source> {
   pub(super) chain: Chain,
   pub(super) block_id_to_rune_event: &'a mut Table<'tx, BlockIdValue, RuneEventEntryValue>,
-  pub(super) block_id_to_rune_spent: &'a mut Table<'tx, BlockIdValue, RuneEventEntryValue>,
   pub(super) last_block_id_to_rune_changes: &'a mut Table<'tx, BlockIdValue, RuneChangesValue>,
   pub(super) block_time: u32,
   pub(super) burned: HashMap<RuneId, Lot>,
   pub(super) mints: HashMap<RuneId, Lot>,
-  pub(super) client: &'client Client,
-  pub(super) event_sender: Option<&'a Sender<Event>>,
+  pub(super) block_source: &'source dyn BlockSource,
+  pub(super) events: &'a mut Vec<Event>,
   pub(super) height: u32,
   pub(super) id_to_entry: &'a mut Table<'tx, RuneIdValue, RuneEntryValue>,
   pub(super) inscription_id_to_sequence_number: &'a Table<'tx, InscriptionIdValue, u32>,
   pub(super) minimum: Rune,
   pub(super) outpoint_to_balances: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+  pub(super) outpoint_to_script_pubkey: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+  pub(super) rune_id_to_rune_event: &'a mut Table<'tx, (RuneIdValue, BlockIdValue), ()>,
+  pub(super) rune_id_to_holder_balance: &'a mut Table<'tx, (RuneIdValue, &'static [u8]), u128>,
+  pub(super) rune_id_to_holder_count: &'a mut Table<'tx, RuneIdValue, u64>,
+  pub(super) rune_id_to_event_totals: &'a mut Table<'tx, RuneIdValue, RuneEventTotalsValue>,
   pub(super) rune_to_id: &'a mut Table<'tx, u128, RuneIdValue>,
+  pub(super) script_pubkey_hash_to_rune_event:
+    &'a mut Table<'tx, (&'static ScriptPubkeyHash, BlockIdValue), ()>,
   pub(super) runes: u64,
   pub(super) sequence_number_to_rune_id: &'a mut Table<'tx, u32, RuneIdValue>,
   pub(super) statistic_to_count: &'a mut Table<'tx, u64, u64>,
   pub(super) transaction_id_to_rune: &'a mut Table<'tx, &'static TxidValue, u128>,
 }
 
-impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
+impl<'a, 'tx, 'source> RuneUpdater<'a, 'tx, 'source> {
   pub(super) fn index_runes(
     &mut self,
     tx_index: u32,
     tx: &Transaction,
     txid: Txid,
+    artifact: Option<Artifact>,
     block_index: &mut u32,
   ) -> Result<()> {
-    let artifact = Runestone::decipher(tx);
-
-    let mut rune_inputs: HashMap<RuneId, Vec<OutPoint>> = HashMap::new(); // @br-indexer
-    let mut unallocated = self.unallocated(tx, &mut rune_inputs, block_index)?;
+    let mut seq_number_rune_event = self.block_id_to_rune_event.len().unwrap_or(0); // @br-indexer
+    let (mut unallocated, mut owners) =
+      self.unallocated(tx, txid, block_index, &mut seq_number_rune_event)?;
 
     let mut allocated: Vec<HashMap<RuneId, Lot>> = vec![HashMap::new(); tx.output.len()];
-    let mut seq_number_rune_event = self.block_id_to_rune_event.len().unwrap_or(0); // @br-indexer
 
     if let Some(artifact) = &artifact {
       if let Some(id) = artifact.mint() {
@@ -47,39 +59,32 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
           *unallocated.entry(id).or_default() += amount;
 
           // @todo br-indexer - new input --> start
-          self.block_id_to_rune_event.insert(
-            &BlockId {
-              block: self.height.into(),
-              index: block_index.saturating_add(1),
-            }
-            .store(),
-            &RuneEventEntry {
-              seq_no: seq_number_rune_event.saturating_add(1),
+          self.insert_rune_event(
+            block_index,
+            &mut seq_number_rune_event,
+            RuneEventEntry {
+              seq_no: 0,
               rune_id: id,
               network: self.chain.network(),
               event: RuneEvent::Mint,
               source: txid,
               height: self.height,
               txid,
-              script_pubkey: ScriptBuf::default(),
+              // recipients are recorded by the transaction's transfer events
+              script_pubkey: ScriptBuf::new(),
               amount: amount.n(),
-              vout: i32::try_from(tx_index).unwrap(),
+              vout: -1,
               timestamp: self.block_time,
-            }
-            .store(),
+            },
           )?;
-          *block_index += 1;
-          seq_number_rune_event += 1;
           // @todo br-indexer - new input --> end
 
-          if let Some(sender) = self.event_sender {
-            sender.blocking_send(Event::RuneMinted {
-              block_height: self.height,
-              txid,
-              rune_id: id,
-              amount: amount.n(),
-            })?;
-          }
+          self.events.push(Event::RuneMinted {
+            block_height: self.height,
+            txid,
+            rune_id: id,
+            amount: amount.n(),
+          });
         }
       }
 
@@ -176,26 +181,9 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         *burned.entry(id).or_default() += balance;
       }
     } else {
-      let pointer = artifact
-        .map(|artifact| match artifact {
-          Artifact::Runestone(runestone) => runestone.pointer,
-          Artifact::Cenotaph(_) => unreachable!(),
-        })
-        .unwrap_or_default();
-
       // assign all un-allocated runes to the default output, or the first non
       // OP_RETURN output if there is no default
-      if let Some(vout) = pointer
-        .map(|pointer| pointer.into_usize())
-        .inspect(|&pointer| assert!(pointer < allocated.len()))
-        .or_else(|| {
-          tx.output
-            .iter()
-            .enumerate()
-            .find(|(_vout, tx_out)| !tx_out.script_pubkey.is_op_return())
-            .map(|(vout, _tx_out)| vout)
-        })
-      {
+      if let Some(vout) = Self::default_output(tx, artifact.as_ref()) {
         for (id, balance) in unallocated {
           if balance > 0 {
             *allocated[vout].entry(id).or_default() += balance;
@@ -241,14 +229,11 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         Index::encode_rune_balance(id, balance.n(), &mut buffer);
 
         // @todo br-indexer - new output --> start
-        self.block_id_to_rune_event.insert(
-          &BlockId {
-            block: self.height.into(),
-            index: block_index.saturating_add(1),
-          }
-          .store(),
-          &RuneEventEntry {
-            seq_no: seq_number_rune_event.saturating_add(1),
+        self.insert_rune_event(
+          block_index,
+          &mut seq_number_rune_event,
+          RuneEventEntry {
+            seq_no: 0,
             rune_id: id,
             network: self.chain.network(),
             event: RuneEvent::Transfer,
@@ -259,94 +244,95 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
             amount: balance.n(),
             vout: i32::try_from(vout).unwrap(),
             timestamp: self.block_time,
-          }
-          .store(),
+          },
         )?;
-        *block_index += 1;
-        seq_number_rune_event += 1;
         // @todo br-indexer - new output --> end
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::RuneTransferred {
-            outpoint,
-            block_height: self.height,
-            txid,
-            rune_id: id,
-            amount: balance.0,
-          })?;
-        }
+        self.events.push(Event::RuneTransferred {
+          outpoint,
+          block_height: self.height,
+          txid,
+          rune_id: id,
+          amount: balance.0,
+        });
       }
 
       self
         .outpoint_to_balances
         .insert(&outpoint.store(), buffer.as_slice())?;
+
+      // @br-indexer: remember the owner of rune balances for spent and burn events
+      self
+        .outpoint_to_script_pubkey
+        .insert(&outpoint.store(), tx.output[vout].script_pubkey.as_bytes())?;
     }
 
+    let mut burned = burned.into_iter().collect::<Vec<(RuneId, Lot)>>();
+
+    burned.sort();
+
     // increment entries with burned runes
     for (id, amount) in burned {
       *self.burned.entry(id).or_default() += amount;
 
-      if let Some(sender) = self.event_sender {
-        sender.blocking_send(Event::RuneBurned {
-          block_height: self.height,
-          txid,
-          rune_id: id,
-          amount: amount.n(),
-        })?;
+      self.events.push(Event::RuneBurned {
+        block_height: self.height,
+        txid,
+        rune_id: id,
+        amount: amount.n(),
+      });
+
+      // @br-indexer: one event per spent outpoint whose runes were burned,
+      // taken in input order, and one for burned runes that were minted or
+      // premined by the transaction
+      let mut remaining = amount.n();
+
+      for (outpoint, script_pubkey, balance) in owners.remove(&id).unwrap_or_default() {
+        if remaining == 0 {
+          break;
+        }
+
+        let amount = remaining.min(balance);
+        remaining -= amount;
+
+        self.insert_rune_event(
+          block_index,
+          &mut seq_number_rune_event,
+          RuneEventEntry {
+            seq_no: 0,
+            rune_id: id,
+            network: self.chain.network(),
+            event: RuneEvent::Burn,
+            source: txid,
+            height: self.height,
+            txid: outpoint.txid,
+            script_pubkey,
+            amount,
+            vout: i32::try_from(outpoint.vout).unwrap(),
+            timestamp: self.block_time,
+          },
+        )?;
       }
-      // @todo br-indexer burn rune --> end
-      if rune_inputs.entry(id).or_default().is_empty() {
-        self.block_id_to_rune_event.insert(
-          &BlockId {
-            block: self.height.into(),
-            index: block_index.saturating_add(1),
-          }
-          .store(),
-          &RuneEventEntry {
-            seq_no: seq_number_rune_event.saturating_add(1),
+
+      if remaining > 0 {
+        self.insert_rune_event(
+          block_index,
+          &mut seq_number_rune_event,
+          RuneEventEntry {
+            seq_no: 0,
             rune_id: id,
             network: self.chain.network(),
             event: RuneEvent::Burn,
+            source: txid,
             height: self.height,
             txid,
-            amount: amount.n(),
-            source: txid,
-            script_pubkey: ScriptBuf::default(),
+            script_pubkey: ScriptBuf::new(),
+            amount: remaining,
             vout: -1,
             timestamp: self.block_time,
-          }
-          .store(),
+          },
         )?;
-        *block_index += 1;
-        seq_number_rune_event += 1;
-      } else {
-        for input in rune_inputs.entry(id).or_default().iter() {
-          self.block_id_to_rune_event.insert(
-            &BlockId {
-              block: self.height.into(),
-              index: block_index.saturating_add(1),
-            }
-            .store(),
-            &RuneEventEntry {
-              seq_no: seq_number_rune_event.saturating_add(1),
-              rune_id: id,
-              network: self.chain.network(),
-              event: RuneEvent::Burn,
-              source: txid,
-              height: self.height,
-              txid: input.txid,
-              amount: amount.n(),
-              script_pubkey: ScriptBuf::default(),
-              vout: i32::try_from(input.vout).unwrap(),
-              timestamp: self.block_time,
-            }
-            .store(),
-          )?;
-          *block_index += 1;
-          seq_number_rune_event += 1;
-        }
       }
-      // @todo br-indexer --> end
     }
 
     Ok(())
@@ -466,13 +452,11 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
 
     self.id_to_entry.insert(id.store(), entry.store())?;
 
-    if let Some(sender) = self.event_sender {
-      sender.blocking_send(Event::RuneEtched {
-        block_height: self.height,
-        txid,
-        rune_id: id,
-      })?;
-    }
+    self.events.push(Event::RuneEtched {
+      block_height: self.height,
+      txid,
+      rune_id: id,
+    });
 
     let inscription_id = InscriptionId { txid, index: 0 };
 
@@ -586,20 +570,15 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
           continue;
         }
 
-        let Some(tx_info) = self
-          .client
-          .get_raw_transaction_info(&input.previous_output.txid, None)
-          .into_option()?
-        else {
+        let Some(commit_tx) = self.block_source.transaction(input.previous_output.txid)? else {
           panic!(
             "can't get input transaction: {}",
             input.previous_output.txid
           );
         };
 
-        let taproot = tx_info.vout[input.previous_output.vout.into_usize()]
-          .script_pub_key
-          .script()?
+        let taproot = commit_tx.output[input.previous_output.vout.into_usize()]
+          .script_pubkey
           .is_v1_p2tr();
 
         if !taproot {
@@ -607,17 +586,11 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         }
 
         let commit_tx_height = self
-          .client
-          .get_block_header_info(&tx_info.blockhash.unwrap())
-          .into_option()?
-          .unwrap()
-          .height;
-
-        let confirmations = self
-          .height
-          .checked_sub(commit_tx_height.try_into().unwrap())
-          .unwrap()
-          + 1;
+          .block_source
+          .transaction_height(input.previous_output.txid)?
+          .unwrap();
+
+        let confirmations = self.height.checked_sub(commit_tx_height).unwrap() + 1;
 
         if confirmations >= Runestone::COMMIT_CONFIRMATIONS.into() {
           return Ok(true);
@@ -631,14 +604,21 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
   fn unallocated(
     &mut self,
     tx: &Transaction,
-    rune_inputs: &mut HashMap<RuneId, Vec<OutPoint>>,
+    txid: Txid,
     block_index: &mut u32,
-  ) -> Result<HashMap<RuneId, Lot>> {
+    seq_number_rune_event: &mut u64,
+  ) -> Result<(HashMap<RuneId, Lot>, HashMap<RuneId, Vec<RuneOwner>>)> {
     // map of rune ID to un-allocated balance of that rune
     let mut unallocated: HashMap<RuneId, Lot> = HashMap::new();
 
+    // @br-indexer: map of rune ID to the spent outpoints holding that rune, in
+    // input order, with their owners and balances
+    let mut owners: HashMap<RuneId, Vec<RuneOwner>> = HashMap::new();
+
     // increment unallocated runes with the runes in tx inputs
     for input in &tx.input {
+      let mut balances = Vec::new();
+
       if let Some(guard) = self
         .outpoint_to_balances
         .remove(&input.previous_output.store())?
@@ -648,40 +628,141 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         while i < buffer.len() {
           let ((id, balance), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
           i += len;
-          *unallocated.entry(id).or_default() += balance;
-
-          // @todo br-indexer - new input --> start
-          rune_inputs
-            .entry(id)
-            .or_default()
-            .push(input.previous_output);
-
-          self.block_id_to_rune_spent.insert(
-            &BlockId {
-              block: self.height.into(),
-              index: block_index.saturating_add(1),
-            }
-            .store(),
-            &RuneEventEntry {
-              seq_no: 0,
-              rune_id: id,
-              network: self.chain.network(),
-              event: RuneEvent::Spent,
-              source: tx.txid(),
-              height: self.height,
-              txid: input.previous_output.txid,
-              script_pubkey: ScriptBuf::default(),
-              amount: 0,
-              vout: i32::try_from(input.previous_output.vout).unwrap(),
-              timestamp: self.block_time,
-            }
-            .store(),
-          )?;
-          *block_index += 1;
+          balances.push((id, balance));
         }
       }
+
+      if balances.is_empty() {
+        continue;
+      }
+
+      let script_pubkey = Self::input_script_pubkey(
+        self.outpoint_to_script_pubkey,
+        self.block_source,
+        input.previous_output,
+      )?; // @br-indexer
+
+      for (id, balance) in balances {
+        *unallocated.entry(id).or_default() += balance;
+
+        owners
+          .entry(id)
+          .or_default()
+          .push((input.previous_output, script_pubkey.clone(), balance));
+
+        // @br-indexer: debit the spent outpoint by its full balance
+        self.insert_rune_event(
+          block_index,
+          seq_number_rune_event,
+          RuneEventEntry {
+            seq_no: 0,
+            rune_id: id,
+            network: self.chain.network(),
+            event: RuneEvent::Spent,
+            source: txid,
+            height: self.height,
+            txid: input.previous_output.txid,
+            script_pubkey: script_pubkey.clone(),
+            amount: balance,
+            vout: i32::try_from(input.previous_output.vout).unwrap(),
+            timestamp: self.block_time,
+          },
+        )?;
+      }
+    }
+
+    Ok((unallocated, owners))
+  }
+
+  // @br-indexer: store an event and make it reachable from its rune and script pubkey
+  fn insert_rune_event(
+    &mut self,
+    block_index: &mut u32,
+    seq_number_rune_event: &mut u64,
+    mut entry: RuneEventEntry,
+  ) -> Result {
+    *seq_number_rune_event += 1;
+    entry.seq_no = *seq_number_rune_event;
+
+    let id = BlockId {
+      block: self.height.into(),
+      index: block_index.saturating_add(1),
+    };
+
+    Index::index_rune_event_script_pubkey(
+      self.script_pubkey_hash_to_rune_event,
+      &entry.script_pubkey,
+      id,
+    )?;
+
+    self
+      .rune_id_to_rune_event
+      .insert((entry.rune_id.store(), id.store()), ())?;
+
+    Index::update_rune_event_totals(self.rune_id_to_event_totals, &entry)?;
+
+    if entry.event.is_balance_change() {
+      Index::update_rune_holder(
+        self.rune_id_to_holder_balance,
+        self.rune_id_to_holder_count,
+        &entry,
+      )?;
+    }
+
+    self
+      .block_id_to_rune_event
+      .insert(&id.store(), &entry.store())?;
+
+    *block_index += 1;
+
+    Ok(())
+  }
+
+  fn default_output(tx: &Transaction, artifact: Option<&Artifact>) -> Option<usize> {
+    let pointer = match artifact {
+      Some(Artifact::Cenotaph(_)) => return None,
+      Some(Artifact::Runestone(runestone)) => runestone.pointer,
+      None => None,
+    };
+
+    pointer
+      .map(|pointer| pointer.into_usize())
+      .inspect(|&pointer| assert!(pointer < tx.output.len()))
+      .or_else(|| {
+        tx.output
+          .iter()
+          .enumerate()
+          .find(|(_vout, tx_out)| !tx_out.script_pubkey.is_op_return())
+          .map(|(vout, _tx_out)| vout)
+      })
+  }
+
+  // @br-indexer: resolve the script pubkey of a spent rune output
+  fn input_script_pubkey(
+    outpoint_to_script_pubkey: &mut Table<&'static OutPointValue, &'static [u8]>,
+    block_source: &dyn BlockSource,
+    outpoint: OutPoint,
+  ) -> Result<ScriptBuf> {
+    if let Some(script_pubkey) = outpoint_to_script_pubkey.remove(&outpoint.store())? {
+      return Ok(ScriptBuf::from_bytes(script_pubkey.value().to_vec()));
     }
 
-    Ok(unallocated)
+    // outputs which received runes before the script pubkey table existed
+    // must be looked up in the block source. Debiting any other script pubkey
+    // would corrupt holder balances, so failing to do so is fatal.
+    block_source
+      .transaction(outpoint.txid)?
+      .and_then(|transaction| {
+        transaction
+          .output
+          .into_iter()
+          .nth(outpoint.vout.into_usize())
+      })
+      .map(|tx_out| tx_out.script_pubkey)
+      .ok_or_else(|| {
+        anyhow!(
+          "failed to resolve script pubkey of spent rune output {outpoint}, is bitcoind running with -txindex?"
+        )
+      })
   }
 }
@@ -0,0 +1,571 @@
+use {super::*, std::fs::File};
+
+const MAX_ATTEMPTS: u32 = 10;
+const MAX_BACKOFF: u64 = 60 * 60;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WebhookConfig {
+  url: String,
+  secret: String,
+  #[serde(default)]
+  events: Vec<String>,
+  #[serde(default)]
+  rune_ids: Vec<RuneId>,
+  #[serde(default)]
+  addresses: Vec<Address<NetworkUnchecked>>,
+  #[serde(default)]
+  parents: Vec<InscriptionId>,
+  max_attempts: Option<u32>,
+}
+
+// @br-indexer: a webhook from the `--webhooks` file. Deliveries are queued
+// for webhooks by their position in the file. Empty filters match everything,
+// non-empty filters must all match. Reorgs are only filtered by event kind,
+// since every consumer needs to hear about them.
+#[derive(Debug, Clone)]
+pub(crate) struct Webhook {
+  pub(crate) url: String,
+  pub(crate) secret: String,
+  pub(crate) max_attempts: u32,
+  events: Vec<String>,
+  rune_ids: Vec<RuneId>,
+  script_pubkeys: Vec<ScriptBuf>,
+  parents: Vec<InscriptionId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Delivery {
+  pub(crate) hook: u32,
+  pub(crate) url: String,
+  pub(crate) payload: serde_json::Value,
+  pub(crate) attempts: u32,
+  pub(crate) next_attempt: u64,
+  pub(crate) last_error: Option<String>,
+}
+
+impl Webhook {
+  pub(crate) fn load(settings: &Settings) -> Result<Vec<Self>> {
+    let Some(path) = settings.webhooks() else {
+      return Ok(Vec::new());
+    };
+
+    let configs: Vec<WebhookConfig> = serde_yaml::from_reader(
+      File::open(path)
+        .with_context(|| format!("failed to open webhooks file `{}`", path.display()))?,
+    )
+    .with_context(|| format!("failed to parse webhooks file `{}`", path.display()))?;
+
+    configs
+      .into_iter()
+      .map(|config| {
+        Url::parse(&config.url).with_context(|| format!("invalid webhook url `{}`", config.url))?;
+
+        for event in &config.events {
+          ensure!(
+            Event::KINDS.contains(&event.as_str()),
+            "invalid event `{event}` for webhook `{}`, expected one of {}",
+            config.url,
+            Event::KINDS.join(", "),
+          );
+        }
+
+        Ok(Self {
+          script_pubkeys: config
+            .addresses
+            .into_iter()
+            .map(|address| {
+              Ok(
+                address
+                  .require_network(settings.chain().network())?
+                  .script_pubkey(),
+              )
+            })
+            .collect::<Result<Vec<ScriptBuf>>>()?,
+          url: config.url,
+          secret: config.secret,
+          max_attempts: config.max_attempts.unwrap_or(MAX_ATTEMPTS),
+          events: config.events,
+          rune_ids: config.rune_ids,
+          parents: config.parents,
+        })
+      })
+      .collect()
+  }
+
+  fn matches(
+    &self,
+    event: &Event,
+    script_pubkey: &impl Fn(OutPoint) -> Option<ScriptBuf>,
+    parents: &impl Fn(InscriptionId) -> Result<Vec<InscriptionId>>,
+  ) -> Result<bool> {
+    if !self.events.is_empty() && !self.events.iter().any(|kind| kind == event.kind()) {
+      return Ok(false);
+    }
+
+    if let Event::Reorg { .. } = event {
+      return Ok(true);
+    }
+
+    if !self.rune_ids.is_empty() {
+      let rune_id = match event {
+        Event::RuneBurned { rune_id, .. }
+        | Event::RuneEtched { rune_id, .. }
+        | Event::RuneMinted { rune_id, .. }
+        | Event::RuneTransferred { rune_id, .. } => Some(rune_id),
+        _ => None,
+      };
+
+      if !rune_id.is_some_and(|rune_id| self.rune_ids.contains(rune_id)) {
+        return Ok(false);
+      }
+    }
+
+    if !self.parents.is_empty() {
+      let event_parents = match event {
+        Event::InscriptionCreated {
+          parent_inscription_ids,
+          ..
+        } => parent_inscription_ids.clone(),
+        Event::InscriptionTransferred { inscription_id, .. } => parents(*inscription_id)?,
+        _ => Vec::new(),
+      };
+
+      if !event_parents
+        .iter()
+        .any(|parent| self.parents.contains(parent))
+      {
+        return Ok(false);
+      }
+    }
+
+    if !self.script_pubkeys.is_empty() {
+      let outpoint = match event {
+        Event::InscriptionCreated { location, .. } => location.map(|location| location.outpoint),
+        Event::InscriptionTransferred { new_location, .. } => Some(new_location.outpoint),
+        Event::RuneTransferred { outpoint, .. } => Some(*outpoint),
+        _ => None,
+      };
+
+      if !outpoint
+        .and_then(script_pubkey)
+        .is_some_and(|script_pubkey| self.script_pubkeys.contains(&script_pubkey))
+      {
+        return Ok(false);
+      }
+    }
+
+    Ok(true)
+  }
+}
+
+impl Index {
+  pub(crate) fn webhooks(&self) -> &[Webhook] {
+    &self.webhooks
+  }
+
+  // @br-indexer: queue deliveries of `events` to matching webhooks in the
+  // transaction that indexes them, so that a crash can neither lose nor
+  // duplicate them. Every event location is an output of the block being
+  // indexed, whose script pubkey is looked up with `script_pubkey`, and
+  // `parents` returns every parent of an inscription.
+  pub(crate) fn enqueue_webhook_deliveries(
+    &self,
+    wtx: &WriteTransaction,
+    statistic_to_count: &mut Table<u64, u64>,
+    events: &[Event],
+    script_pubkey: impl Fn(OutPoint) -> Option<ScriptBuf>,
+    parents: impl Fn(InscriptionId) -> Result<Vec<InscriptionId>>,
+  ) -> Result {
+    if self.webhooks.is_empty() {
+      return Ok(());
+    }
+
+    let mut outbox = wtx.open_table(WEBHOOK_OUTBOX)?;
+
+    let mut next_delivery_id = statistic_to_count
+      .get(&Statistic::NextWebhookDelivery.key())?
+      .map(|id| id.value())
+      .unwrap_or_default();
+
+    for event in events {
+      for (hook, webhook) in self.webhooks.iter().enumerate() {
+        if !webhook.matches(event, &script_pubkey, &parents)? {
+          continue;
+        }
+
+        let hook = u32::try_from(hook).unwrap();
+
+        let delivery = Delivery {
+          hook,
+          url: webhook.url.clone(),
+          payload: serde_json::json!({
+            "delivery_id": next_delivery_id,
+            "event": event,
+          }),
+          attempts: 0,
+          next_attempt: 0,
+          last_error: None,
+        };
+
+        outbox.insert(
+          (hook, next_delivery_id),
+          serde_json::to_vec(&delivery)?.as_slice(),
+        )?;
+
+        next_delivery_id += 1;
+      }
+    }
+
+    statistic_to_count.insert(&Statistic::NextWebhookDelivery.key(), &next_delivery_id)?;
+
+    Ok(())
+  }
+
+  // @br-indexer: deliveries to `hook` that are due at `now`, oldest first
+  pub(crate) fn get_webhook_deliveries_due(
+    &self,
+    hook: u32,
+    now: u64,
+    limit: usize,
+  ) -> Result<Vec<(u64, Delivery)>> {
+    let mut due = Vec::new();
+
+    for result in self
+      .database
+      .begin_read()?
+      .open_table(WEBHOOK_OUTBOX)?
+      .range((hook, 0)..=(hook, u64::MAX))?
+    {
+      let (key, delivery) = result?;
+      let delivery: Delivery = serde_json::from_slice(delivery.value())?;
+
+      if delivery.next_attempt <= now {
+        due.push((key.value().1, delivery));
+
+        if due.len() == limit {
+          break;
+        }
+      }
+    }
+
+    Ok(due)
+  }
+
+  // @br-indexer: remove a delivery attempted at `now` from the outbox if it
+  // succeeded, otherwise back off, or move it to the dead letters once its
+  // webhook's attempts are used up
+  pub(crate) fn finish_webhook_delivery(
+    &self,
+    id: u64,
+    mut delivery: Delivery,
+    error: Option<String>,
+    now: u64,
+  ) -> Result {
+    let wtx = self.begin_write()?;
+
+    {
+      let mut outbox = wtx.open_table(WEBHOOK_OUTBOX)?;
+
+      outbox.remove((delivery.hook, id))?;
+
+      if let Some(error) = error {
+        delivery.attempts += 1;
+        delivery.next_attempt = now + 2u64.saturating_pow(delivery.attempts).min(MAX_BACKOFF);
+        delivery.last_error = Some(error);
+
+        let value = serde_json::to_vec(&delivery)?;
+
+        let max_attempts = usize::try_from(delivery.hook)
+          .ok()
+          .and_then(|hook| self.webhooks.get(hook))
+          .map(|webhook| webhook.max_attempts)
+          .unwrap_or(MAX_ATTEMPTS);
+
+        if delivery.attempts >= max_attempts {
+          wtx
+            .open_table(WEBHOOK_DEAD_LETTER)?
+            .insert(id, value.as_slice())?;
+        } else {
+          outbox.insert((delivery.hook, id), value.as_slice())?;
+        }
+      }
+    }
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  // @br-indexer: deliveries queued for webhooks that are no longer in the
+  // `--webhooks` file, or whose url changed, will never be delivered, so they
+  // are moved to the dead letters
+  pub(crate) fn dead_letter_unconfigured_webhook_deliveries(&self) -> Result {
+    let wtx = self.begin_write()?;
+
+    {
+      let mut outbox = wtx.open_table(WEBHOOK_OUTBOX)?;
+      let mut dead_letter = wtx.open_table(WEBHOOK_DEAD_LETTER)?;
+
+      let mut unconfigured = Vec::new();
+
+      for result in outbox.iter()? {
+        let (key, delivery) = result?;
+        let delivery: Delivery = serde_json::from_slice(delivery.value())?;
+
+        let configured = usize::try_from(delivery.hook)
+          .ok()
+          .and_then(|hook| self.webhooks.get(hook))
+          .is_some_and(|webhook| webhook.url == delivery.url);
+
+        if !configured {
+          unconfigured.push((key.value(), delivery));
+        }
+      }
+
+      for ((hook, id), mut delivery) in unconfigured {
+        outbox.remove((hook, id))?;
+
+        delivery.last_error = Some(format!("webhook {} is no longer configured", delivery.url));
+
+        dead_letter.insert(id, serde_json::to_vec(&delivery)?.as_slice())?;
+      }
+    }
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  pub(crate) fn get_webhook_dead_letters(&self) -> Result<Vec<(u64, Delivery)>> {
+    self
+      .database
+      .begin_read()?
+      .open_table(WEBHOOK_DEAD_LETTER)?
+      .iter()?
+      .map(|result| {
+        let (id, delivery) = result?;
+        Ok((id.value(), serde_json::from_slice(delivery.value())?))
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::testing::Context};
+
+  fn configure(context: &mut Context, webhooks: &str) -> Result {
+    let path = context.tempdir.path().join("webhooks.yaml");
+
+    fs::write(&path, webhooks).unwrap();
+
+    context.index.webhooks = Webhook::load(&Settings::from_options(
+      Options::try_parse_from(["ord", "--regtest", "--webhooks", path.to_str().unwrap()]).unwrap(),
+    ))?;
+
+    Ok(())
+  }
+
+  fn address(hash: u8) -> Address {
+    Address::from_script(
+      &ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::from_byte_array([hash; 20])),
+      Network::Regtest,
+    )
+    .unwrap()
+  }
+
+  fn queued(context: &Context, hook: u32) -> Vec<serde_json::Value> {
+    context
+      .index
+      .get_webhook_deliveries_due(hook, u64::MAX, usize::MAX)
+      .unwrap()
+      .into_iter()
+      .map(|(_, delivery)| delivery.payload["event"].clone())
+      .collect()
+  }
+
+  #[test]
+  fn events_are_validated() {
+    let mut context = Context::builder().build();
+
+    assert_eq!(
+      configure(
+        &mut context,
+        "
+- url: http://localhost/
+  secret: foo
+  events: [inscription_moved]
+",
+      )
+      .unwrap_err()
+      .to_string(),
+      format!(
+        "invalid event `inscription_moved` for webhook `http://localhost/`, expected one of {}",
+        Event::KINDS.join(", "),
+      ),
+    );
+  }
+
+  #[test]
+  fn deliveries_are_filtered_by_every_parent_and_output_address() {
+    let mut context = Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let parent_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "parent").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let parent = InscriptionId {
+      txid: parent_txid,
+      index: 0,
+    };
+
+    let child_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        2,
+        1,
+        0,
+        Inscription {
+          content_type: Some("text/plain".into()),
+          body: Some("child".into()),
+          parents: vec![parent.value()],
+          ..default()
+        }
+        .to_witness(),
+      )],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    configure(
+      &mut context,
+      &format!(
+        "
+- url: http://localhost/parent
+  secret: foo
+  parents: [{parent}]
+- url: http://localhost/other-parent
+  secret: foo
+  parents: [{}]
+- url: http://localhost/address
+  secret: foo
+  addresses: [{}]
+- url: http://localhost/other-address
+  secret: foo
+  addresses: [{}]
+",
+        InscriptionId {
+          txid: child_txid,
+          index: 0,
+        },
+        address(0),
+        address(1),
+      ),
+    )
+    .unwrap();
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 0, Default::default())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let transferred = queued(&context, 0);
+
+    assert_eq!(transferred.len(), 1);
+    assert_eq!(transferred[0]["type"], "inscription_transferred");
+    assert_eq!(
+      transferred[0]["inscription_id"],
+      InscriptionId {
+        txid: child_txid,
+        index: 0,
+      }
+      .to_string(),
+    );
+
+    assert!(queued(&context, 1).is_empty());
+    assert!(queued(&context, 2).contains(&transferred[0]));
+    assert!(queued(&context, 3).is_empty());
+  }
+
+  #[test]
+  fn deliveries_are_kept_across_reorgs() {
+    let mut context = Context::builder().build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    configure(
+      &mut context,
+      "
+- url: http://localhost/
+  secret: foo
+  max_attempts: 1
+",
+    )
+    .unwrap();
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, inscription("text/plain", "bar").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(10);
+
+    let mut due = context
+      .index
+      .get_webhook_deliveries_due(0, u64::MAX, usize::MAX)
+      .unwrap();
+
+    assert_eq!(due.len(), 2);
+
+    let (dead, dead_delivery) = due.remove(1);
+    let (delivered, delivered_delivery) = due.remove(0);
+
+    context
+      .index
+      .finish_webhook_delivery(delivered, delivered_delivery, None, 0)
+      .unwrap();
+
+    context
+      .index
+      .finish_webhook_delivery(dead, dead_delivery, Some("error".into()), 0)
+      .unwrap();
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    assert_eq!(
+      context
+        .index
+        .get_webhook_dead_letters()
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect::<Vec<u64>>(),
+      [dead],
+    );
+
+    let queued = context
+      .index
+      .get_webhook_deliveries_due(0, u64::MAX, usize::MAX)
+      .unwrap();
+
+    assert!(queued.iter().all(|(id, _)| *id > dead));
+    assert_eq!(queued[0].1.payload["event"]["type"], "reorg");
+  }
+}
@@ -3,5 +3,6 @@ pub mod constants;
 pub mod function;
 pub mod inscription_entries;
 pub mod inscription_transfer;
+pub mod reorg_log;
 pub mod rune_event;
 pub mod script_pubkey_value;
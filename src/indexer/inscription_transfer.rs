@@ -3,9 +3,10 @@ use bitcoin::{Network, ScriptBuf, Txid};
 use serde::{Deserialize, Serialize};
 
 use crate::index::entry::{Entry, InscriptionIdValue};
+use crate::indexer::script_pubkey_value::ScriptPubkeyValue;
 use crate::InscriptionId;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct InscriptionTransfer {
   pub inscription_id: InscriptionId,
   pub network: Network,
@@ -21,7 +22,7 @@ pub(crate) type InscriptionTransferValue = (
   InscriptionIdValue, // inscription_id
   u8,                 // network
   (u128, u128),       // txid
-  (u128, u128, u128), // to_script_pubkey
+  ScriptPubkeyValue,  // to_script_pubkey
   u64,                // output_value
   u32,                // height
   u32,                // index
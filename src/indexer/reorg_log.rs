@@ -0,0 +1,60 @@
+use bitcoin::hashes::Hash;
+use bitcoin::BlockHash;
+use serde::{Deserialize, Serialize};
+
+use crate::index::entry::Entry;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ReorgLogEntry {
+  pub height: u32,
+  pub depth: u32,
+  pub start_height: u32,
+  pub end_height: u32,
+  // every block rolled back, from start_height to end_height
+  pub orphaned_blocks: Vec<(u32, BlockHash)>,
+  pub timestamp: u64,
+}
+
+pub(crate) type ReorgLogEntryValue = (
+  u32,                  // height
+  u32,                  // depth
+  u32,                  // start_height
+  u32,                  // end_height
+  Vec<(u32, [u8; 32])>, // orphaned_blocks
+  u64,                  // timestamp
+);
+
+impl Entry for ReorgLogEntry {
+  type Value = ReorgLogEntryValue;
+
+  fn load(
+    (height, depth, start_height, end_height, orphaned_blocks, timestamp): ReorgLogEntryValue,
+  ) -> Self {
+    Self {
+      height,
+      depth,
+      start_height,
+      end_height,
+      orphaned_blocks: orphaned_blocks
+        .into_iter()
+        .map(|(height, hash)| (height, BlockHash::from_byte_array(hash)))
+        .collect(),
+      timestamp,
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.height,
+      self.depth,
+      self.start_height,
+      self.end_height,
+      self
+        .orphaned_blocks
+        .into_iter()
+        .map(|(height, hash)| (height, hash.to_byte_array()))
+        .collect(),
+      self.timestamp,
+    )
+  }
+}
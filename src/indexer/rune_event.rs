@@ -3,9 +3,19 @@ use bitcoin::{Network, ScriptBuf, Txid};
 use serde::{Deserialize, Serialize};
 
 use crate::index::entry::Entry;
+use crate::indexer::script_pubkey_value::ScriptPubkeyValue;
 
 use super::*;
 
+// @br-indexer: `Transfer` credits and `Spent` debits one outpoint's balance
+// of one rune, so summing them per rune yields supply minus burned. `Mint`
+// and `Burn` record issuance and destruction of runes by a transaction, and
+// are reconciled against the rune entry separately. Minted runes are pooled
+// with the transaction's other runes before edicts allocate them, so mints
+// have no script pubkey, and their recipients are those of the transaction's
+// `Transfer` events. Burns are attributed to the spent outpoints holding the
+// rune, in input order, and then to runes minted or premined by the
+// transaction, which have no script pubkey.
 #[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize)]
 pub enum RuneEvent {
   Mint,
@@ -14,7 +24,30 @@ pub enum RuneEvent {
   Spent,
 }
 
+#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+  Credit,
+  Debit,
+}
+
 impl RuneEvent {
+  /// Direction of a balance change, or `None` for mints and burns, whose
+  /// runes are already credited or debited by balance change events
+  pub fn direction(&self) -> Option<Direction> {
+    match self {
+      RuneEvent::Transfer => Some(Direction::Credit),
+      RuneEvent::Spent => Some(Direction::Debit),
+      RuneEvent::Mint | RuneEvent::Burn => None,
+    }
+  }
+
+  /// Whether the event moves runes in or out of an outpoint, rather than
+  /// recording their issuance or destruction
+  pub fn is_balance_change(&self) -> bool {
+    self.direction().is_some()
+  }
+
   pub fn from_u8(value: u8) -> Self {
     match value {
       0 => RuneEvent::Mint,
@@ -34,6 +67,20 @@ impl RuneEvent {
   }
 }
 
+impl FromStr for RuneEvent {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "mint" => Ok(RuneEvent::Mint),
+      "transfer" => Ok(RuneEvent::Transfer),
+      "burn" => Ok(RuneEvent::Burn),
+      "spent" => Ok(RuneEvent::Spent),
+      _ => Err(anyhow!("invalid rune event: {s}")),
+    }
+  }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct RuneChanges {
   pub rune_id: RuneId,
@@ -64,21 +111,133 @@ pub struct RuneEventResponse {
   pub rune_id: RuneId,
   pub network: Network,
   pub event: RuneEvent,
+  pub direction: Option<Direction>,
   pub source: Txid,
   pub txid: Txid,
   pub height: u32,
   pub script_pubkey: ScriptBuf,
+  pub address: Option<String>,
   pub amount: String,
+  pub delta: Option<String>,
   pub timestamp: u32,
   pub vout: i32,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+impl From<RuneEventEntry> for RuneEventResponse {
+  fn from(event: RuneEventEntry) -> Self {
+    Self {
+      seq_no: event.seq_no,
+      rune_id: event.rune_id,
+      network: event.network,
+      event: event.event,
+      direction: event.event.direction(),
+      source: event.source,
+      height: event.height,
+      txid: event.txid,
+      address: Address::from_script(&event.script_pubkey, event.network)
+        .ok()
+        .map(|address| address.to_string()),
+      script_pubkey: event.script_pubkey,
+      amount: event.amount.to_string(),
+      delta: event.event.direction().map(|direction| match direction {
+        Direction::Credit => event.amount.to_string(),
+        Direction::Debit => format!("-{}", event.amount),
+      }),
+      vout: event.vout,
+      timestamp: event.timestamp,
+    }
+  }
+}
+
+// @br-indexer: signed sums of a rune's events, by kind
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub struct RuneEventTotals {
+  pub events: u64,
+  pub minted: u128,
+  pub burned: u128,
+  pub credited: u128,
+  pub debited: u128,
+}
+
+impl RuneEventTotals {
+  pub fn add(&mut self, entry: &RuneEventEntry) {
+    self.events += 1;
+
+    let total = match entry.event {
+      RuneEvent::Mint => &mut self.minted,
+      RuneEvent::Burn => &mut self.burned,
+      RuneEvent::Transfer => &mut self.credited,
+      RuneEvent::Spent => &mut self.debited,
+    };
+
+    *total = total.saturating_add(entry.amount);
+  }
+
+  /// Runes held by unspent outpoints according to the balance change events
+  pub fn outstanding(&self) -> Option<u128> {
+    self.credited.checked_sub(self.debited)
+  }
+}
+
+pub(crate) type RuneEventTotalsValue = (
+  u64,  // events
+  u128, // minted
+  u128, // burned
+  u128, // credited
+  u128, // debited
+);
+
+impl Entry for RuneEventTotals {
+  type Value = RuneEventTotalsValue;
+
+  fn load((events, minted, burned, credited, debited): RuneEventTotalsValue) -> Self {
+    Self {
+      events,
+      minted,
+      burned,
+      credited,
+      debited,
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.events,
+      self.minted,
+      self.burned,
+      self.credited,
+      self.debited,
+    )
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
 pub struct BlockId {
   pub block: u64,
   pub index: u32,
 }
 
+impl Display for BlockId {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}:{}", self.block, self.index)
+  }
+}
+
+impl FromStr for BlockId {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (block, index) = s
+      .split_once(':')
+      .ok_or_else(|| anyhow!("invalid block id: {s}"))?;
+
+    Ok(Self {
+      block: block.parse()?,
+      index: index.parse()?,
+    })
+  }
+}
+
 pub(crate) type BlockIdValue = (u64, u32);
 
 impl Entry for BlockId {
@@ -102,17 +261,17 @@ pub(crate) type RuneChangesValue = (
 );
 
 pub(crate) type RuneEventEntryValue = (
-  u64,                // seq_no
-  (u64, u32),         // rune_id
-  u8,                 // network
-  u8,                 // event
-  (u128, u128),       // source
-  u32,                // height
-  (u128, u128),       // txid
-  (u128, u128, u128), // script_pubkey
-  u128,               // amount
-  u32,                // timestamp
-  i32,                // vout
+  u64,               // seq_no
+  (u64, u32),        // rune_id
+  u8,                // network
+  u8,                // event
+  (u128, u128),      // source
+  u32,               // height
+  (u128, u128),      // txid
+  ScriptPubkeyValue, // script_pubkey
+  u128,              // amount
+  u32,               // timestamp
+  i32,               // vout
 );
 
 impl Entry for RuneEventEntry {
@@ -45,11 +45,23 @@ pub struct Options {
   pub(crate) height_limit: Option<u32>,
   #[arg(long, help = "Use index at <INDEX>.")]
   pub(crate) index: Option<PathBuf>,
+  #[arg(long, help = "Track unspent outputs by address.")]
+  pub(crate) index_addresses: bool,
+  #[arg(
+    long,
+    help = "Keep a log of recent index events, from which `/events/stream` subscribers resume."
+  )]
+  pub(crate) index_events: bool,
   #[arg(
     long,
     help = "Set index cache size to <INDEX_CACHE_SIZE> bytes. [default: 1/4 available RAM]"
   )]
   pub(crate) index_cache_size: Option<usize>,
+  #[arg(
+    long,
+    help = "Read blocks directly from Bitcoin Core's blk*.dat files in <BITCOIN_DATA_DIR>."
+  )]
+  pub(crate) index_from_blk_files: bool,
   #[arg(
     long,
     help = "Track location of runes. RUNES ARE IN AN UNFINISHED PRE-ALPHA STATE AND SUBJECT TO CHANGE AT ANY TIME."
@@ -59,6 +71,11 @@ pub struct Options {
   pub(crate) index_sats: bool,
   #[arg(long, help = "Keep sat index entries of spent outputs.")]
   pub(crate) index_spent_sats: bool,
+  #[arg(
+    long,
+    help = "Parse inscriptions and runestones on <INDEX_THREADS> threads while indexing, or on the indexing thread if 0. [default: available parallelism]"
+  )]
+  pub(crate) index_threads: Option<usize>,
   #[arg(long, help = "Store transactions in index.")]
   pub(crate) index_transactions: bool,
   #[arg(long, help = "Run in integration test mode.")]
@@ -82,6 +99,11 @@ pub struct Options {
     help = "Require basic HTTP authentication with <SERVER_USERNAME>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
   )]
   pub(crate) server_username: Option<String>,
+  #[arg(
+    long,
+    help = "Queue index events for the webhooks configured in <WEBHOOKS> YAML file. Queued events are delivered by `ord server`."
+  )]
+  pub(crate) webhooks: Option<PathBuf>,
   #[arg(long, short, help = "Use regtest. Equivalent to `--chain regtest`.")]
   pub(crate) regtest: bool,
   #[arg(long, short, help = "Use signet. Equivalent to `--chain signet`.")]
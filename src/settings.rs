@@ -17,16 +17,21 @@ pub struct Settings {
   height_limit: Option<u32>,
   hidden: Option<HashSet<InscriptionId>>,
   index: Option<PathBuf>,
+  index_addresses: bool,
   index_cache_size: Option<usize>,
+  index_events: bool,
+  index_from_blk_files: bool,
   index_runes: bool,
   index_sats: bool,
   index_spent_sats: bool,
+  index_threads: Option<usize>,
   index_transactions: bool,
   integration_test: bool,
   no_index_inscriptions: bool,
   server_password: Option<String>,
   server_url: Option<String>,
   server_username: Option<String>,
+  webhooks: Option<PathBuf>,
 }
 
 impl Settings {
@@ -131,16 +136,21 @@ impl Settings {
           .collect(),
       ),
       index: self.index.or(source.index),
+      index_addresses: self.index_addresses || source.index_addresses,
       index_cache_size: self.index_cache_size.or(source.index_cache_size),
+      index_events: self.index_events || source.index_events,
+      index_from_blk_files: self.index_from_blk_files || source.index_from_blk_files,
       index_runes: self.index_runes || source.index_runes,
       index_sats: self.index_sats || source.index_sats,
       index_spent_sats: self.index_spent_sats || source.index_spent_sats,
+      index_threads: self.index_threads.or(source.index_threads),
       index_transactions: self.index_transactions || source.index_transactions,
       integration_test: self.integration_test || source.integration_test,
       no_index_inscriptions: self.no_index_inscriptions || source.no_index_inscriptions,
       server_password: self.server_password.or(source.server_password),
       server_url: self.server_url.or(source.server_url),
       server_username: self.server_username.or(source.server_username),
+      webhooks: self.webhooks.or(source.webhooks),
     }
   }
 
@@ -165,16 +175,21 @@ impl Settings {
       height_limit: options.height_limit,
       hidden: None,
       index: options.index,
+      index_addresses: options.index_addresses,
       index_cache_size: options.index_cache_size,
+      index_events: options.index_events,
+      index_from_blk_files: options.index_from_blk_files,
       index_runes: options.index_runes,
       index_sats: options.index_sats,
       index_spent_sats: options.index_spent_sats,
+      index_threads: options.index_threads,
       index_transactions: options.index_transactions,
       integration_test: options.integration_test,
       no_index_inscriptions: options.no_index_inscriptions,
       server_password: options.server_password,
       server_url: None,
       server_username: options.server_username,
+      webhooks: options.webhooks,
     }
   }
 
@@ -243,16 +258,21 @@ impl Settings {
       height_limit: get_u32("HEIGHT_LIMIT")?,
       hidden: inscriptions("HIDDEN")?,
       index: get_path("INDEX"),
+      index_addresses: get_bool("INDEX_ADDRESSES"),
       index_cache_size: get_usize("INDEX_CACHE_SIZE")?,
+      index_events: get_bool("INDEX_EVENTS"),
+      index_from_blk_files: get_bool("INDEX_FROM_BLK_FILES"),
       index_runes: get_bool("INDEX_RUNES"),
       index_sats: get_bool("INDEX_SATS"),
       index_spent_sats: get_bool("INDEX_SPENT_SATS"),
+      index_threads: get_usize("INDEX_THREADS")?,
       index_transactions: get_bool("INDEX_TRANSACTIONS"),
       integration_test: get_bool("INTEGRATION_TEST"),
       no_index_inscriptions: get_bool("NO_INDEX_INSCRIPTIONS"),
       server_password: get_string("SERVER_PASSWORD"),
       server_url: get_string("SERVER_URL"),
       server_username: get_string("SERVER_USERNAME"),
+      webhooks: get_path("WEBHOOKS"),
     })
   }
 
@@ -272,16 +292,21 @@ impl Settings {
       height_limit: None,
       hidden: None,
       index: None,
+      index_addresses: false,
       index_cache_size: None,
+      index_events: false,
+      index_from_blk_files: false,
       index_runes: true,
       index_sats: true,
       index_spent_sats: false,
+      index_threads: None,
       index_transactions: false,
       integration_test: false,
       no_index_inscriptions: false,
       server_password: None,
       server_url: Some(server_url.into()),
       server_username: None,
+      webhooks: None,
     }
   }
 
@@ -344,6 +369,7 @@ impl Settings {
       height_limit: self.height_limit,
       hidden: self.hidden,
       index: Some(index),
+      index_addresses: self.index_addresses,
       index_cache_size: Some(match self.index_cache_size {
         Some(index_cache_size) => index_cache_size,
         None => {
@@ -352,15 +378,23 @@ impl Settings {
           usize::try_from(sys.total_memory() / 4)?
         }
       }),
+      index_events: self.index_events,
+      index_from_blk_files: self.index_from_blk_files,
       index_runes: self.index_runes,
       index_sats: self.index_sats,
       index_spent_sats: self.index_spent_sats,
+      index_threads: Some(self.index_threads.unwrap_or_else(|| {
+        thread::available_parallelism()
+          .map(|threads| threads.get())
+          .unwrap_or(1)
+      })),
       index_transactions: self.index_transactions,
       integration_test: self.integration_test,
       no_index_inscriptions: self.no_index_inscriptions,
       server_password: self.server_password,
       server_url: self.server_url,
       server_username: self.server_username,
+      webhooks: self.webhooks,
     })
   }
 
@@ -445,6 +479,13 @@ impl Settings {
     Ok(client)
   }
 
+  pub(crate) fn blocks_dir(&self) -> PathBuf {
+    self
+      .chain()
+      .join_with_data_dir(self.bitcoin_data_dir.as_ref().unwrap())
+      .join("blocks")
+  }
+
   pub(crate) fn chain(&self) -> Chain {
     self.chain.unwrap()
   }
@@ -506,6 +547,18 @@ impl Settings {
     self.index.as_ref().unwrap()
   }
 
+  pub(crate) fn index_addresses(&self) -> bool {
+    self.index_addresses
+  }
+
+  pub(crate) fn index_events(&self) -> bool {
+    self.index_events
+  }
+
+  pub(crate) fn index_from_blk_files(&self) -> bool {
+    self.index_from_blk_files
+  }
+
   pub(crate) fn index_inscriptions(&self) -> bool {
     !self.no_index_inscriptions
   }
@@ -526,6 +579,10 @@ impl Settings {
     self.index_spent_sats
   }
 
+  pub(crate) fn index_threads(&self) -> usize {
+    self.index_threads.unwrap()
+  }
+
   pub(crate) fn index_transactions(&self) -> bool {
     self.index_transactions
   }
@@ -553,6 +610,10 @@ impl Settings {
   pub(crate) fn server_url(&self) -> Option<&str> {
     self.server_url.as_deref()
   }
+
+  pub(crate) fn webhooks(&self) -> Option<&Path> {
+    self.webhooks.as_deref()
+  }
 }
 
 #[cfg(test)]
@@ -754,6 +815,14 @@ mod tests {
     }));
   }
 
+  #[test]
+  fn blocks_dir_is_in_chain_bitcoin_data_dir() {
+    assert_eq!(
+      parse(&["--bitcoin-data-dir=foo", "--chain=signet"]).blocks_dir(),
+      Path::new("foo").join("signet").join("blocks"),
+    );
+  }
+
   #[test]
   fn mainnet_data_dir() {
     let data_dir = parse(&[]).data_dir().display().to_string();
@@ -991,16 +1060,21 @@ mod tests {
       ("HEIGHT_LIMIT", "3"),
       ("HIDDEN", "6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799i0 703e5f7c49d82aab99e605af306b9a30e991e57d42f982908a962a81ac439832i0"),
       ("INDEX", "index"),
+      ("INDEX_ADDRESSES", "1"),
       ("INDEX_CACHE_SIZE", "4"),
+      ("INDEX_EVENTS", "1"),
+      ("INDEX_FROM_BLK_FILES", "1"),
       ("INDEX_RUNES", "1"),
       ("INDEX_SATS", "1"),
       ("INDEX_SPENT_SATS", "1"),
+      ("INDEX_THREADS", "2"),
       ("INDEX_TRANSACTIONS", "1"),
       ("INTEGRATION_TEST", "1"),
       ("NO_INDEX_INSCRIPTIONS", "1"),
       ("SERVER_PASSWORD", "server password"),
       ("SERVER_URL", "server url"),
       ("SERVER_USERNAME", "server username"),
+      ("WEBHOOKS", "webhooks"),
     ]
     .into_iter()
     .map(|(key, value)| (key.into(), value.into()))
@@ -1034,16 +1108,21 @@ mod tests {
           .collect()
         ),
         index: Some("index".into()),
+        index_addresses: true,
         index_cache_size: Some(4),
+        index_events: true,
+        index_from_blk_files: true,
         index_runes: true,
         index_sats: true,
         index_spent_sats: true,
+        index_threads: Some(2),
         index_transactions: true,
         integration_test: true,
         no_index_inscriptions: true,
         server_password: Some("server password".into()),
         server_url: Some("server url".into()),
         server_username: Some("server username".into()),
+        webhooks: Some("webhooks".into()),
       }
     );
   }
@@ -1066,16 +1145,21 @@ mod tests {
           "--datadir=/data/dir",
           "--first-inscription-height=2",
           "--height-limit=3",
+          "--index-addresses",
           "--index-cache-size=4",
+          "--index-events",
+          "--index-from-blk-files",
           "--index-runes",
           "--index-sats",
           "--index-spent-sats",
+          "--index-threads=2",
           "--index-transactions",
           "--index=index",
           "--integration-test",
           "--no-index-inscriptions",
           "--server-password=server password",
           "--server-username=server username",
+          "--webhooks=webhooks",
         ])
         .unwrap()
       ),
@@ -1094,16 +1178,21 @@ mod tests {
         height_limit: Some(3),
         hidden: None,
         index: Some("index".into()),
+        index_addresses: true,
         index_cache_size: Some(4),
+        index_events: true,
+        index_from_blk_files: true,
         index_runes: true,
         index_sats: true,
         index_spent_sats: true,
+        index_threads: Some(2),
         index_transactions: true,
         integration_test: true,
         no_index_inscriptions: true,
         server_password: Some("server password".into()),
         server_url: None,
         server_username: Some("server username".into()),
+        webhooks: Some("webhooks".into()),
       }
     );
   }
@@ -2,14 +2,17 @@ use super::*;
 
 mod export;
 pub mod info;
+mod snapshot;
 mod update;
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
-  #[command(about = "Write inscription numbers and ids to a tab-separated file")]
+  #[command(about = "Export inscription numbers and ids, or rune and inscription tables")]
   Export(export::Export),
   #[command(about = "Print index statistics")]
   Info(info::Info),
+  #[command(about = "Create and load rune index snapshots")]
+  Snapshot(snapshot::Snapshot),
   #[command(about = "Update the index", alias = "run")]
   Update,
 }
@@ -19,6 +22,7 @@ impl IndexSubcommand {
     match self {
       Self::Export(export) => export.run(settings),
       Self::Info(info) => info.run(settings),
+      Self::Snapshot(snapshot) => snapshot.run(settings),
       Self::Update => update::run(settings),
     }
   }
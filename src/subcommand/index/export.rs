@@ -1,11 +1,41 @@
-use super::*;
+use {super::*, crate::index::export::ExportFormat};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Export {
-  #[arg(long, help = "Include addresses in export")]
+  #[arg(long, requires = "tsv", help = "Include addresses in export")]
   include_addresses: bool,
-  #[arg(long, help = "Write export to <TSV>")]
-  tsv: String,
+  #[arg(
+    long,
+    required_unless_present = "output",
+    conflicts_with = "output",
+    help = "Write export to <TSV>"
+  )]
+  tsv: Option<String>,
+  #[arg(
+    long,
+    help = "Write rune and inscription tables to files in <OUTPUT> directory"
+  )]
+  output: Option<PathBuf>,
+  #[arg(
+    long,
+    value_enum,
+    default_value = "json-lines",
+    requires = "output",
+    help = "Write tables in <FORMAT>"
+  )]
+  format: ExportFormat,
+  #[arg(
+    long,
+    requires = "output",
+    help = "Only export rows from blocks at or above <FROM_HEIGHT>"
+  )]
+  from_height: Option<u32>,
+  #[arg(
+    long,
+    requires = "output",
+    help = "Only export rows from blocks at or below <TO_HEIGHT>"
+  )]
+  to_height: Option<u32>,
 }
 
 impl Export {
@@ -13,7 +43,17 @@ impl Export {
     let index = Index::open(&settings)?;
 
     index.update()?;
-    index.export(&self.tsv, self.include_addresses)?;
+
+    if let Some(output) = self.output {
+      return Ok(Some(Box::new(index.export_tables(
+        &output,
+        self.format,
+        self.from_height,
+        self.to_height,
+      )?)));
+    }
+
+    index.export(&self.tsv.unwrap(), self.include_addresses)?;
 
     Ok(None)
   }
@@ -0,0 +1,46 @@
+use {super::*, bitcoin::hashes::sha256};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Snapshot {
+  #[command(subcommand)]
+  subcommand: SnapshotSubcommand,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) enum SnapshotSubcommand {
+  #[command(about = "Update the index and write its rune state to a snapshot")]
+  Create(Create),
+  #[command(about = "Bootstrap an empty index from a snapshot")]
+  Load(Load),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[arg(help = "Write snapshot to <SNAPSHOT>.")]
+  snapshot: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Load {
+  #[arg(
+    long,
+    help = "Require snapshot to hash to <HASH>, as reported by `ord index snapshot create`."
+  )]
+  hash: sha256::Hash,
+  #[arg(help = "Read snapshot from <SNAPSHOT>.")]
+  snapshot: PathBuf,
+}
+
+impl Snapshot {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    Ok(Some(Box::new(match self.subcommand {
+      SnapshotSubcommand::Create(create) => {
+        index.update()?;
+        index.create_snapshot(&create.snapshot)?
+      }
+      SnapshotSubcommand::Load(load) => index.load_snapshot(&load.snapshot, load.hash)?,
+    })))
+  }
+}
@@ -1,5 +1,28 @@
 use super::*;
 
+pub mod check;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Runes {
+  #[command(subcommand)]
+  subcommand: Option<RunesSubcommand>,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) enum RunesSubcommand {
+  #[command(about = "Check whether a rune name can be etched")]
+  Check(check::Check),
+}
+
+impl Runes {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    match self.subcommand {
+      Some(RunesSubcommand::Check(check)) => check.run(settings),
+      None => run(settings),
+    }
+  }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
   pub runes: BTreeMap<Rune, RuneInfo>,
@@ -0,0 +1,46 @@
+use {
+  super::*,
+  crate::{templates::RuneCheckJson, wallet::Wallet},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Check {
+  #[arg(help = "Check rune <NAME>.")]
+  name: SpacedRune,
+  #[arg(
+    long,
+    default_value = "ord",
+    help = "Look for pending etchings in wallet named <WALLET>."
+  )]
+  wallet: String,
+}
+
+impl Check {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    ensure!(
+      index.has_rune_index(),
+      "`ord runes check` requires index created with `--index-runes` flag",
+    );
+
+    index.update()?;
+
+    let pending_etching = Wallet::has_pending_etching(&self.wallet, &settings, self.name.rune)?;
+
+    let check = index.rune_check(self.name)?;
+
+    if pending_etching.is_none() {
+      log::warn!(
+        "wallet `{}` is in use by another ord process, pending etchings were not checked",
+        self.wallet
+      );
+    }
+
+    Ok(Some(Box::new(RuneCheckJson {
+      available: check.available && pending_etching != Some(true),
+      pending_etching,
+      ..check
+    })))
+  }
+}
@@ -4,10 +4,11 @@ use crate::indexer::{
     InscriptionInfo,
   },
   inscription_transfer::InscriptionTransfer,
-  rune_event::RuneEventResponse,
+  rune_event::{RuneEvent, RuneEventResponse},
 };
 use crate::templates::{
-  InscriptionsEntriesJson, InscriptionsTransfersJson, RunesEventsJson, StatsUpdaterJson,
+  InscriptionsEntriesJson, InscriptionsTransfersJson, ReorgsJson, RuneEventsJson, RuneHolderJson,
+  RuneHoldersJson, RunesAddressEventsJson, RunesEventsJson, StatsUpdaterJson,
 };
 // @todo br-indexer: above
 
@@ -16,6 +17,8 @@ use {
     accept_encoding::AcceptEncoding,
     accept_json::AcceptJson,
     error::{OptionExt, ServerError, ServerResult},
+    event_stream::EventStream,
+    webhooks::{DeadLettersJson, Webhooks},
   },
   super::*,
   crate::templates::{
@@ -28,8 +31,11 @@ use {
   axum::{
     body,
     extract::{DefaultBodyLimit, Extension, Json, Path, Query},
-    http::{header, HeaderValue, StatusCode, Uri},
-    response::{IntoResponse, Redirect, Response},
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{
+      sse::{self, KeepAlive, Sse},
+      IntoResponse, Redirect, Response,
+    },
     routing::{get, post},
     Router,
   },
@@ -45,13 +51,17 @@ use {
   std::{cmp::Ordering, str, sync::Arc},
   tokio_stream::StreamExt,
   tower_http::{
-    compression::CompressionLayer,
+    compression::{
+      predicate::{DefaultPredicate, NotForContentType, Predicate},
+      CompressionLayer,
+    },
     cors::{Any, CorsLayer},
     set_header::SetResponseHeaderLayer,
     validate_request::ValidateRequestHeaderLayer,
   },
 };
 
+use crate::index::event::{Event, EventId};
 use crate::indexer::rune_event::BlockId;
 use crate::templates::indexer::RunesChangesJson;
 pub(crate) use server_config::ServerConfig;
@@ -59,8 +69,10 @@ pub(crate) use server_config::ServerConfig;
 mod accept_encoding;
 mod accept_json;
 mod error;
+mod event_stream;
 pub mod query;
 mod server_config;
+mod webhooks;
 
 enum SpawnConfig {
   Https(AxumAcceptor),
@@ -79,6 +91,66 @@ struct Pagination {
   size: Option<u32>,
 }
 
+// @br-indexer: largest page of a cursor paginated endpoint
+const MAX_PAGE_SIZE: u32 = 1000;
+
+// @br-indexer: requested page size, 100 if absent, capped at MAX_PAGE_SIZE
+fn page_size(size: Option<u32>) -> usize {
+  usize::try_from(size.unwrap_or(100).clamp(1, MAX_PAGE_SIZE)).unwrap()
+}
+
+// @br-indexer: cursor pagination and filters for rune events
+#[derive(Deserialize)]
+struct RunesEventsQuery {
+  cursor: Option<DeserializeFromStr<BlockId>>,
+  size: Option<u32>,
+  rune: Option<DeserializeFromStr<query::Rune>>,
+  event: Option<DeserializeFromStr<RuneEvent>>,
+}
+
+// @br-indexer: height bounds, cursor pagination and filter for a rune's events
+#[derive(Deserialize)]
+struct RuneEventsQuery {
+  cursor: Option<DeserializeFromStr<BlockId>>,
+  size: Option<u32>,
+  from_height: Option<u64>,
+  to_height: Option<u64>,
+  event: Option<DeserializeFromStr<RuneEvent>>,
+}
+
+// @br-indexer: cursor pagination for a rune's holders, keyed by script pubkey
+#[derive(Deserialize)]
+struct RuneHoldersQuery {
+  cursor: Option<String>,
+  size: Option<u32>,
+}
+
+// @br-indexer: cursor pagination for an address's outputs, by outpoint
+#[derive(Deserialize)]
+struct AddressQuery {
+  cursor: Option<DeserializeFromStr<OutPoint>>,
+  size: Option<u32>,
+}
+
+// @br-indexer: cursor pagination for the reorg log, by sequence number
+#[derive(Deserialize)]
+struct ReorgsQuery {
+  cursor: Option<u32>,
+  size: Option<u32>,
+}
+
+// @br-indexer: height of the first block whose events a stream subscriber wants
+#[derive(Deserialize)]
+struct EventsStreamQuery {
+  from: Option<u32>,
+}
+
+// @br-indexer: block hash a per-block read must still match on the indexed chain
+#[derive(Deserialize)]
+struct ExpectedHash {
+  expected_hash: Option<BlockHash>,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -162,7 +234,36 @@ pub struct Server {
 
 impl Server {
   pub fn run(self, settings: Settings, index: Arc<Index>, handle: Handle) -> SubcommandResult {
+    self.run_with_event_receiver(settings, index, handle, None)
+  }
+
+  pub fn run_with_event_receiver(
+    self,
+    settings: Settings,
+    index: Arc<Index>,
+    handle: Handle,
+    event_receiver: Option<tokio::sync::mpsc::Receiver<Event>>,
+  ) -> SubcommandResult {
     Runtime::new()?.block_on(async {
+      let event_stream = Arc::new(EventStream::new(index.clone()));
+
+      if let Some(mut event_receiver) = event_receiver {
+        let event_stream = event_stream.clone();
+
+        // stream subscribers read events from the index's event log, and are
+        // only woken up here
+        tokio::spawn(async move {
+          while event_receiver.recv().await.is_some() {
+            event_stream.notify();
+          }
+        });
+      }
+
+      // webhook deliveries are queued by the index, and delivered from here
+      if !index.webhooks().is_empty() {
+        Arc::new(Webhooks::new(index.clone())?).deliver();
+      }
+
       let index_clone = index.clone();
       let integration_test = settings.integration_test();
 
@@ -237,6 +338,7 @@ impl Server {
         .route("/install.sh", get(Self::install_script))
         .route("/ordinal/:sat", get(Self::ordinal))
         .route("/output/:output", get(Self::output))
+        .route("/address/:address", get(Self::address))
         .route("/outputs", post(Self::outputs))
         .route("/parents/:inscription_id", get(Self::parents))
         .route(
@@ -283,6 +385,14 @@ impl Server {
         .route("/runes/spent/:block", get(Self::runes_events_spent))
         .route("/runes/events/:block", get(Self::runes_events))
         .route("/runes/events/page/:page", get(Self::runes_events_paginated))
+        .route("/runes/audit/:rune", get(Self::rune_audit))
+        .route("/runes/check/:rune", get(Self::rune_check))
+        .route("/rune/:rune/holders", get(Self::rune_holders))
+        .route(
+          "/runes/events/address/:address",
+          get(Self::runes_events_by_address),
+        )
+        .route("/rune/:rune/events", get(Self::rune_events))
         .route(
           "/inscriptions/entries/:page",
           get(Self::inscriptions_entries_paginated),
@@ -292,6 +402,9 @@ impl Server {
           get(Self::inscriptions_transfers),
         )
         .route("/stats", get(Self::stats_updater))
+        .route("/reorgs", get(Self::reorgs))
+        .route("/events/stream", get(Self::events_stream))
+        .route("/webhooks/dead-letters", get(Self::webhooks_dead_letters))
         // @br-indexer: add router --> end
         .route("/sat/:sat", get(Self::sat))
         .route("/search", get(Self::search_by_query))
@@ -302,6 +415,7 @@ impl Server {
         .route("/update", get(Self::update))
         .fallback(Self::fallback)
         .layer(Extension(index))
+        .layer(Extension(event_stream))
         .layer(Extension(server_config.clone()))
         .layer(Extension(settings.clone()))
         .layer(SetResponseHeaderLayer::if_not_present(
@@ -317,7 +431,10 @@ impl Server {
             .allow_methods([http::Method::GET])
             .allow_origin(Any),
         )
-        .layer(CompressionLayer::new())
+        // compressing would buffer `/events/stream` instead of pushing events
+        .layer(CompressionLayer::new().compress_when(
+          DefaultPredicate::new().and(NotForContentType::const_new("text/event-stream")),
+        ))
         .with_state(server_config.clone());
 
       let router = if server_config.json_api_enabled {
@@ -646,6 +763,72 @@ impl Server {
     })
   }
 
+  // @br-indexer
+  async fn address(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<String>,
+    Query(query): Query<AddressQuery>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      if !index.has_address_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address index".to_string(),
+        ));
+      }
+
+      let address = address
+        .parse::<Address<NetworkUnchecked>>()
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let mut outputs = Vec::new();
+      let mut inscriptions = Vec::new();
+      let mut sat_balance = 0;
+      let mut cardinal_sats = 0;
+      let mut runes_balances: BTreeMap<SpacedRune, Pile> = BTreeMap::new();
+
+      let (address_outputs, next) = index.get_address_outputs(
+        &address.script_pubkey(),
+        query.cursor.map(|DeserializeFromStr(cursor)| cursor),
+        page_size(query.size),
+      )?;
+
+      for (outpoint, tx_out) in address_outputs {
+        let output_inscriptions = index.get_inscriptions_on_output(outpoint)?;
+        let output_runes = index.get_rune_balances_for_outpoint(outpoint)?;
+
+        if output_inscriptions.is_empty() && output_runes.is_empty() {
+          cardinal_sats += tx_out.value;
+        }
+
+        for (spaced_rune, pile) in output_runes {
+          runes_balances
+            .entry(spaced_rune)
+            .and_modify(|balance| balance.amount += pile.amount)
+            .or_insert(pile);
+        }
+
+        outputs.push(outpoint);
+        inscriptions.extend(output_inscriptions);
+        sat_balance += tx_out.value;
+      }
+
+      Ok(
+        Json(api::AddressInfo {
+          outputs,
+          inscriptions,
+          sat_balance,
+          cardinal_sats,
+          runes_balances: runes_balances.into_iter().collect(),
+          next,
+        })
+        .into_response(),
+      )
+    })
+  }
+
   async fn outputs(
     Extension(index): Extension<Arc<Index>>,
     AcceptJson(accept_json): AcceptJson,
@@ -719,9 +902,12 @@ impl Server {
 
       let mintable = entry.mintable((block_height.n() + 1).into()).is_ok();
 
+      let holders = index.get_rune_holder_count(id)?;
+
       Ok(if accept_json {
         Json(api::Rune {
           entry,
+          holders,
           id,
           mintable,
           parent,
@@ -730,6 +916,7 @@ impl Server {
       } else {
         RuneHtml {
           entry,
+          holders,
           id,
           mintable,
           parent,
@@ -832,6 +1019,7 @@ impl Server {
         Json(StatsUpdaterJson {
           network: server_config.chain.network(),
           height: index.block_count().unwrap(),
+          tip_hash: index.block_hash(None)?,
           runes: u32::try_from(index.runes()?.len()).unwrap(),
           inscriptions: total_inscriptions,
           inscriptions_transfer: total_inscriptions_transfers,
@@ -842,6 +1030,104 @@ impl Server {
     })
   }
 
+  async fn reorgs(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<ReorgsQuery>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      let (reorgs, total, next) = index.get_reorgs(query.cursor, page_size(query.size))?;
+      Ok(
+        Json(ReorgsJson {
+          total,
+          size: u32::try_from(reorgs.len()).unwrap(),
+          next,
+          reorgs,
+          tip_hash: index.block_hash(None)?,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  async fn events_stream(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(event_stream): Extension<Arc<EventStream>>,
+    headers: HeaderMap,
+    Query(query): Query<EventsStreamQuery>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      if !index.has_event_index() {
+        return Err(ServerError::NotFound(
+          "this server has no event index".to_string(),
+        ));
+      }
+
+      // reconnecting `EventSource`s send the id of the last event they received
+      // along with the original url, so the header takes precedence
+      let after = match headers.get("last-event-id") {
+        Some(value) => Some(
+          value
+            .to_str()
+            .ok()
+            .and_then(|value| value.parse::<EventId>().ok())
+            .ok_or_else(|| ServerError::BadRequest("invalid Last-Event-ID header".into()))?,
+        ),
+        None => match query.from {
+          Some(0) => None,
+          Some(from) => Some(EventId::after_block(
+            from - 1,
+            index.block_hash(Some(from - 1))?.ok_or_else(|| {
+              ServerError::Conflict(format!("events from block {from} are not available"))
+            })?,
+          )),
+          None => index.get_event_tip()?,
+        },
+      };
+
+      let events = event_stream.subscribe(after)?.ok_or_else(|| {
+        ServerError::Conflict(match after {
+          Some(after) => format!("events after {after} are not available"),
+          None => "events from block 0 are not available".into(),
+        })
+      })?;
+
+      // reorg notices have no id, so that a subscriber reconnecting after one
+      // resumes from its last event, and is told about the reorg again
+      let stream = events.map(|(id, event)| {
+        let sse_event = sse::Event::default();
+
+        match id {
+          Some(id) => sse_event.id(id.to_string()),
+          None => sse_event,
+        }
+        .json_data(event)
+      });
+
+      Ok(
+        Sse::new(stream)
+          .keep_alive(KeepAlive::default())
+          .into_response(),
+      )
+    })
+  }
+
+  async fn webhooks_dead_letters(
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      if index.webhooks().is_empty() {
+        return Err(ServerError::NotFound("webhooks are not configured".into()));
+      }
+
+      Ok(
+        Json(DeadLettersJson {
+          dead_letters: index.get_webhook_dead_letters()?,
+        })
+        .into_response(),
+      )
+    })
+  }
+
   async fn inscriptions_entries_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -908,9 +1194,17 @@ impl Server {
         })
         .collect();
 
+      let block_hashes = index.block_hashes(
+        inscriptions_entries
+          .iter()
+          .filter_map(|(_, entry)| entry.as_ref().map(|entry| entry.height)),
+      )?;
+
       Ok(
         Json(InscriptionsEntriesJson {
           entries: inscriptions_entries,
+          block_hashes,
+          tip_hash: index.block_hash(None)?,
           page_index,
           more,
           total,
@@ -924,41 +1218,34 @@ impl Server {
   async fn runes_events_spent(
     Extension(index): Extension<Arc<Index>>,
     Path(height): Path<u64>,
+    Query(query): Query<ExpectedHash>,
   ) -> ServerResult<Response> {
-    return Self::_runes_events(&index, height, true).await;
+    return Self::_runes_events(&index, height, query.expected_hash, true).await;
   }
 
   async fn runes_events(
     Extension(index): Extension<Arc<Index>>,
     Path(height): Path<u64>,
+    Query(query): Query<ExpectedHash>,
   ) -> ServerResult<Response> {
-    return Self::_runes_events(&index, height, false).await;
+    return Self::_runes_events(&index, height, query.expected_hash, false).await;
   }
 
-  async fn _runes_events(index: &Index, height: u64, is_spent: bool) -> ServerResult<Response> {
+  async fn _runes_events(
+    index: &Index,
+    height: u64,
+    expected_hash: Option<BlockHash>,
+    is_spent: bool,
+  ) -> ServerResult<Response> {
     task::block_in_place(|| {
+      let (runes_events, total, block_hash) = index.get_runes_events_by_height(height, is_spent)?;
+      Self::check_expected_hash(height, block_hash, expected_hash)?;
       let mut data_size = 0;
-      let (runes_events, total) = index.get_runes_events_by_height(height, is_spent)?;
       let runes_events_map_address: Vec<(BlockId, RuneEventResponse)> = runes_events
         .into_iter()
         .map(|(block_id, event)| {
           data_size += 1;
-          (
-            block_id,
-            RuneEventResponse {
-              seq_no: event.seq_no,
-              rune_id: event.rune_id,
-              network: event.network,
-              event: event.event,
-              source: event.txid,
-              height: event.height,
-              txid: event.txid,
-              script_pubkey: event.script_pubkey,
-              amount: event.amount.to_string(),
-              vout: event.vout,
-              timestamp: event.timestamp,
-            },
-          )
+          (block_id, RuneEventResponse::from(event))
         })
         .collect();
 
@@ -967,6 +1254,11 @@ impl Server {
           events: runes_events_map_address,
           total,
           block: u32::try_from(height).unwrap(),
+          block_hashes: block_hash
+            .map(|block_hash| (u32::try_from(height).unwrap(), block_hash))
+            .into_iter()
+            .collect(),
+          tip_hash: index.block_hash(None)?,
           size: data_size,
         })
         .into_response(),
@@ -974,146 +1266,367 @@ impl Server {
     })
   }
 
-  async fn runes_changes(
+  async fn runes_events_by_address(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(height): Path<u64>,
+    Path(address): Path<String>,
+    Query(query): Query<RunesEventsQuery>,
   ) -> ServerResult<Response> {
     task::block_in_place(|| {
-      let (runes_changes, total) = index.get_runes_changes_by_height(height)?;
-      let size = runes_changes.len();
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let address = address
+        .parse::<Address<NetworkUnchecked>>()
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let rune_id = match query.rune {
+        Some(DeserializeFromStr(rune)) => Some(Self::rune_id(&index, rune)?),
+        None => None,
+      };
+
+      let (events, next) = index.get_runes_events_by_script_pubkey(
+        &address.script_pubkey(),
+        query.cursor.map(|DeserializeFromStr(cursor)| cursor),
+        page_size(query.size),
+        rune_id,
+        query.event.map(|DeserializeFromStr(event)| event),
+      )?;
+
+      let block_hashes = index.block_hashes(
+        events
+          .iter()
+          .map(|(block_id, _)| u32::try_from(block_id.block).unwrap()),
+      )?;
+
       Ok(
-        Json(RunesChangesJson {
-          data: runes_changes,
-          total,
-          block: u32::try_from(height).unwrap(),
-          size,
+        Json(RunesAddressEventsJson {
+          address: address.to_string(),
+          block_hashes,
+          tip_hash: index.block_hash(None)?,
+          size: u32::try_from(events.len()).unwrap(),
+          events: events
+            .into_iter()
+            .map(|(block_id, event)| (block_id, RuneEventResponse::from(event)))
+            .collect(),
+          next: next.map(|cursor| cursor.to_string()),
         })
         .into_response(),
       )
     })
   }
 
-  async fn runes_in_block(
+  async fn rune_events(
     Extension(index): Extension<Arc<Index>>,
-    Path(height): Path<u64>,
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+    Query(query): Query<RuneEventsQuery>,
   ) -> ServerResult<Response> {
     task::block_in_place(|| {
-      let runes_entries = index.get_runes_entries_in_block(u64::from(height))?;
-      Ok(Json(runes_entries).into_response())
-    })
-  }
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
 
-  async fn runes_events_paginated(
-    Extension(index): Extension<Arc<Index>>,
-    Path(page_index): Path<usize>,
-    Query(pagination): Query<Pagination>,
-  ) -> ServerResult<Response> {
-    task::block_in_place(|| {
-      let (runes_events, total) = index.get_runes_events_paginated(
-        pagination.size.unwrap_or(100),
-        u32::try_from(page_index).unwrap_or_default(),
+      if let (Some(from_height), Some(to_height)) = (query.from_height, query.to_height) {
+        if from_height > to_height {
+          return Err(ServerError::BadRequest(
+            "from_height is greater than to_height".to_string(),
+          ));
+        }
+      }
+
+      let rune_id = Self::rune_id(&index, rune_query)?;
+
+      let (events, next) = index.get_rune_events(
+        rune_id,
+        query.from_height,
+        query.to_height,
+        query.cursor.map(|DeserializeFromStr(cursor)| cursor),
+        page_size(query.size),
+        query.event.map(|DeserializeFromStr(event)| event),
       )?;
 
-      let mut data_size = 0;
-      let runes_events_map_address: Vec<(BlockId, RuneEventResponse)> = runes_events
-        .into_iter()
-        .map(|(block_id, event)| {
-          data_size += 1;
-          (
-            block_id,
-            RuneEventResponse {
-              seq_no: event.seq_no,
-              rune_id: event.rune_id,
-              network: event.network,
-              event: event.event,
-              source: event.txid,
-              height: event.height,
-              txid: event.txid,
-              script_pubkey: event.script_pubkey,
-              amount: event.amount.to_string(),
-              vout: event.vout,
-              timestamp: event.timestamp,
-            },
-          )
-        })
-        .collect();
+      let block_hashes = index.block_hashes(
+        events
+          .iter()
+          .map(|(block_id, _)| u32::try_from(block_id.block).unwrap()),
+      )?;
 
       Ok(
-        Json(RunesEventsJson {
-          events: runes_events_map_address,
-          total,
-          block: index.block_count().unwrap_or_default(),
-          size: data_size,
+        Json(RuneEventsJson {
+          rune_id,
+          block_hashes,
+          tip_hash: index.block_hash(None)?,
+          size: u32::try_from(events.len()).unwrap(),
+          events: events
+            .into_iter()
+            .map(|(block_id, event)| (block_id, RuneEventResponse::from(event)))
+            .collect(),
+          next: next.map(|cursor| cursor.to_string()),
         })
         .into_response(),
       )
     })
   }
 
-  async fn inscriptions_transfers(
+  // @br-indexer: script pubkeys currently holding a rune and their balances
+  async fn rune_holders(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(page_index): Path<u32>,
-    Query(pagination): Query<Pagination>,
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+    Query(query): Query<RuneHoldersQuery>,
   ) -> ServerResult<Response> {
     task::block_in_place(|| {
-      let page_size = pagination.size.unwrap_or(5000);
-      let mut data_size = 0;
-      let (transfers, total, more) =
-        index.get_inscriptions_transfers_paginated(page_size, page_index)?;
-      let transfers_map_address: Vec<(u32, String, InscriptionTransfer)> = transfers
-        .into_iter()
-        .map(|(inscription_id, transfer)| {
-          let mut address_output = String::new();
-          if let Ok(address) = server_config.chain.address_from_script(Script::from_bytes(
-            transfer.to_script_pubkey.clone().as_bytes(),
-          )) {
-            address_output = address.to_string();
-          }
-          data_size += 1;
-          (inscription_id, address_output.to_string(), transfer)
-        })
-        .collect();
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let rune_id = Self::rune_id(&index, rune_query)?;
+
+      let cursor = query
+        .cursor
+        .map(|cursor| ScriptBuf::from_hex(&cursor))
+        .transpose()
+        .map_err(|err| ServerError::BadRequest(format!("invalid cursor: {err}")))?;
+
+      let (holders, next) =
+        index.get_rune_holders(rune_id, cursor.as_deref(), page_size(query.size))?;
 
       Ok(
-        Json(InscriptionsTransfersJson {
-          transfers: transfers_map_address,
-          total,
-          page_index,
-          page_size: u32::try_from(data_size).unwrap(),
-          more,
+        Json(RuneHoldersJson {
+          rune_id,
+          total: index.get_rune_holder_count(rune_id)?,
+          size: u32::try_from(holders.len()).unwrap(),
+          holders: holders
+            .into_iter()
+            .map(|(script_pubkey, amount)| RuneHolderJson {
+              address: server_config
+                .chain
+                .address_from_script(&script_pubkey)
+                .ok()
+                .map(|address| address.to_string()),
+              script_pubkey,
+              amount: amount.to_string(),
+            })
+            .collect(),
+          next: next.map(|cursor| cursor.to_hex_string()),
+          tip_hash: index.block_hash(None)?,
         })
         .into_response(),
       )
     })
   }
-  // @br-indexer: add function for server --> end
 
-  async fn home(
-    Extension(server_config): Extension<Arc<ServerConfig>>,
+  async fn rune_check(
     Extension(index): Extension<Arc<Index>>,
-  ) -> ServerResult<PageHtml<HomeHtml>> {
+    Path(DeserializeFromStr(spaced_rune)): Path<DeserializeFromStr<SpacedRune>>,
+  ) -> ServerResult<Response> {
     task::block_in_place(|| {
-      Ok(
-        HomeHtml {
-          inscriptions: index.get_home_inscriptions()?,
-        }
-        .page(server_config),
-      )
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      Ok(Json(index.rune_check(spaced_rune)?).into_response())
     })
   }
 
-  async fn blocks(
-    Extension(server_config): Extension<Arc<ServerConfig>>,
+  // @br-indexer: reconciles a rune's events with its entry. Balance changes
+  // must net out to the runes that haven't been burned, and mint and burn
+  // events must add up to the entry's mints and burned amount.
+  async fn rune_audit(
     Extension(index): Extension<Arc<Index>>,
-    AcceptJson(accept_json): AcceptJson,
-  ) -> ServerResult {
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+  ) -> ServerResult<Response> {
     task::block_in_place(|| {
-      let blocks = index.blocks(100)?;
-      let mut featured_blocks = BTreeMap::new();
-      for (height, hash) in blocks.iter().take(5) {
-        let (inscriptions, _total_num) =
-          index.get_highest_paying_inscriptions_in_block(*height, 8)?;
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let rune_id = Self::rune_id(&index, rune_query)?;
+
+      Ok(
+        Json(
+          index
+            .get_rune_audit(rune_id)?
+            .ok_or_not_found(|| format!("rune {rune_id}"))?,
+        )
+        .into_response(),
+      )
+    })
+  }
+
+  fn rune_id(index: &Index, rune: query::Rune) -> ServerResult<RuneId> {
+    let rune = match rune {
+      query::Rune::Spaced(spaced_rune) => spaced_rune.rune,
+      query::Rune::Id(rune_id) => index
+        .get_rune_by_id(rune_id)?
+        .ok_or_not_found(|| format!("rune {rune_id}"))?,
+      query::Rune::Number(number) => index
+        .get_rune_by_number(usize::try_from(number).unwrap())?
+        .ok_or_not_found(|| format!("rune number {number}"))?,
+    };
+
+    Ok(
+      index
+        .rune(rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?
+        .0,
+    )
+  }
+
+  // @br-indexer: `block_hash` is read in the same transaction as the data it
+  // is returned with, so the data is from the expected block if this passes
+  fn check_expected_hash(
+    height: u64,
+    block_hash: Option<BlockHash>,
+    expected_hash: Option<BlockHash>,
+  ) -> ServerResult<()> {
+    if let Some(expected_hash) = expected_hash {
+      if block_hash != Some(expected_hash) {
+        return Err(ServerError::Conflict(format!(
+          "block {expected_hash} is not at height {height} of the indexed chain"
+        )));
+      }
+    }
+
+    Ok(())
+  }
+
+  async fn runes_changes(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u64>,
+    Query(query): Query<ExpectedHash>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      let (runes_changes, total, block_hash) = index.get_runes_changes_by_height(height)?;
+      Self::check_expected_hash(height, block_hash, query.expected_hash)?;
+      let size = runes_changes.len();
+      Ok(
+        Json(RunesChangesJson {
+          data: runes_changes,
+          total,
+          block: u32::try_from(height).unwrap(),
+          block_hash,
+          tip_hash: index.block_hash(None)?,
+          size,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  async fn runes_in_block(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u64>,
+    Query(query): Query<ExpectedHash>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      let runes_entries = index.get_runes_entries_in_block(height)?;
+      Self::check_expected_hash(height, runes_entries.block_hash, query.expected_hash)?;
+      Ok(Json(runes_entries).into_response())
+    })
+  }
+
+  async fn runes_events_paginated(
+    Extension(index): Extension<Arc<Index>>,
+    Path(page_index): Path<usize>,
+    Query(pagination): Query<Pagination>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      Ok(
+        Json(index.get_runes_events_paginated(
+          pagination.size.unwrap_or(100),
+          u32::try_from(page_index).unwrap_or_default(),
+        )?)
+        .into_response(),
+      )
+    })
+  }
+
+  async fn inscriptions_transfers(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(page_index): Path<u32>,
+    Query(pagination): Query<Pagination>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      let page_size = pagination.size.unwrap_or(5000);
+      let mut data_size = 0;
+      let (transfers, total, more) =
+        index.get_inscriptions_transfers_paginated(page_size, page_index)?;
+      let transfers_map_address: Vec<(u32, String, InscriptionTransfer)> = transfers
+        .into_iter()
+        .map(|(inscription_id, transfer)| {
+          let mut address_output = String::new();
+          if let Ok(address) = server_config.chain.address_from_script(Script::from_bytes(
+            transfer.to_script_pubkey.clone().as_bytes(),
+          )) {
+            address_output = address.to_string();
+          }
+          data_size += 1;
+          (inscription_id, address_output.to_string(), transfer)
+        })
+        .collect();
+
+      let block_hashes = index.block_hashes(
+        transfers_map_address
+          .iter()
+          .map(|(_, _, transfer)| transfer.height),
+      )?;
+
+      Ok(
+        Json(InscriptionsTransfersJson {
+          transfers: transfers_map_address,
+          block_hashes,
+          tip_hash: index.block_hash(None)?,
+          total,
+          page_index,
+          page_size: u32::try_from(data_size).unwrap(),
+          more,
+        })
+        .into_response(),
+      )
+    })
+  }
+  // @br-indexer: add function for server --> end
+
+  async fn home(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<PageHtml<HomeHtml>> {
+    task::block_in_place(|| {
+      Ok(
+        HomeHtml {
+          inscriptions: index.get_home_inscriptions()?,
+        }
+        .page(server_config),
+      )
+    })
+  }
+
+  async fn blocks(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let blocks = index.blocks(100)?;
+      let mut featured_blocks = BTreeMap::new();
+      for (height, hash) in blocks.iter().take(5) {
+        let (inscriptions, _total_num) =
+          index.get_highest_paying_inscriptions_in_block(*height, 8)?;
 
         featured_blocks.insert(*hash, inscriptions);
       }
@@ -2208,7 +2721,14 @@ impl Server {
 #[cfg(test)]
 mod tests {
   use {
-    super::*, reqwest::Url, serde::de::DeserializeOwned, std::net::TcpListener, tempfile::TempDir,
+    super::*,
+    reqwest::Url,
+    serde::de::DeserializeOwned,
+    std::{
+      io::{BufRead, BufReader, Write},
+      net::TcpListener,
+    },
+    tempfile::TempDir,
   };
 
   const RUNE: u128 = 99246114928149462;
@@ -2340,13 +2860,18 @@ mod tests {
         .or_defaults()
         .unwrap();
 
-      let index = Arc::new(Index::open(&settings).unwrap());
+      let (event_sender, event_receiver) = tokio::sync::mpsc::channel(1024);
+      let index = Arc::new(Index::open_with_event_sender(&settings, Some(event_sender)).unwrap());
       let ord_server_handle = Handle::new();
 
       {
         let index = index.clone();
         let ord_server_handle = ord_server_handle.clone();
-        thread::spawn(|| server.run(settings, index, ord_server_handle).unwrap());
+        thread::spawn(|| {
+          server
+            .run_with_event_receiver(settings, index, ord_server_handle, Some(event_receiver))
+            .unwrap()
+        });
       }
 
       while index.statistic(crate::index::Statistic::Commits) == 0 {
@@ -3198,6 +3723,8 @@ mod tests {
   <dd>100%</dd>
   <dt>burned</dt>
   <dd>0\u{A0}%</dd>
+  <dt>holders</dt>
+  <dd>1</dd>
   <dt>divisibility</dt>
   <dd>0</dd>
   <dt>symbol</dt>
@@ -4227,6 +4754,713 @@ mod tests {
     );
   }
 
+  #[test]
+  fn runes_changes_checks_expected_hash() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let block_hash = server.index.block_hash(Some(1)).unwrap().unwrap();
+
+    let changes =
+      server.get_json::<RunesChangesJson>(format!("/runes/changes/1?expected_hash={block_hash}"));
+
+    assert_eq!(changes.block_hash, Some(block_hash));
+    assert_eq!(changes.tip_hash, Some(block_hash));
+
+    server.assert_response(
+      format!("/runes/changes/1?expected_hash={}", BlockHash::all_zeros()),
+      StatusCode::CONFLICT,
+      &format!(
+        "block {} is not at height 1 of the indexed chain",
+        BlockHash::all_zeros()
+      ),
+    );
+
+    server.assert_response(
+      format!("/runes/events/2?expected_hash={block_hash}"),
+      StatusCode::CONFLICT,
+      &format!("block {block_hash} is not at height 2 of the indexed chain"),
+    );
+  }
+
+  #[test]
+  fn runes_entries_include_block_and_tip_hash() {
+    use crate::templates::RunesEntriesJson;
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let (_txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    server.mine_blocks(1);
+
+    let block_hash = server.index.block_hash(Some(8)).unwrap().unwrap();
+
+    let entries =
+      server.get_json::<RunesEntriesJson>(format!("/runes/entries/8?expected_hash={block_hash}"));
+
+    assert_eq!(entries.runes.len(), 1);
+    assert_eq!(entries.runes[0].0, id);
+    assert_eq!(entries.block, 8);
+    assert_eq!(entries.block_hash, Some(block_hash));
+    assert_eq!(entries.tip_hash, server.index.block_hash(None).unwrap());
+
+    server.assert_response(
+      format!("/runes/entries/8?expected_hash={}", BlockHash::all_zeros()),
+      StatusCode::CONFLICT,
+      &format!(
+        "block {} is not at height 8 of the indexed chain",
+        BlockHash::all_zeros()
+      ),
+    );
+  }
+
+  #[test]
+  fn rune_check() {
+    use crate::templates::RuneCheckJson;
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let spaced_rune = SpacedRune {
+      rune: Rune(RUNE),
+      spacers: 0,
+    };
+
+    pretty_assert_eq!(
+      server.get_json::<RuneCheckJson>(format!("/runes/check/{spaced_rune}")),
+      RuneCheckJson {
+        rune: spaced_rune,
+        available: true,
+        reserved: false,
+        etched: None,
+        height: 2,
+        minimum: Rune::minimum_at_height(Network::Regtest, Height(2)),
+        unlocked: true,
+        unlock_height: Some(0),
+        commitment: hex::encode(Rune(RUNE).commitment()),
+        commit_confirmations: 6,
+        reveal_height: Some(7),
+        mempool: Vec::new(),
+        pending_etching: None,
+        tip_hash: server.index.block_hash(None).unwrap(),
+      }
+    );
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          etching: Some(Etching {
+            rune: Some(Rune(RUNE)),
+            ..default()
+          }),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    let check = server.get_json::<RuneCheckJson>(format!("/runes/check/{spaced_rune}"));
+
+    assert!(!check.available);
+    assert_eq!(check.mempool, [txid]);
+
+    server.mine_blocks(1);
+
+    let (_txid, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    server.mine_blocks(1);
+
+    let check = server.get_json::<RuneCheckJson>(format!("/runes/check/{spaced_rune}"));
+
+    assert!(!check.available);
+    assert_eq!(check.etched, Some(id));
+    assert!(check.mempool.is_empty());
+
+    let locked = SpacedRune {
+      rune: Rune(0),
+      spacers: 0,
+    };
+
+    let check = server.get_json::<RuneCheckJson>(format!("/runes/check/{locked}"));
+
+    assert!(!check.available);
+    assert!(!check.reserved);
+    assert!(!check.unlocked);
+    assert_eq!(check.etched, None);
+
+    let reserved = SpacedRune {
+      rune: Rune::reserved(0, 0),
+      spacers: 0,
+    };
+
+    let check = server.get_json::<RuneCheckJson>(format!("/runes/check/{reserved}"));
+
+    assert!(!check.available);
+    assert!(check.reserved);
+    assert!(!check.unlocked);
+    assert_eq!(check.unlock_height, None);
+    assert_eq!(check.reveal_height, None);
+  }
+
+  #[test]
+  fn rune_audit_reconciles_events_with_entry() {
+    use crate::templates::RuneAuditJson;
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/runes/audit/1:1",
+      StatusCode::NOT_FOUND,
+      "rune 1:1 not found",
+    );
+
+    let (_txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          terms: Some(Terms {
+            amount: Some(10),
+            cap: Some(10),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    server.mine_blocks(1);
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        usize::try_from(server.index.block_count().unwrap()).unwrap() - 1,
+        0,
+        0,
+        Witness::new(),
+      )],
+      op_return: Some(
+        Runestone {
+          mint: Some(id),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 100,
+            output: 0,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      op_return_index: Some(0),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    pretty_assert_eq!(
+      server.get_json::<RuneAuditJson>(format!("/runes/audit/{id}")),
+      RuneAuditJson {
+        rune_id: id,
+        spaced_rune: SpacedRune {
+          rune: Rune(RUNE),
+          spacers: 0,
+        },
+        supply: "1010".into(),
+        burned: "100".into(),
+        minted: "10".into(),
+        events: 6,
+        event_minted: "10".into(),
+        event_burned: "100".into(),
+        credited: "1910".into(),
+        debited: "1000".into(),
+        outstanding: Some("910".into()),
+        balanced: true,
+        tip_hash: server.index.block_hash(None).unwrap(),
+      }
+    );
+
+    let events = server
+      .get_json::<RuneEventsJson>(format!("/rune/{id}/events"))
+      .events;
+
+    assert_eq!(events.len(), 6);
+
+    assert!(events
+      .iter()
+      .filter(|(_, event)| !event.event.is_balance_change())
+      .all(|(_, event)| event.direction.is_none() && event.delta.is_none()));
+
+    // supply minus burned
+    assert_eq!(
+      events
+        .iter()
+        .filter_map(|(_, event)| event.delta.as_ref())
+        .map(|delta| delta.parse::<i128>().unwrap())
+        .sum::<i128>(),
+      910,
+    );
+  }
+
+  #[test]
+  fn rune_holders() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/rune/1:1/holders",
+      StatusCode::NOT_FOUND,
+      "rune 1:1 not found",
+    );
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let script_pubkey = server.core.tx_by_id(txid).output[0].script_pubkey.clone();
+
+    pretty_assert_eq!(
+      server.get_json::<RuneHoldersJson>(format!("/rune/{id}/holders")),
+      RuneHoldersJson {
+        rune_id: id,
+        holders: vec![RuneHolderJson {
+          address: Some(
+            Chain::Regtest
+              .address_from_script(&script_pubkey)
+              .unwrap()
+              .to_string()
+          ),
+          script_pubkey: script_pubkey.clone(),
+          amount: "1000".into(),
+        }],
+        total: 1,
+        size: 1,
+        next: None,
+        tip_hash: server.index.block_hash(None).unwrap(),
+      }
+    );
+
+    assert_eq!(
+      server.get_json::<api::Rune>(format!("/rune/{id}")).holders,
+      1
+    );
+
+    assert_eq!(
+      server
+        .get_json::<RuneHoldersJson>(format!(
+          "/rune/{id}/holders?cursor={}",
+          script_pubkey.to_hex_string()
+        ))
+        .holders,
+      Vec::new()
+    );
+
+    server.assert_response(
+      format!("/rune/{id}/holders?cursor=xyz"),
+      StatusCode::BAD_REQUEST,
+      "invalid cursor: odd hex string length 3",
+    );
+  }
+
+  #[test]
+  fn address_lists_outputs_inscriptions_and_runes() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-addresses")
+      .build();
+
+    server.mine_blocks(1);
+
+    let (etching, _id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let inscription = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(4, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    let cardinal = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(5, 0, 0, Witness::new())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let address = server.core.address(OutPoint {
+      txid: cardinal,
+      vout: 0,
+    });
+
+    let value = |txid| server.core.tx_by_id(txid).output[0].value;
+
+    let mut info = server.get_json::<api::AddressInfo>(format!("/address/{address}"));
+
+    let first = server.get_json::<api::AddressInfo>(format!("/address/{address}?size=2"));
+
+    assert_eq!(first.outputs, info.outputs[..2]);
+    assert_eq!(first.next, Some(info.outputs[1]));
+
+    let second = server.get_json::<api::AddressInfo>(format!(
+      "/address/{address}?size=2&cursor={}",
+      first.next.unwrap()
+    ));
+
+    assert_eq!(second.outputs, info.outputs[2..]);
+    assert_eq!(second.next, None);
+    assert_eq!(first.sat_balance + second.sat_balance, info.sat_balance);
+
+    info.outputs.sort();
+
+    let mut outputs = [etching, inscription, cardinal].map(|txid| OutPoint { txid, vout: 0 });
+
+    outputs.sort();
+
+    pretty_assert_eq!(
+      info,
+      api::AddressInfo {
+        outputs: outputs.into(),
+        inscriptions: vec![InscriptionId {
+          txid: inscription,
+          index: 0,
+        }],
+        sat_balance: value(etching) + value(inscription) + value(cardinal),
+        cardinal_sats: value(cardinal),
+        runes_balances: vec![(
+          SpacedRune {
+            rune: Rune(RUNE),
+            spacers: 0,
+          },
+          Pile {
+            amount: 1000,
+            divisibility: 0,
+            symbol: None,
+          },
+        )],
+        next: None,
+      }
+    );
+  }
+
+  #[test]
+  fn address_requires_address_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.assert_response(
+      "/address/bcrt1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqdku202",
+      StatusCode::NOT_FOUND,
+      "this server has no address index",
+    );
+  }
+
+  #[test]
+  fn events_stream_requires_event_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.assert_response(
+      "/events/stream",
+      StatusCode::NOT_FOUND,
+      "this server has no event index",
+    );
+  }
+
+  #[test]
+  fn events_stream() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-events")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/events/stream?from=4",
+      StatusCode::CONFLICT,
+      "events from block 4 are not available",
+    );
+
+    let response = reqwest::blocking::Client::new()
+      .get(server.join_url("/events/stream"))
+      .header("last-event-id", "foo")
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let mut response = reqwest::blocking::get(server.join_url("/events/stream?from=0")).unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut received = String::new();
+    let mut buffer = [0; 1024];
+
+    while !received.contains("\n\n") {
+      let n = response.read(&mut buffer).unwrap();
+      assert!(n > 0);
+      received.push_str(str::from_utf8(&buffer[..n]).unwrap());
+    }
+
+    let (id, data) = received.split_once('\n').unwrap();
+
+    assert_eq!(
+      id,
+      format!(
+        "id:2:0:{}",
+        server.index.block_hash(Some(2)).unwrap().unwrap()
+      ),
+    );
+
+    let data =
+      serde_json::from_str::<serde_json::Value>(data.trim().strip_prefix("data:").unwrap())
+        .unwrap();
+
+    assert_eq!(data["type"], "inscription_created");
+    assert_eq!(
+      data["inscription_id"],
+      InscriptionId { txid, index: 0 }.to_string()
+    );
+  }
+
+  #[test]
+  fn webhooks() {
+    let (request_sender, request_receiver) = std::sync::mpsc::channel();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        assert!(request_line.starts_with("POST / "));
+
+        let mut content_length = 0;
+        let mut signature = None;
+
+        loop {
+          let mut line = String::new();
+          reader.read_line(&mut line).unwrap();
+
+          let Some((name, value)) = line.trim_end().split_once(": ") else {
+            break;
+          };
+
+          match name.to_lowercase().as_str() {
+            "content-length" => content_length = value.parse().unwrap(),
+            "x-ord-signature" => signature = Some(value.to_string()),
+            _ => {}
+          }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        stream
+          .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+          .unwrap();
+
+        request_sender.send((signature.unwrap(), body)).unwrap();
+      }
+    });
+
+    let closed_port = TcpListener::bind("127.0.0.1:0")
+      .unwrap()
+      .local_addr()
+      .unwrap()
+      .port();
+
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join("webhooks.yaml");
+
+    fs::write(
+      &path,
+      format!(
+        "
+- url: http://127.0.0.1:{port}/
+  secret: foo
+  events: [inscription_created]
+- url: http://127.0.0.1:{closed_port}/
+  secret: bar
+  max_attempts: 1
+"
+      ),
+    )
+    .unwrap();
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_option("--webhooks", path.to_str().unwrap())
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let (signature, body) = request_receiver
+      .recv_timeout(Duration::from_secs(30))
+      .unwrap();
+
+    assert_eq!(signature, webhooks::signature("foo", &body));
+
+    let payload = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+
+    assert_eq!(payload["delivery_id"], 0);
+    assert_eq!(payload["event"]["type"], "inscription_created");
+    assert_eq!(
+      payload["event"]["inscription_id"],
+      InscriptionId { txid, index: 0 }.to_string()
+    );
+
+    let dead_letters = (0..100)
+      .find_map(|_| {
+        let dead_letters = server
+          .get_json::<DeadLettersJson>("/webhooks/dead-letters")
+          .dead_letters;
+
+        if dead_letters.is_empty() {
+          thread::sleep(Duration::from_millis(100));
+          None
+        } else {
+          Some(dead_letters)
+        }
+      })
+      .unwrap();
+
+    assert_eq!(dead_letters.len(), 1);
+
+    let (id, delivery) = &dead_letters[0];
+
+    assert_eq!(*id, 1);
+    assert_eq!(delivery.url, format!("http://127.0.0.1:{closed_port}/"));
+    assert_eq!(delivery.attempts, 1);
+    assert!(delivery.last_error.is_some());
+    assert_eq!(delivery.payload["event"], payload["event"]);
+  }
+
+  #[test]
+  fn webhooks_dead_letters_require_webhooks() {
+    TestServer::builder()
+      .chain(Chain::Regtest)
+      .build()
+      .assert_response(
+        "/webhooks/dead-letters",
+        StatusCode::NOT_FOUND,
+        "webhooks are not configured",
+      );
+  }
+
   #[test]
   fn rare_with_sat_index() {
     TestServer::builder().index_sats().build().assert_response(
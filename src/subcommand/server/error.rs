@@ -3,6 +3,7 @@ use {super::*, std::fmt::Write};
 #[derive(Debug)]
 pub(super) enum ServerError {
   BadRequest(String),
+  Conflict(String),
   Internal(Error),
   NotAcceptable {
     accept_encoding: AcceptEncoding,
@@ -17,6 +18,12 @@ impl IntoResponse for ServerError {
   fn into_response(self) -> Response {
     match self {
       Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+      Self::Conflict(message) => (
+        StatusCode::CONFLICT,
+        [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+        message,
+      )
+        .into_response(),
       Self::Internal(error) => {
         eprintln!("error serving request: {error}");
         (
@@ -0,0 +1,101 @@
+use {
+  super::*,
+  crate::index::event::{EventId, Events},
+  futures::Stream,
+  std::collections::VecDeque,
+  tokio::sync::Notify,
+};
+
+const PAGE_SIZE: usize = 1_000;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// @br-indexer: serves `/events/stream` subscribers from the index's event log,
+// so that a subscriber can resume from the id of the last event it received,
+// even after the server restarts. Subscribers are woken up when the index
+// sends new events, and poll in case a wake-up was dropped.
+pub(crate) struct EventStream {
+  index: Arc<Index>,
+  notify: Notify,
+}
+
+impl EventStream {
+  pub(crate) fn new(index: Arc<Index>) -> Self {
+    Self {
+      index,
+      notify: Notify::new(),
+    }
+  }
+
+  pub(crate) fn notify(&self) {
+    self.notify.notify_waiters();
+  }
+
+  /// Logged events after `after`, or the whole log if `None`, followed by
+  /// events yet to come. Reorgs that orphan the block of the last event are
+  /// yielded without an id, after which events resume from the reorg's start
+  /// height. Returns `None` if the events after `after` are not available.
+  pub(crate) fn subscribe(
+    self: Arc<Self>,
+    after: Option<EventId>,
+  ) -> Result<Option<impl Stream<Item = (Option<EventId>, Event)>>> {
+    if self.index.get_events(after, 0)? == Events::Unavailable {
+      return Ok(None);
+    }
+
+    Ok(Some(futures::stream::unfold(
+      (self, after, VecDeque::new()),
+      |(event_stream, mut after, mut pending)| async move {
+        loop {
+          if let Some(event) = pending.pop_front() {
+            return Some((event, (event_stream, after, pending)));
+          }
+
+          // created before reading the log, so that events sent while
+          // reading still wake this subscriber
+          let notified = event_stream.notify.notified();
+
+          match task::block_in_place(|| event_stream.index.get_events(after, PAGE_SIZE)) {
+            Ok(Events::Page(events)) if events.is_empty() => {
+              tokio::time::timeout(POLL_INTERVAL, notified).await.ok();
+            }
+            Ok(Events::Page(events)) => {
+              after = events.last().map(|(id, _)| *id);
+              pending.extend(events.into_iter().map(|(id, event)| (Some(id), event)));
+            }
+            Ok(Events::Reorged(entry)) => {
+              after = match entry.start_height.checked_sub(1) {
+                Some(height) => {
+                  match task::block_in_place(|| event_stream.index.block_hash(Some(height))) {
+                    Ok(Some(block_hash)) => Some(EventId::after_block(height, block_hash)),
+                    Ok(None) => return None,
+                    Err(err) => {
+                      log::error!("failed to resume event stream after reorg: {err}");
+                      return None;
+                    }
+                  }
+                }
+                None => None,
+              };
+
+              pending.push_back((
+                None,
+                Event::Reorg {
+                  block_height: entry.height,
+                  depth: entry.depth,
+                  end_height: entry.end_height,
+                  orphaned_blocks: entry.orphaned_blocks,
+                  start_height: entry.start_height,
+                },
+              ));
+            }
+            Ok(Events::Unavailable) => return None,
+            Err(err) => {
+              log::error!("failed to read event log: {err}");
+              return None;
+            }
+          }
+        }
+      },
+    )))
+  }
+}
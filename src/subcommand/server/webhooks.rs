@@ -0,0 +1,98 @@
+use {
+  super::*,
+  crate::index::webhooks::Delivery,
+  bitcoin::hashes::{hmac, sha256, HashEngine},
+};
+
+const BATCH_SIZE: usize = 100;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DeadLettersJson {
+  pub(crate) dead_letters: Vec<(u64, Delivery)>,
+}
+
+// @br-indexer: at-least-once delivery of the index's webhook outbox. Each
+// webhook is delivered to by its own task, so that an unresponsive endpoint
+// only delays its own deliveries.
+pub(crate) struct Webhooks {
+  client: reqwest::Client,
+  index: Arc<Index>,
+}
+
+impl Webhooks {
+  pub(crate) fn new(index: Arc<Index>) -> Result<Self> {
+    index.dead_letter_unconfigured_webhook_deliveries()?;
+
+    Ok(Self {
+      client: reqwest::Client::builder().timeout(TIMEOUT).build()?,
+      index,
+    })
+  }
+
+  pub(crate) fn deliver(self: Arc<Self>) {
+    for hook in 0..self.index.webhooks().len() {
+      tokio::spawn(self.clone().deliver_hook(u32::try_from(hook).unwrap()));
+    }
+  }
+
+  async fn deliver_hook(self: Arc<Self>, hook: u32) {
+    loop {
+      if let Err(err) = self.deliver_due(hook).await {
+        log::error!("failed to deliver webhook {hook}: {err}");
+      }
+
+      tokio::time::sleep(POLL_INTERVAL).await;
+    }
+  }
+
+  async fn deliver_due(&self, hook: u32) -> Result {
+    loop {
+      let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+
+      let due =
+        task::block_in_place(|| self.index.get_webhook_deliveries_due(hook, now, BATCH_SIZE))?;
+
+      if due.is_empty() {
+        return Ok(());
+      }
+
+      for (id, delivery) in due {
+        let error = match self.post(&delivery).await {
+          Ok(()) => None,
+          Err(err) => Some(err.to_string()),
+        };
+
+        task::block_in_place(|| self.index.finish_webhook_delivery(id, delivery, error, now))?;
+      }
+    }
+  }
+
+  async fn post(&self, delivery: &Delivery) -> Result {
+    let webhook = &self.index.webhooks()[usize::try_from(delivery.hook).unwrap()];
+
+    let body = serde_json::to_vec(&delivery.payload)?;
+
+    self
+      .client
+      .post(&webhook.url)
+      .header(header::CONTENT_TYPE, "application/json")
+      .header("X-Ord-Signature", signature(&webhook.secret, &body))
+      .body(body)
+      .send()
+      .await?
+      .error_for_status()?;
+
+    Ok(())
+  }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `body`, keyed with `secret`
+pub(crate) fn signature(secret: &str, body: &[u8]) -> String {
+  let mut engine = hmac::HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+  engine.input(body);
+  format!("sha256={}", hmac::Hmac::<sha256::Hash>::from_engine(engine))
+}
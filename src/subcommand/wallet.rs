@@ -7,9 +7,11 @@ use {
 
 pub mod balance;
 mod batch_command;
+pub mod broadcast;
 pub mod cardinals;
 pub mod create;
 pub mod dump;
+pub mod etch_watch;
 pub mod inscribe;
 pub mod inscriptions;
 mod label;
@@ -45,12 +47,16 @@ pub(crate) enum Subcommand {
   Balance,
   #[command(about = "Create inscriptions and runes")]
   Batch(batch_command::Batch),
+  #[command(about = "Finalize and broadcast PSBTs")]
+  Broadcast(broadcast::Broadcast),
   #[command(about = "List unspent cardinal outputs in wallet")]
   Cardinals,
   #[command(about = "Create new wallet")]
   Create(create::Create),
   #[command(about = "Dump wallet descriptors")]
   Dump,
+  #[command(about = "Watch pending etchings, revealing and bumping them")]
+  EtchWatch(etch_watch::EtchWatch),
   #[command(about = "Create inscription")]
   Inscribe(inscribe::Inscribe),
   #[command(about = "List wallet inscriptions")]
@@ -100,9 +106,11 @@ impl WalletCommand {
     match self.subcommand {
       Subcommand::Balance => balance::run(wallet),
       Subcommand::Batch(batch) => batch.run(wallet),
+      Subcommand::Broadcast(broadcast) => broadcast.run(wallet),
       Subcommand::Cardinals => cardinals::run(wallet),
       Subcommand::Create(_) | Subcommand::Restore(_) => unreachable!(),
       Subcommand::Dump => dump::run(wallet),
+      Subcommand::EtchWatch(etch_watch) => etch_watch.run(wallet),
       Subcommand::Inscribe(inscribe) => inscribe.run(wallet),
       Subcommand::Inscriptions => inscriptions::run(wallet),
       Subcommand::Label => label::run(wallet),
@@ -9,6 +9,11 @@ pub(crate) struct Batch {
     help = "Inscribe multiple inscriptions and rune defined in YAML <BATCH_FILE>."
   )]
   pub(crate) batch: PathBuf,
+  #[arg(
+    long,
+    help = "Save the key that signs the rune etching's reveal transaction in the wallet database, so that `ord wallet etch-watch` can fee-bump a contested reveal. The key is stored unencrypted, and anyone who can read the wallet database can use it to spend the commit output until the reveal confirms."
+  )]
+  pub(crate) save_reveal_key: bool,
 }
 
 impl Batch {
@@ -17,12 +22,12 @@ impl Batch {
 
     let batchfile = batch::File::load(&self.batch)?;
 
-    let parent_info = wallet.get_parent_info(batchfile.parent)?;
+    let parent_info = wallet.get_parent_info(&batchfile.parents())?;
 
     let (inscriptions, reveal_satpoints, postages, destinations) = batchfile.inscriptions(
       &wallet,
       utxos,
-      parent_info.as_ref().map(|info| info.tx_out.value),
+      parent_info.iter().map(|info| info.tx_out.value).sum(),
       self.shared.compress,
     )?;
 
@@ -42,6 +47,7 @@ impl Batch {
       commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
       destinations,
       dry_run: self.shared.dry_run,
+      psbt_out: self.shared.psbt_out,
       etching: batchfile.etching,
       inscriptions,
       mode: batchfile.mode,
@@ -57,6 +63,7 @@ impl Batch {
       } else {
         batchfile.satpoint
       },
+      save_reveal_key: self.save_reveal_key,
     }
     .inscribe(
       &locked_utxos.into_keys().collect(),
@@ -0,0 +1,71 @@
+use {super::*, base64::Engine, bitcoin::psbt::Psbt};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Broadcast {
+  #[arg(
+    long = "psbt",
+    required = true,
+    help = "Finalize and broadcast base64-encoded <PSBT>. May be given more than once, PSBTs are broadcast in the order given."
+  )]
+  psbts: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub txids: Vec<Txid>,
+}
+
+impl Broadcast {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let mut transactions = Vec::new();
+
+    // finalize every PSBT before broadcasting any of them, so that an
+    // incomplete reveal doesn't leave its commit broadcast on its own
+    for (i, psbt) in self.psbts.iter().enumerate() {
+      Psbt::deserialize(
+        &base64::engine::general_purpose::STANDARD
+          .decode(psbt)
+          .with_context(|| format!("PSBT {} is not valid base64", i + 1))?,
+      )
+      .with_context(|| format!("PSBT {} is invalid", i + 1))?;
+
+      let result = bitcoin_client.finalize_psbt(psbt, None)?;
+
+      let Some(hex) = result.hex.filter(|_| result.complete) else {
+        bail!("PSBT {} is not fully signed", i + 1);
+      };
+
+      transactions.push(consensus::encode::deserialize::<Transaction>(&hex)?);
+    }
+
+    // revealing an etching before its commit matures would forfeit the rune
+    let pending_etchings = wallet.pending_etchings()?;
+
+    for transaction in &transactions {
+      for (rune, entry) in &pending_etchings {
+        if entry.reveal.txid() == transaction.txid() {
+          ensure!(
+            wallet.is_mature(*rune, &entry.commit)?,
+            "commitment of rune {rune} has not matured, reveal it with `ord wallet resume --psbt-out` once it has",
+          );
+        }
+      }
+    }
+
+    let mut txids = Vec::new();
+
+    for transaction in &transactions {
+      txids.push(bitcoin_client.send_raw_transaction(transaction)?);
+    }
+
+    for (rune, entry) in pending_etchings {
+      if txids.contains(&entry.reveal.txid()) {
+        wallet.clear_etching(rune)?;
+      }
+    }
+
+    Ok(Some(Box::new(Output { txids })))
+  }
+}
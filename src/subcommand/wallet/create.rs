@@ -1,6 +1,7 @@
 use {
   super::*,
   bitcoin::secp256k1::rand::{self, RngCore},
+  bitcoincore_rpc::bitcoincore_rpc_json::Timestamp,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -17,10 +18,40 @@ pub(crate) struct Create {
     help = "Use <PASSPHRASE> to derive wallet seed."
   )]
   pub(crate) passphrase: String,
+  #[arg(
+    long,
+    requires = "descriptor",
+    conflicts_with = "passphrase",
+    help = "Create a watch-only wallet that holds no private keys. Transactions must be created with `--psbt-out` and signed elsewhere."
+  )]
+  pub(crate) watch_only: bool,
+  #[arg(
+    long,
+    requires = "watch_only",
+    help = "Import public taproot <DESCRIPTOR>. Pass once with a `<0;1>` multipath key, or twice with the receive descriptor followed by the change descriptor."
+  )]
+  pub(crate) descriptor: Vec<String>,
+  #[arg(
+    long,
+    requires = "watch_only",
+    help = "Rescan the chain from <TIMESTAMP>, in seconds since the epoch, for funds already sent to the descriptors. [default: now]"
+  )]
+  pub(crate) timestamp: Option<u64>,
 }
 
 impl Create {
   pub(crate) fn run(self, name: String, settings: &Settings) -> SubcommandResult {
+    if self.watch_only {
+      Wallet::initialize_watch_only(
+        name,
+        settings,
+        &self.descriptor,
+        self.timestamp.map_or(Timestamp::Now, Timestamp::Time),
+      )?;
+
+      return Ok(None);
+    }
+
     let mut entropy = [0; 16];
     rand::thread_rng().fill_bytes(&mut entropy);
 
@@ -0,0 +1,582 @@
+use {
+  super::*,
+  crate::wallet::entry::EtchingEntry,
+  bitcoin::{
+    key::UntweakedKeyPair,
+    secp256k1::{self, constants::SCHNORR_SIGNATURE_SIZE, Secp256k1},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot::{LeafVersion, Signature, TapLeafHash},
+  },
+  bitcoincore_rpc::bitcoincore_rpc_json::SignRawTransactionInput,
+  std::{fs::OpenOptions, io::Write},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct EtchWatch {
+  #[arg(
+    long,
+    help = "Append state changes as JSON lines to <LOG>. [default: stderr]"
+  )]
+  log: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Bump contested reveal transactions up to at most <MAX_FEE_RATE> sats/vB."
+  )]
+  max_fee_rate: FeeRate,
+  #[arg(
+    long,
+    default_value = "5",
+    help = "Check pending etchings every <INTERVAL> seconds."
+  )]
+  interval: u64,
+  #[arg(long, help = "Exit once no etchings are pending.")]
+  exit_when_idle: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+  Waiting,
+  Revealed,
+  Rebroadcast,
+  Contested,
+  Bumped,
+  Confirmed,
+  Lost,
+  Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Event {
+  pub timestamp: u64,
+  pub rune: SpacedRune,
+  pub state: State,
+  pub commit: Txid,
+  pub reveal: Txid,
+  pub fee_rate: Option<f64>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Output {
+  pub confirmed: Vec<batch::Output>,
+}
+
+struct Watcher {
+  etchings: BTreeMap<Txid, Option<Rune>>,
+  log: Box<dyn Write>,
+  max_fee_rate: FeeRate,
+  states: BTreeMap<Rune, State>,
+}
+
+impl EtchWatch {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let log: Box<dyn Write> = match &self.log {
+      Some(path) => Box::new(
+        OpenOptions::new()
+          .create(true)
+          .append(true)
+          .open(path)
+          .with_context(|| format!("failed to open log file `{}`", path.display()))?,
+      ),
+      None => Box::new(io::stderr()),
+    };
+
+    let mut watcher = Watcher {
+      etchings: BTreeMap::new(),
+      log,
+      max_fee_rate: self.max_fee_rate,
+      states: BTreeMap::new(),
+    };
+
+    let mut confirmed = Vec::new();
+
+    loop {
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+
+      let (pending, mempool) = match Self::poll(&wallet) {
+        Ok(poll) => poll,
+        Err(err) => {
+          log::error!("failed to poll pending etchings, retrying: {err}");
+          self.sleep(&wallet);
+          continue;
+        }
+      };
+
+      if pending.is_empty() && self.exit_when_idle {
+        break;
+      }
+
+      // transactions that have left the mempool are never checked again
+      watcher.etchings.retain(|txid, _| mempool.contains(txid));
+
+      for (rune, entry) in pending {
+        let (state, reveal, fee_rate, error) = match watcher.check(&wallet, rune, &entry, &mempool)
+        {
+          Ok(Some((state, reveal, fee_rate))) => (state, reveal, fee_rate, None),
+          Ok(None) => continue,
+          Err(err) => (
+            State::Failed,
+            entry.reveal.txid(),
+            None,
+            Some(err.to_string()),
+          ),
+        };
+
+        if state == State::Confirmed {
+          confirmed.push(entry.output.clone());
+        }
+
+        watcher.log(&entry, rune, state, reveal, fee_rate, error)?;
+      }
+
+      self.sleep(&wallet);
+    }
+
+    Ok(Some(Box::new(Output { confirmed })))
+  }
+
+  fn poll(wallet: &Wallet) -> Result<(Vec<(Rune, EtchingEntry)>, BTreeSet<Txid>)> {
+    Ok((
+      wallet.pending_etchings()?,
+      wallet
+        .bitcoin_client()
+        .get_raw_mempool()?
+        .into_iter()
+        .collect(),
+    ))
+  }
+
+  fn sleep(&self, wallet: &Wallet) {
+    if !wallet.integration_test() {
+      thread::sleep(Duration::from_secs(self.interval));
+    }
+  }
+}
+
+impl Watcher {
+  // returns the etching's new state, or `None` if nothing changed
+  fn check(
+    &mut self,
+    wallet: &Wallet,
+    rune: Rune,
+    entry: &EtchingEntry,
+    mempool: &BTreeSet<Txid>,
+  ) -> Result<Option<(State, Txid, Option<f64>)>> {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let reveal = entry.reveal.txid();
+
+    if let Some(info) = bitcoin_client
+      .get_raw_transaction_info(&reveal, None)
+      .into_option()?
+    {
+      if info.confirmations.unwrap_or_default() > 0 {
+        wallet.clear_etching(rune)?;
+        self.states.remove(&rune);
+        return Ok(Some((State::Confirmed, reveal, None)));
+      }
+    }
+
+    // once another transaction has etched the rune, the reveal can never
+    // etch it, so the etching is abandoned instead of being retried
+    if let Some((_id, rune_entry, _parent)) = wallet.get_rune(rune)? {
+      // the reveal may have been mined since its confirmations were checked,
+      // in which case it is confirmed on the next pass
+      if rune_entry.etching == reveal {
+        return Ok(None);
+      }
+
+      wallet.clear_etching(rune)?;
+      self.states.remove(&rune);
+      return Ok(Some((State::Lost, reveal, None)));
+    }
+
+    if !mempool.contains(&reveal) {
+      if !wallet.is_mature(rune, &entry.commit)? {
+        return Ok(Some((State::Waiting, reveal, None)));
+      }
+
+      bitcoin_client.send_raw_transaction(&entry.reveal)?;
+
+      let state = match self.states.get(&rune) {
+        None | Some(State::Waiting) => State::Revealed,
+        Some(_) => State::Rebroadcast,
+      };
+
+      return Ok(Some((state, reveal, Self::fee_rate(wallet, reveal)?)));
+    }
+
+    // the reveal may have been mined since the mempool was listed, in which
+    // case it is confirmed on the next pass
+    let Some(fee_rate) = Self::fee_rate(wallet, reveal)? else {
+      return Ok(None);
+    };
+
+    let mut contender = None;
+
+    for txid in mempool {
+      if *txid == reveal || self.etched(wallet, *txid)? != Some(rune) {
+        continue;
+      }
+
+      // the contender may have been mined or evicted since the mempool was
+      // listed, so leave the etching as it is until the next pass
+      let Some(rate) = Self::fee_rate(wallet, *txid)? else {
+        return Ok(None);
+      };
+
+      if contender.map_or(true, |contender| rate > contender) {
+        contender = Some(rate);
+      }
+    }
+
+    let Some(contender) = contender.filter(|contender| *contender >= fee_rate) else {
+      // a bumped or rebroadcast reveal that is no longer contested is still
+      // revealed, so only log it if it was contested or not yet revealed
+      return Ok(match self.states.get(&rune) {
+        Some(State::Revealed | State::Rebroadcast | State::Bumped) => None,
+        _ => Some((State::Revealed, reveal, Some(fee_rate))),
+      });
+    };
+
+    let target = contender.floor() + 1.0;
+
+    if target > self.max_fee_rate.n() {
+      return Ok(Some((State::Contested, reveal, Some(fee_rate))));
+    }
+
+    let bumped = Self::bump(wallet, rune, entry, target.try_into()?)?;
+
+    Ok(Some((
+      State::Bumped,
+      bumped,
+      Self::fee_rate(wallet, bumped)?,
+    )))
+  }
+
+  fn log(
+    &mut self,
+    entry: &EtchingEntry,
+    rune: Rune,
+    state: State,
+    reveal: Txid,
+    fee_rate: Option<f64>,
+    error: Option<String>,
+  ) -> Result {
+    if !matches!(state, State::Confirmed | State::Lost) {
+      let previous = self.states.insert(rune, state);
+
+      if previous == Some(state) && state != State::Bumped {
+        return Ok(());
+      }
+    }
+
+    let event = Event {
+      timestamp: SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs(),
+      rune: entry
+        .output
+        .rune
+        .as_ref()
+        .map(|rune_info| rune_info.rune)
+        .unwrap_or(SpacedRune { rune, spacers: 0 }),
+      state,
+      commit: entry.commit.txid(),
+      reveal,
+      fee_rate,
+      error,
+    };
+
+    writeln!(self.log, "{}", serde_json::to_string(&event)?)?;
+    self.log.flush()?;
+
+    Ok(())
+  }
+
+  // rune etched by mempool transaction `txid`, cached since mempool
+  // transactions don't change
+  fn etched(&mut self, wallet: &Wallet, txid: Txid) -> Result<Option<Rune>> {
+    if let Some(rune) = self.etchings.get(&txid) {
+      return Ok(*rune);
+    }
+
+    let transaction = wallet.bitcoin_client().get_raw_transaction(&txid, None)?;
+
+    let rune = match Runestone::decipher(&transaction) {
+      Some(Artifact::Runestone(runestone)) => runestone.etching.and_then(|etching| etching.rune),
+      Some(Artifact::Cenotaph(cenotaph)) => cenotaph.etching,
+      None => None,
+    };
+
+    self.etchings.insert(txid, rune);
+
+    Ok(rune)
+  }
+
+  // fee rate of mempool transaction `txid`, or `None` if it has left the
+  // mempool since it was listed
+  fn fee_rate(wallet: &Wallet, txid: Txid) -> Result<Option<f64>> {
+    match wallet.bitcoin_client().get_mempool_entry(&txid) {
+      Ok(entry) => Ok(Some(entry.fees.base.to_sat() as f64 / entry.vsize as f64)),
+      Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+        bitcoincore_rpc::jsonrpc::error::RpcError { code: -5, .. },
+      ))) => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  // Replaces the etching's reveal with one paying `fee_rate`. Inputs after
+  // the commit input and outputs after the runestone are fee bump inputs and
+  // change, so that bumping an already bumped reveal reuses them.
+  fn bump(wallet: &Wallet, rune: Rune, entry: &EtchingEntry, fee_rate: FeeRate) -> Result<Txid> {
+    wallet.ensure_can_sign()?;
+
+    let Some(reveal_key) = wallet.load_reveal_key(rune)? else {
+      bail!(
+        "no reveal key saved for rune {rune}, its reveal transaction cannot be bumped, etch with `ord wallet batch --save-reveal-key` to allow bumping"
+      );
+    };
+
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let commit_txid = entry.commit.txid();
+
+    let mut reveal = entry.reveal.clone();
+
+    let commit_input = reveal
+      .input
+      .iter()
+      .position(|txin| txin.previous_output.txid == commit_txid)
+      .context("reveal transaction does not spend commit transaction")?;
+
+    let witness = &reveal.input[commit_input].witness;
+
+    let (Some(reveal_script), Some(control_block)) = (witness.nth(1), witness.nth(2)) else {
+      bail!("reveal transaction is missing its commit input script path spend");
+    };
+
+    let reveal_script = ScriptBuf::from_bytes(reveal_script.to_vec());
+    let control_block = control_block.to_vec();
+
+    let change_vout = reveal
+      .output
+      .iter()
+      .position(|output| output.script_pubkey.is_op_return())
+      .context("reveal transaction does not contain a runestone")?
+      + 1;
+
+    if reveal.output.len() == change_vout {
+      reveal.output.push(TxOut {
+        script_pubkey: wallet.get_change_address()?.script_pubkey(),
+        value: 0,
+      });
+    }
+
+    let mut prevouts = Vec::new();
+
+    for txin in &reveal.input {
+      let OutPoint { txid, vout } = txin.previous_output;
+
+      let output = if txid == commit_txid {
+        entry.commit.output[usize::try_from(vout).unwrap()].clone()
+      } else {
+        bitcoin_client.get_raw_transaction(&txid, None)?.output[usize::try_from(vout).unwrap()]
+          .clone()
+      };
+
+      prevouts.push(output);
+    }
+
+    let old_fee = prevouts.iter().map(|output| output.value).sum::<u64>()
+      - entry
+        .reveal
+        .output
+        .iter()
+        .map(|output| output.value)
+        .sum::<u64>();
+
+    loop {
+      for (i, txin) in reveal.input.iter_mut().enumerate() {
+        if i != commit_input {
+          txin.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
+        }
+      }
+
+      let vsize = reveal.vsize();
+
+      // a replacement must also pay for its own relay at the minimum
+      // incremental relay fee rate of 1 sat/vB
+      let fee = fee_rate
+        .fee(vsize)
+        .to_sat()
+        .max(old_fee + u64::try_from(vsize).unwrap());
+
+      let available = prevouts.iter().map(|output| output.value).sum::<u64>()
+        - reveal.output[..change_vout]
+          .iter()
+          .map(|output| output.value)
+          .sum::<u64>();
+
+      let change = &mut reveal.output[change_vout];
+
+      if let Some(value) = available
+        .checked_sub(fee)
+        .filter(|value| *value >= change.script_pubkey.dust_value().to_sat())
+      {
+        change.value = value;
+        break;
+      }
+
+      let (outpoint, output) = Self::cardinal(wallet, &reveal)?;
+
+      reveal.input.push(TxIn {
+        previous_output: outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      });
+
+      prevouts.push(output);
+    }
+
+    for txin in &mut reveal.input {
+      txin.witness = Witness::new();
+    }
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut sighash_cache = SighashCache::new(&mut reveal);
+
+    let sighash = sighash_cache
+      .taproot_script_spend_signature_hash(
+        commit_input,
+        &Prevouts::All(&prevouts),
+        TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
+        TapSighashType::Default,
+      )
+      .expect("signature hash should compute");
+
+    let sig = secp256k1.sign_schnorr(
+      &secp256k1::Message::from_slice(sighash.as_ref())
+        .expect("should be cryptographically secure hash"),
+      &UntweakedKeyPair::from_secret_key(&secp256k1, &reveal_key),
+    );
+
+    let witness = sighash_cache
+      .witness_mut(commit_input)
+      .expect("getting mutable witness reference should work");
+
+    witness.push(
+      Signature {
+        sig,
+        hash_ty: TapSighashType::Default,
+      }
+      .to_vec(),
+    );
+
+    witness.push(reveal_script);
+    witness.push(control_block);
+
+    let commit_outpoint = reveal.input[commit_input].previous_output;
+
+    let result = bitcoin_client.sign_raw_transaction_with_wallet(
+      &reveal,
+      Some(&[SignRawTransactionInput {
+        txid: commit_txid,
+        vout: commit_outpoint.vout,
+        script_pub_key: prevouts[commit_input].script_pubkey.clone(),
+        redeem_script: None,
+        amount: Some(Amount::from_sat(prevouts[commit_input].value)),
+      }]),
+      None,
+    )?;
+
+    ensure!(
+      result.complete,
+      format!(
+        "Failed to sign bumped reveal transaction: {:?}",
+        result.errors
+      )
+    );
+
+    let reveal = consensus::encode::deserialize::<Transaction>(&result.hex)?;
+
+    let txid = bitcoin_client.send_raw_transaction(&reveal)?;
+
+    let old_txid = entry.reveal.txid();
+
+    let mut output = entry.output.clone();
+
+    output.reveal = txid;
+    output.reveal_broadcast = true;
+    output.total_fees = output.total_fees - old_fee
+      + prevouts.iter().map(|output| output.value).sum::<u64>()
+      - reveal.output.iter().map(|output| output.value).sum::<u64>();
+
+    for inscription in &mut output.inscriptions {
+      if inscription.id.txid == old_txid {
+        inscription.id.txid = txid;
+      }
+
+      if inscription.location.outpoint.txid == old_txid {
+        inscription.location.outpoint.txid = txid;
+      }
+    }
+
+    if let Some(location) = output
+      .rune
+      .as_mut()
+      .and_then(|rune_info| rune_info.location.as_mut())
+    {
+      location.txid = txid;
+    }
+
+    wallet.save_etching(&rune, &entry.commit, &reveal, output, Some(&reveal_key))?;
+
+    Ok(txid)
+  }
+
+  // largest cardinal output in the wallet that is still unspent and not
+  // already spent by `reveal`
+  fn cardinal(wallet: &Wallet, reveal: &Transaction) -> Result<(OutPoint, TxOut)> {
+    let inscribed = wallet
+      .inscriptions()
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let runic = wallet.get_runic_outputs()?;
+
+    let mut candidates = wallet
+      .utxos()
+      .iter()
+      .filter(|(outpoint, _)| {
+        !inscribed.contains(outpoint)
+          && !runic.contains(outpoint)
+          && !wallet.locked_utxos().contains_key(outpoint)
+          && !reveal
+            .input
+            .iter()
+            .any(|txin| txin.previous_output == **outpoint)
+      })
+      .collect::<Vec<(&OutPoint, &TxOut)>>();
+
+    candidates.sort_by_key(|(_, output)| Reverse(output.value));
+
+    for (outpoint, output) in candidates {
+      if wallet
+        .bitcoin_client()
+        .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+        .is_some()
+      {
+        return Ok((*outpoint, output.clone()));
+      }
+    }
+
+    bail!("wallet contains no cardinal outputs to bump reveal transaction with")
+  }
+}
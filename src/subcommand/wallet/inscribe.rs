@@ -33,8 +33,11 @@ pub(crate) struct Inscribe {
   pub(crate) json_metadata: Option<PathBuf>,
   #[clap(long, help = "Set inscription metaprotocol to <METAPROTOCOL>.")]
   pub(crate) metaprotocol: Option<String>,
-  #[clap(long, help = "Make inscription a child of <PARENT>.")]
-  pub(crate) parent: Option<InscriptionId>,
+  #[clap(
+    long,
+    help = "Make inscription a child of <PARENT>. May be given more than once."
+  )]
+  pub(crate) parent: Vec<InscriptionId>,
   #[arg(
     long,
     help = "Include <AMOUNT> postage with inscription. [default: 10000sat]"
@@ -66,6 +69,7 @@ impl Inscribe {
         None => wallet.get_change_address()?,
       }],
       dry_run: self.shared.dry_run,
+      psbt_out: self.shared.psbt_out,
       etching: None,
       inscriptions: vec![Inscription::new(
         chain,
@@ -73,7 +77,7 @@ impl Inscribe {
         self.delegate,
         Inscribe::parse_metadata(self.cbor_metadata, self.json_metadata)?,
         self.metaprotocol,
-        self.parent.into_iter().collect(),
+        self.parent.clone(),
         self.file,
         None,
         None,
@@ -81,7 +85,7 @@ impl Inscribe {
       mode: batch::Mode::SeparateOutputs,
       no_backup: self.shared.no_backup,
       no_limit: self.shared.no_limit,
-      parent_info: wallet.get_parent_info(self.parent)?,
+      parent_info: wallet.get_parent_info(&self.parent)?,
       postages: vec![self.postage.unwrap_or(TARGET_POSTAGE)],
       reinscribe: self.reinscribe,
       reveal_fee_rate: self.shared.fee_rate,
@@ -91,6 +95,7 @@ impl Inscribe {
       } else {
         self.satpoint
       },
+      save_reveal_key: false,
     }
     .inscribe(
       &wallet.locked_utxos().clone().into_keys().collect(),
@@ -1,4 +1,4 @@
-use super::*;
+use {super::*, base64::Engine, bitcoin::psbt::Psbt};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Mint {
@@ -13,6 +13,28 @@ pub(crate) struct Mint {
   postage: Option<Amount>,
   #[clap(long, help = "Send minted runes to <DESTINATION>.")]
   destination: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Mint <COUNT> times, funding each mint from a distinct UTXO."
+  )]
+  count: Option<u32>,
+  #[clap(
+    long,
+    requires = "count",
+    help = "Chain mints, spending the change of each mint in the next one."
+  )]
+  chained: bool,
+  #[clap(
+    long,
+    help = "Don't sign or broadcast mints, return unsigned PSBTs instead."
+  )]
+  dry_run: bool,
+  #[clap(
+    long,
+    conflicts_with = "dry_run",
+    help = "Emit unsigned mint PSBTs to be signed elsewhere and submitted with `ord wallet broadcast`."
+  )]
+  psbt_out: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -22,8 +44,191 @@ pub struct Output {
   pub mint: Txid,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct MintTransaction {
+  pub txid: Txid,
+  pub psbt: Option<String>,
+}
+
+// @br-indexer: result of `--count` and `--dry-run` mints. Minting stops at the
+// first mint that fails, whose error is reported alongside the mints that
+// were made before it.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchOutput {
+  pub rune: SpacedRune,
+  pub pile: Pile,
+  pub mints: Vec<MintTransaction>,
+  pub error: Option<String>,
+}
+
+// @br-indexer: what single and batch mints have in common, checked before
+// any mint is built
+struct Prepared {
+  amount: u128,
+  block_height: u64,
+  destination: Address,
+  postage: Amount,
+  rune_entry: RuneEntry,
+  runestone: Runestone,
+}
+
 impl Mint {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    if self.count.is_some() || self.dry_run || self.psbt_out {
+      return self.run_batch(wallet);
+    }
+
+    wallet.ensure_can_sign()?;
+
+    let Prepared {
+      amount,
+      destination,
+      postage,
+      rune_entry,
+      runestone,
+      ..
+    } = self.prepare(&wallet)?;
+
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let unfunded_transaction = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: vec![
+        TxOut {
+          script_pubkey: runestone.encipher(),
+          value: 0,
+        },
+        TxOut {
+          script_pubkey: destination.script_pubkey(),
+          value: postage.to_sat(),
+        },
+      ],
+    };
+
+    wallet.lock_non_cardinal_outputs()?;
+
+    let unsigned_transaction =
+      fund_raw_transaction(bitcoin_client, self.fee_rate, &unfunded_transaction)?;
+
+    let signed_transaction = bitcoin_client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+
+    assert_eq!(
+      Runestone::decipher(&signed_transaction),
+      Some(Artifact::Runestone(runestone)),
+    );
+
+    let transaction = bitcoin_client.send_raw_transaction(&signed_transaction)?;
+
+    Ok(Some(Box::new(Output {
+      rune: self.rune,
+      pile: Pile {
+        amount,
+        divisibility: rune_entry.divisibility,
+        symbol: rune_entry.symbol,
+      },
+      mint: transaction,
+    })))
+  }
+
+  fn run_batch(self, wallet: Wallet) -> SubcommandResult {
+    let count = self.count.unwrap_or(1);
+
+    ensure!(count > 0, "mint count must be greater than zero");
+
+    if !(self.dry_run || self.psbt_out) {
+      wallet.ensure_can_sign()?;
+    }
+
+    let Prepared {
+      amount,
+      block_height,
+      destination,
+      postage,
+      rune_entry,
+      runestone,
+    } = self.prepare(&wallet)?;
+
+    let rune = self.rune.rune;
+
+    let inscribed_outputs = wallet
+      .inscriptions()
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<HashSet<OutPoint>>();
+
+    let runic_outputs = wallet.get_runic_outputs()?;
+
+    // largest last, so that mints are funded from the largest UTXOs first
+    let mut cardinals = wallet
+      .utxos()
+      .iter()
+      .filter(|(outpoint, _)| {
+        !inscribed_outputs.contains(outpoint)
+          && !runic_outputs.contains(outpoint)
+          && !wallet.locked_utxos().contains_key(outpoint)
+      })
+      .map(|(outpoint, tx_out)| (*outpoint, tx_out.clone()))
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    cardinals.sort_by_key(|(_, tx_out)| tx_out.value);
+
+    let mut mints = Vec::new();
+    let mut error = None;
+    let mut change = None;
+
+    for i in 0..count {
+      let result = (|| -> Result<(MintTransaction, (OutPoint, TxOut))> {
+        let mut entry = rune_entry;
+        entry.mints += u128::from(i);
+        entry
+          .mintable(block_height + 1)
+          .map_err(|err| anyhow!("rune {rune} {err}"))?;
+
+        let input = match change.take() {
+          Some(change) if self.chained => change,
+          _ => cardinals
+            .pop()
+            .ok_or_else(|| anyhow!("wallet contains no cardinal utxos to fund mint"))?,
+        };
+
+        self.mint_transaction(&wallet, input, &runestone, &destination, postage)
+      })();
+
+      match result {
+        Ok((mint, output)) => {
+          mints.push(mint);
+          change = Some(output);
+        }
+        Err(err) => {
+          if mints.is_empty() {
+            return Err(err);
+          }
+
+          error = Some(err.to_string());
+          break;
+        }
+      }
+    }
+
+    Ok(Some(Box::new(BatchOutput {
+      rune: self.rune,
+      pile: Pile {
+        amount,
+        divisibility: rune_entry.divisibility,
+        symbol: rune_entry.symbol,
+      },
+      mints,
+      error,
+    })))
+  }
+
+  fn prepare(&self, wallet: &Wallet) -> Result<Prepared> {
     ensure!(
       wallet.has_rune_index(),
       "`ord wallet mint` requires index created with `--index-runes` flag",
@@ -31,9 +236,7 @@ impl Mint {
 
     let rune = self.rune.rune;
 
-    let bitcoin_client = wallet.bitcoin_client();
-
-    let block_height = bitcoin_client.get_block_count()?;
+    let block_height = wallet.bitcoin_client().get_block_count()?;
 
     let Some((id, rune_entry, _)) = wallet.get_rune(rune)? else {
       bail!("rune {rune} has not been etched");
@@ -45,10 +248,8 @@ impl Mint {
       .mintable(block_height + 1)
       .map_err(|err| anyhow!("rune {rune} {err}"))?;
 
-    let chain = wallet.chain();
-
-    let destination = match self.destination {
-      Some(destination) => destination.require_network(chain.network())?,
+    let destination = match self.destination.clone() {
+      Some(destination) => destination.require_network(wallet.chain().network())?,
       None => wallet.get_change_address()?,
     };
 
@@ -71,48 +272,134 @@ impl Mint {
       script_pubkey.len()
     );
 
-    let unfunded_transaction = Transaction {
+    Ok(Prepared {
+      amount,
+      block_height,
+      destination,
+      postage,
+      rune_entry,
+      runestone,
+    })
+  }
+
+  /// Mint transaction spending `input`, with the runestone in output 0, the
+  /// minted runes in output 1, and change in output 2. Returns the mint and
+  /// its change output.
+  fn mint_transaction(
+    &self,
+    wallet: &Wallet,
+    (outpoint, input): (OutPoint, TxOut),
+    runestone: &Runestone,
+    destination: &Address,
+    postage: Amount,
+  ) -> Result<(MintTransaction, (OutPoint, TxOut))> {
+    let change = wallet.get_change_address()?.script_pubkey();
+
+    let mut transaction = Transaction {
       version: 2,
       lock_time: LockTime::ZERO,
-      input: Vec::new(),
+      input: vec![TxIn {
+        previous_output: outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Self::placeholder_witness(&input.script_pubkey),
+      }],
       output: vec![
         TxOut {
-          script_pubkey,
+          script_pubkey: runestone.encipher(),
           value: 0,
         },
         TxOut {
           script_pubkey: destination.script_pubkey(),
           value: postage.to_sat(),
         },
+        TxOut {
+          script_pubkey: change.clone(),
+          value: 0,
+        },
       ],
     };
 
-    wallet.lock_non_cardinal_outputs()?;
+    let fee = self.fee_rate.fee(transaction.vsize());
 
-    let unsigned_transaction =
-      fund_raw_transaction(bitcoin_client, self.fee_rate, &unfunded_transaction)?;
+    let value = input
+      .value
+      .checked_sub(postage.to_sat() + fee.to_sat())
+      .filter(|value| *value >= change.dust_value().to_sat())
+      .ok_or_else(|| anyhow!("{outpoint} has insufficient value to fund mint"))?;
 
-    let signed_transaction = bitcoin_client
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+    transaction.output[2].value = value;
+    transaction.input[0].witness = Witness::new();
 
-    let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+    let psbt = if self.dry_run || self.psbt_out {
+      let mut psbt = Psbt::from_unsigned_tx(transaction.clone())?;
+      psbt.inputs[0].witness_utxo = Some(input);
+      Some(base64::engine::general_purpose::STANDARD.encode(psbt.serialize()))
+    } else {
+      let signed_transaction = wallet
+        .bitcoin_client()
+        .sign_raw_transaction_with_wallet(&transaction, None, None)?
+        .hex;
+
+      let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+
+      assert_eq!(
+        Runestone::decipher(&signed_transaction),
+        Some(Artifact::Runestone(Runestone {
+          mint: runestone.mint,
+          ..default()
+        })),
+      );
+
+      wallet
+        .bitcoin_client()
+        .send_raw_transaction(&signed_transaction)?;
+
+      None
+    };
+
+    let txid = transaction.txid();
+
+    Ok((
+      MintTransaction { txid, psbt },
+      (OutPoint { txid, vout: 2 }, transaction.output[2].clone()),
+    ))
+  }
+
+  /// Witness of the same size as the one that will spend `script_pubkey`, so
+  /// that the fee covers it. Outputs of ord's own wallets are P2TR, but
+  /// watch-only wallets may also hold P2WPKH outputs.
+  fn placeholder_witness(script_pubkey: &Script) -> Witness {
+    if script_pubkey.is_v0_p2wpkh() {
+      // maximum size DER signature with sighash flag, and compressed key
+      Witness::from_slice(&[&[0; 73][..], &[0; 33][..]])
+    } else {
+      // key path spend signature
+      Witness::from_slice(&[&[0; 64]])
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn placeholder_witness_matches_spend_size() {
+    let key = bitcoin::key::PublicKey::from_slice(&[2; 33]).unwrap();
 
     assert_eq!(
-      Runestone::decipher(&signed_transaction),
-      Some(Artifact::Runestone(runestone)),
+      Mint::placeholder_witness(&ScriptBuf::new_v0_p2wpkh(&key.wpubkey_hash().unwrap()))
+        .serialized_len(),
+      1 + 1 + 73 + 1 + 33,
     );
 
-    let transaction = bitcoin_client.send_raw_transaction(&signed_transaction)?;
-
-    Ok(Some(Box::new(Output {
-      rune: self.rune,
-      pile: Pile {
-        amount,
-        divisibility: rune_entry.divisibility,
-        symbol: rune_entry.symbol,
-      },
-      mint: transaction,
-    })))
+    assert_eq!(
+      Mint::placeholder_witness(&ScriptBuf::new_v1_p2tr_tweaked(
+        bitcoin::key::TweakedPublicKey::dangerous_assume_tweaked(key.inner.x_only_public_key().0)
+      ))
+      .serialized_len(),
+      1 + 1 + 64,
+    );
   }
 }
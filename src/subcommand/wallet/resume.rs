@@ -8,11 +8,22 @@ pub struct ResumeOutput {
 pub(crate) struct Resume {
   #[arg(long, help = "Don't broadcast transactions.")]
   pub(crate) dry_run: bool,
+  #[arg(
+    long,
+    conflicts_with = "dry_run",
+    help = "Emit reveal transactions as PSBTs once their commits mature, instead of broadcasting them. Submit them with `ord wallet broadcast`."
+  )]
+  pub(crate) psbt_out: bool,
 }
 
 impl Resume {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
     let mut etchings = Vec::new();
+
+    // @br-indexer: etchings stay pending until their reveal is broadcast, so
+    // track which ones have already been emitted as PSBTs
+    let mut emitted = BTreeSet::new();
+
     loop {
       if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
         break;
@@ -27,12 +38,29 @@ impl Resume {
           continue;
         };
 
+        if emitted.contains(&rune) {
+          continue;
+        }
+
         if wallet.is_mature(rune, &entry.commit)? {
-          etchings.push(wallet.send_etching(rune, &entry)?);
+          if self.psbt_out {
+            etchings.push(batch::Output {
+              reveal_broadcast: false,
+              reveal_psbt: Some(wallet.partially_signed_psbt(&entry.reveal, &[&entry.commit])?),
+              ..entry.output.clone()
+            });
+            emitted.insert(rune);
+          } else {
+            etchings.push(wallet.send_etching(rune, &entry)?);
+          }
         }
       }
 
-      if wallet.pending_etchings()?.is_empty() {
+      if wallet
+        .pending_etchings()?
+        .iter()
+        .all(|(rune, _)| emitted.contains(rune))
+      {
         break;
       }
 
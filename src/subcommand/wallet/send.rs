@@ -1,9 +1,24 @@
-use {super::*, crate::outgoing::Outgoing, base64::Engine, bitcoin::psbt::Psbt};
+use {
+  super::*,
+  crate::{
+    outgoing::Outgoing,
+    wallet::rune_selector::{self, RuneSelector},
+  },
+  base64::Engine,
+  bitcoin::psbt::Psbt,
+  std::collections::btree_map,
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Send {
   #[arg(long, help = "Don't sign or broadcast transaction")]
   pub(crate) dry_run: bool,
+  #[arg(
+    long,
+    conflicts_with = "dry_run",
+    help = "Emit an unsigned PSBT to be signed elsewhere and submitted with `ord wallet broadcast`"
+  )]
+  pub(crate) psbt_out: bool,
   #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
   fee_rate: FeeRate,
   #[arg(
@@ -11,8 +26,16 @@ pub(crate) struct Send {
     help = "Target <AMOUNT> postage with sent inscriptions. [default: 10000 sat]"
   )]
   pub(crate) postage: Option<Amount>,
-  address: Address<NetworkUnchecked>,
-  outgoing: Outgoing,
+  #[arg(
+    long,
+    help = "Send runes to every destination in YAML or CSV <PLAN> in a single transaction",
+    conflicts_with_all = ["address", "outgoing", "postage"]
+  )]
+  pub(crate) plan: Option<PathBuf>,
+  #[arg(required_unless_present = "plan")]
+  address: Option<Address<NetworkUnchecked>>,
+  #[arg(required_unless_present = "plan")]
+  outgoing: Option<Outgoing>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,22 +46,120 @@ pub struct Output {
   pub fee: u64,
 }
 
+// @br-indexer: one line of a send plan. YAML plans are a list of these, CSV
+// plans have one `rune,amount,address` row per line and an optional header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanEntry {
+  pub rune: SpacedRune,
+  pub amount: Decimal,
+  pub address: Address<NetworkUnchecked>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanOutput {
+  pub txid: Txid,
+  pub psbt: String,
+  pub sends: Vec<PlanEntry>,
+  pub fee: u64,
+}
+
+impl PlanEntry {
+  pub(crate) fn load(path: &Path) -> Result<Vec<Self>> {
+    let contents = fs::read_to_string(path)
+      .with_context(|| format!("failed to read send plan `{}`", path.display()))?;
+
+    if path
+      .extension()
+      .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+    {
+      Self::parse_csv(&contents)
+    } else {
+      serde_yaml::from_str(&contents)
+        .with_context(|| format!("failed to parse send plan `{}`", path.display()))
+    }
+  }
+
+  fn parse_csv(contents: &str) -> Result<Vec<Self>> {
+    let mut entries = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+      let line = line.trim();
+
+      if line.is_empty() || (i == 0 && line.eq_ignore_ascii_case("rune,amount,address")) {
+        continue;
+      }
+
+      let [rune, amount, address] = line.split(',').map(str::trim).collect::<Vec<&str>>()[..]
+      else {
+        bail!(
+          "send plan line {} must have three fields: rune,amount,address",
+          i + 1
+        );
+      };
+
+      entries.push(Self {
+        rune: rune
+          .parse()
+          .with_context(|| format!("invalid rune on send plan line {}", i + 1))?,
+        amount: amount
+          .parse()
+          .with_context(|| format!("invalid amount on send plan line {}", i + 1))?,
+        address: address
+          .parse()
+          .with_context(|| format!("invalid address on send plan line {}", i + 1))?,
+      });
+    }
+
+    Ok(entries)
+  }
+}
+
 impl Send {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    if let Some(plan) = &self.plan {
+      let sends = PlanEntry::load(plan)?;
+
+      let mut destinations = Vec::new();
+      for send in &sends {
+        destinations.push((
+          send
+            .address
+            .clone()
+            .require_network(wallet.chain().network())?,
+          send.rune,
+          send.amount,
+        ));
+      }
+
+      let unsigned_transaction =
+        Self::create_unsigned_send_runes_transaction(&wallet, &destinations, self.fee_rate)?;
+
+      let (txid, psbt, fee) =
+        Self::sign_and_broadcast(&wallet, unsigned_transaction, self.dry_run || self.psbt_out)?;
+
+      return Ok(Some(Box::new(PlanOutput {
+        txid,
+        psbt,
+        sends,
+        fee,
+      })));
+    }
+
+    let outgoing = self.outgoing.unwrap();
+
     let address = self
       .address
-      .clone()
+      .unwrap()
       .require_network(wallet.chain().network())?;
 
-    let unsigned_transaction = match self.outgoing {
+    let unsigned_transaction = match outgoing.clone() {
       Outgoing::Amount(amount) => {
         Self::create_unsigned_send_amount_transaction(&wallet, address, amount, self.fee_rate)?
       }
       Outgoing::Rune { decimal, rune } => Self::create_unsigned_send_runes_transaction(
         &wallet,
-        address,
-        rune,
-        decimal,
+        &[(address, rune, decimal)],
         self.fee_rate,
       )?,
       Outgoing::InscriptionId(id) => Self::create_unsigned_send_satpoint_transaction(
@@ -71,9 +192,25 @@ impl Send {
       )?,
     };
 
+    let (txid, psbt, fee) =
+      Self::sign_and_broadcast(&wallet, unsigned_transaction, self.dry_run || self.psbt_out)?;
+
+    Ok(Some(Box::new(Output {
+      txid,
+      psbt,
+      outgoing,
+      fee,
+    })))
+  }
+
+  fn sign_and_broadcast(
+    wallet: &Wallet,
+    unsigned_transaction: Transaction,
+    dry_run: bool,
+  ) -> Result<(Txid, String, u64)> {
     let unspent_outputs = wallet.utxos();
 
-    let (txid, psbt) = if self.dry_run {
+    let (txid, psbt) = if dry_run {
       let psbt = wallet
         .bitcoin_client()
         .wallet_process_psbt(
@@ -87,6 +224,8 @@ impl Send {
 
       (unsigned_transaction.txid(), psbt)
     } else {
+      wallet.ensure_can_sign()?;
+
       let psbt = wallet
         .bitcoin_client()
         .wallet_process_psbt(
@@ -122,12 +261,7 @@ impl Send {
       fee = fee.checked_sub(txout.value).unwrap();
     }
 
-    Ok(Some(Box::new(Output {
-      txid,
-      psbt,
-      outgoing: self.outgoing,
-      fee,
-    })))
+    Ok((txid, psbt, fee))
   }
 
   fn create_unsigned_send_amount_transaction(
@@ -204,11 +338,13 @@ impl Send {
     )
   }
 
+  // @br-indexer: settles every (destination, rune, amount) in one transaction.
+  // Output 0 holds the runestone, output 1 receives leftover balances of every
+  // rune in the selected inputs, and each distinct destination gets one
+  // output, in order of first appearance, starting at output 2.
   fn create_unsigned_send_runes_transaction(
     wallet: &Wallet,
-    destination: Address,
-    spaced_rune: SpacedRune,
-    decimal: Decimal,
+    sends: &[(Address, SpacedRune, Decimal)],
     fee_rate: FeeRate,
   ) -> Result<Transaction> {
     ensure!(
@@ -216,63 +352,158 @@ impl Send {
       "sending runes with `ord send` requires index created with `--index-runes` flag",
     );
 
+    ensure!(!sends.is_empty(), "send plan is empty");
+
     let inscriptions = wallet.inscriptions();
     let runic_outputs = wallet.get_runic_outputs()?;
     let bitcoin_client = wallet.bitcoin_client();
 
     wallet.lock_non_cardinal_outputs()?;
 
-    let (id, entry, _parent) = wallet
-      .get_rune(spaced_rune.rune)?
-      .with_context(|| format!("rune `{}` has not been etched", spaced_rune.rune))?;
+    let mut runes: BTreeMap<Rune, (SpacedRune, RuneId, RuneEntry, u128)> = BTreeMap::new();
+    let mut destinations: Vec<&Address> = Vec::new();
+    let mut edicts = Vec::new();
+
+    for (destination, spaced_rune, decimal) in sends {
+      let (_, id, entry, total) = match runes.entry(spaced_rune.rune) {
+        btree_map::Entry::Occupied(occupied) => occupied.into_mut(),
+        btree_map::Entry::Vacant(vacant) => {
+          let (id, entry, _parent) = wallet
+            .get_rune(spaced_rune.rune)?
+            .with_context(|| format!("rune `{}` has not been etched", spaced_rune.rune))?;
+
+          vacant.insert((*spaced_rune, id, entry, 0))
+        }
+      };
+
+      let amount = decimal.to_integer(entry.divisibility)?;
+
+      // a zero amount edict would send the entire balance
+      ensure!(amount > 0, "cannot send zero `{spaced_rune}`");
+
+      *total = total
+        .checked_add(amount)
+        .with_context(|| format!("total amount of `{spaced_rune}` to send overflows"))?;
+
+      let output = match destinations
+        .iter()
+        .position(|address| *address == destination)
+      {
+        Some(position) => position,
+        None => {
+          destinations.push(destination);
+          destinations.len() - 1
+        }
+      };
+
+      edicts.push(Edict {
+        amount,
+        id: *id,
+        output: u32::try_from(output + 2).unwrap(),
+      });
+    }
 
-    let amount = decimal.to_integer(entry.divisibility)?;
+    // edicts are enciphered in rune ID order
+    edicts.sort_by_key(|edict| edict.id);
 
     let inscribed_outputs = inscriptions
       .keys()
       .map(|satpoint| satpoint.outpoint)
       .collect::<HashSet<OutPoint>>();
 
-    let mut input_runes = 0;
-    let mut input = Vec::new();
+    let mut candidates = BTreeMap::new();
 
     for output in runic_outputs {
       if inscribed_outputs.contains(&output) {
         continue;
       }
 
-      let balance = wallet.get_rune_balance_in_output(&output, entry.spaced_rune.rune)?;
+      let Some(tx_out) = wallet.utxos().get(&output) else {
+        continue;
+      };
 
-      if balance > 0 {
-        input_runes += balance;
-        input.push(output);
+      let mut balances = BTreeMap::new();
+      for (spaced_rune, pile) in wallet.get_runes_balances_for_output(&output)? {
+        *balances.entry(spaced_rune.rune).or_default() += pile.amount;
       }
 
-      if input_runes >= amount {
-        break;
-      }
+      candidates.insert(output, (tx_out.value, balances));
     }
 
-    ensure! {
-      input_runes >= amount,
-      "insufficient `{}` balance, only {} in wallet",
-      spaced_rune,
-      Pile {
-        amount: input_runes,
-        divisibility: entry.divisibility,
-        symbol: entry.symbol
-      },
+    let selection = RuneSelector::new(
+      candidates,
+      runes
+        .iter()
+        .map(|(rune, (_, _, _, total))| (*rune, *total))
+        .collect(),
+    )
+    .select()
+    .map_err(|err| match err {
+      rune_selector::Error::InsufficientBalance { rune, available } => {
+        let (spaced_rune, _, entry, _) = &runes[&rune];
+        anyhow!(
+          "insufficient `{}` balance, only {} in wallet",
+          spaced_rune,
+          Pile {
+            amount: available,
+            divisibility: entry.divisibility,
+            symbol: entry.symbol
+          },
+        )
+      }
+      err => err.into(),
+    })?;
+
+    // every rune carried by the selected inputs, including those which are
+    // only carried along and must be returned as change
+    let mut input_balances = BTreeMap::new();
+    for (rune, balance) in &selection.balances {
+      let id = match runes.get(rune) {
+        Some((_, id, _, _)) => *id,
+        None => {
+          wallet
+            .get_rune(*rune)?
+            .with_context(|| format!("rune `{rune}` has not been etched"))?
+            .0
+        }
+      };
+
+      input_balances.insert(id, *balance);
     }
 
+    let input = selection.inputs;
+
     let runestone = Runestone {
-      edicts: vec![Edict {
-        amount,
-        id,
-        output: 2,
-      }],
+      edicts,
       ..default()
     };
 
+    let script_pubkey = runestone.encipher();
+
+    ensure!(
+      script_pubkey.len() <= 82,
+      "runestone greater than maximum OP_RETURN size: {} > 82",
+      script_pubkey.len()
+    );
+
+    let mut output = vec![
+      TxOut {
+        script_pubkey,
+        value: 0,
+      },
+      TxOut {
+        script_pubkey: wallet.get_change_address()?.script_pubkey(),
+        value: TARGET_POSTAGE.to_sat(),
+      },
+    ];
+
+    for destination in destinations {
+      output.push(TxOut {
+        script_pubkey: destination.script_pubkey(),
+        value: TARGET_POSTAGE.to_sat(),
+      });
+    }
+
     let unfunded_transaction = Transaction {
       version: 2,
       lock_time: LockTime::ZERO,
@@ -285,20 +516,7 @@ impl Send {
           witness: Witness::new(),
         })
         .collect(),
-      output: vec![
-        TxOut {
-          script_pubkey: runestone.encipher(),
-          value: 0,
-        },
-        TxOut {
-          script_pubkey: wallet.get_change_address()?.script_pubkey(),
-          value: TARGET_POSTAGE.to_sat(),
-        },
-        TxOut {
-          script_pubkey: destination.script_pubkey(),
-          value: TARGET_POSTAGE.to_sat(),
-        },
-      ],
+      output,
     };
 
     let unsigned_transaction =
@@ -306,6 +524,8 @@ impl Send {
 
     let unsigned_transaction = consensus::encode::deserialize(&unsigned_transaction)?;
 
+    rune_selector::check_allocation(&unsigned_transaction, &input_balances)?;
+
     assert_eq!(
       Runestone::decipher(&unsigned_transaction),
       Some(Artifact::Runestone(runestone)),
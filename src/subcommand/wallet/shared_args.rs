@@ -13,6 +13,12 @@ pub(super) struct SharedArgs {
   pub(crate) fee_rate: FeeRate,
   #[arg(long, help = "Don't sign or broadcast transactions.")]
   pub(crate) dry_run: bool,
+  #[arg(
+    long,
+    conflicts_with = "dry_run",
+    help = "Don't broadcast transactions. Emit an unsigned commit PSBT and a reveal PSBT signed by ord, to be signed elsewhere and submitted with `ord wallet broadcast`."
+  )]
+  pub(crate) psbt_out: bool,
   #[arg(long, alias = "nobackup", help = "Do not back up recovery key.")]
   pub(crate) no_backup: bool,
   #[arg(
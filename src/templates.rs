@@ -9,7 +9,9 @@ pub(crate) use {
   home::HomeHtml,
   iframe::Iframe,
   indexer::{
-    InscriptionsEntriesJson, InscriptionsTransfersJson, RunesEventsJson, StatsUpdaterJson,
+    InscriptionsEntriesJson, InscriptionsTransfersJson, ReorgsJson, RuneAuditJson, RuneCheckJson,
+    RuneEventsJson, RuneHolderJson, RuneHoldersJson, RunesAddressEventsJson, RunesEntriesJson,
+    RunesEventsJson, StatsUpdaterJson,
   },
   input::InputHtml,
   inscription::InscriptionHtml,
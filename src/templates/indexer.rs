@@ -1,6 +1,7 @@
 use super::*;
 use crate::indexer::inscription_entries::InscriptionEntry;
 use crate::indexer::inscription_transfer::InscriptionTransfer;
+use crate::indexer::reorg_log::ReorgLogEntry;
 use crate::indexer::rune_event::{BlockId, RuneChanges, RuneEventResponse};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -8,20 +9,106 @@ pub struct RunesChangesJson {
   pub data: Vec<RuneChanges>,
   pub total: u64,
   pub block: u32,
+  pub block_hash: Option<BlockHash>,
+  pub tip_hash: Option<BlockHash>,
   pub size: usize,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunesEntriesJson {
+  pub runes: Vec<(RuneId, RuneEntry)>,
+  pub block: u32,
+  pub block_hash: Option<BlockHash>,
+  pub tip_hash: Option<BlockHash>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct RunesEventsJson {
   pub events: Vec<(BlockId, RuneEventResponse)>,
   pub total: u64,
   pub block: u32,
+  pub block_hashes: BTreeMap<u32, BlockHash>,
+  pub tip_hash: Option<BlockHash>,
+  pub size: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunesAddressEventsJson {
+  pub address: String,
+  pub events: Vec<(BlockId, RuneEventResponse)>,
+  pub block_hashes: BTreeMap<u32, BlockHash>,
+  pub tip_hash: Option<BlockHash>,
+  pub size: u32,
+  pub next: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneEventsJson {
+  pub rune_id: RuneId,
+  pub events: Vec<(BlockId, RuneEventResponse)>,
+  pub block_hashes: BTreeMap<u32, BlockHash>,
+  pub tip_hash: Option<BlockHash>,
+  pub size: u32,
+  pub next: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneAuditJson {
+  pub rune_id: RuneId,
+  pub spaced_rune: SpacedRune,
+  pub supply: String,
+  pub burned: String,
+  pub minted: String,
+  pub events: u64,
+  pub event_minted: String,
+  pub event_burned: String,
+  pub credited: String,
+  pub debited: String,
+  pub outstanding: Option<String>,
+  pub balanced: bool,
+  pub tip_hash: Option<BlockHash>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneCheckJson {
+  pub rune: SpacedRune,
+  pub available: bool,
+  pub reserved: bool,
+  pub etched: Option<RuneId>,
+  pub height: u32,
+  pub minimum: Rune,
+  pub unlocked: bool,
+  pub unlock_height: Option<u32>,
+  pub commitment: String,
+  pub commit_confirmations: u16,
+  pub reveal_height: Option<u32>,
+  pub mempool: Vec<Txid>,
+  pub pending_etching: Option<bool>,
+  pub tip_hash: Option<BlockHash>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneHolderJson {
+  pub script_pubkey: ScriptBuf,
+  pub address: Option<String>,
+  pub amount: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneHoldersJson {
+  pub rune_id: RuneId,
+  pub holders: Vec<RuneHolderJson>,
+  pub total: u64,
   pub size: u32,
+  pub next: Option<String>,
+  pub tip_hash: Option<BlockHash>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct InscriptionsTransfersJson {
   pub transfers: Vec<(u32, String, InscriptionTransfer)>,
+  pub block_hashes: BTreeMap<u32, BlockHash>,
+  pub tip_hash: Option<BlockHash>,
   pub total: u32,
   pub page_index: u32,
   pub page_size: u32,
@@ -31,6 +118,8 @@ pub struct InscriptionsTransfersJson {
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct InscriptionsEntriesJson {
   pub entries: Vec<(u32, Option<InscriptionEntry>)>,
+  pub block_hashes: BTreeMap<u32, BlockHash>,
+  pub tip_hash: Option<BlockHash>,
   pub total: u32,
   pub page_index: u32,
   pub page_size: u32,
@@ -41,8 +130,18 @@ pub struct InscriptionsEntriesJson {
 pub struct StatsUpdaterJson {
   pub network: Network,
   pub height: u32,
+  pub tip_hash: Option<BlockHash>,
   pub runes: u32,
   pub inscriptions: u32,
   pub runes_events: u64,
   pub inscriptions_transfer: u32,
 }
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReorgsJson {
+  pub reorgs: Vec<(u32, ReorgLogEntry)>,
+  pub tip_hash: Option<BlockHash>,
+  pub total: u64,
+  pub size: u32,
+  pub next: Option<u32>,
+}
@@ -2,7 +2,7 @@ use {
   super::*,
   base64::{self, Engine},
   batch::ParentInfo,
-  bitcoin::secp256k1::{All, Secp256k1},
+  bitcoin::secp256k1::{All, Secp256k1, SecretKey},
   bitcoin::{
     bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint},
     psbt::Psbt,
@@ -13,7 +13,10 @@ use {
   index::entry::Entry,
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
-  miniscript::descriptor::{DescriptorSecretKey, DescriptorXKey, Wildcard},
+  miniscript::{
+    descriptor::{DescriptorPublicKey, DescriptorSecretKey, DescriptorXKey, Wildcard},
+    ForEachKey,
+  },
   redb::{Database, DatabaseError, ReadableTable, RepairSession, StorageError, TableDefinition},
   reqwest::header,
   std::sync::Once,
@@ -22,12 +25,14 @@ use {
 
 pub mod batch;
 pub mod entry;
+pub mod rune_selector;
 pub mod transaction_builder;
 pub mod wallet_constructor;
 
-const SCHEMA_VERSION: u64 = 1;
+const SCHEMA_VERSION: u64 = 2;
 
 define_table! { RUNE_TO_ETCHING, u128, EtchingEntryValue }
+define_table! { RUNE_TO_REVEAL_KEY, u128, &[u8; 32] }
 define_table! { STATISTICS, u64, u64 }
 
 #[derive(Copy, Clone)]
@@ -60,6 +65,7 @@ pub(crate) struct Wallet {
   inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
   locked_utxos: BTreeMap<OutPoint, TxOut>,
   settings: Settings,
+  watch_only: bool,
 }
 
 impl Wallet {
@@ -174,11 +180,15 @@ impl Wallet {
     )
   }
 
-  pub(crate) fn get_parent_info(
-    &self,
-    parent: Option<InscriptionId>,
-  ) -> Result<Option<ParentInfo>> {
-    if let Some(parent_id) = parent {
+  pub(crate) fn get_parent_info(&self, parents: &[InscriptionId]) -> Result<Vec<ParentInfo>> {
+    let mut parent_info = Vec::<ParentInfo>::new();
+
+    for &parent_id in parents {
+      ensure!(
+        !parent_info.iter().any(|info| info.id == parent_id),
+        "duplicate parent {parent_id}"
+      );
+
       if !self.inscription_exists(parent_id)? {
         return Err(anyhow!("parent {parent_id} does not exist"));
       }
@@ -189,21 +199,34 @@ impl Wallet {
         .ok_or_else(|| anyhow!("parent {parent_id} not in wallet"))?
         .satpoint;
 
+      // @br-indexer: each parent is spent by its own reveal input and returned
+      // in its own reveal output
+      if let Some(other) = parent_info
+        .iter()
+        .find(|info| info.location.outpoint == satpoint.outpoint)
+      {
+        bail!(
+          "parents {} and {parent_id} are in the same output {}",
+          other.id,
+          satpoint.outpoint
+        );
+      }
+
       let tx_out = self
         .utxos
         .get(&satpoint.outpoint)
         .ok_or_else(|| anyhow!("parent {parent_id} not in wallet"))?
         .clone();
 
-      Ok(Some(ParentInfo {
+      parent_info.push(ParentInfo {
         destination: self.get_change_address()?,
         id: parent_id,
         location: satpoint,
         tx_out,
-      }))
-    } else {
-      Ok(None)
+      });
     }
+
+    Ok(parent_info)
   }
 
   pub(crate) fn get_runic_outputs(&self) -> Result<BTreeSet<OutPoint>> {
@@ -231,22 +254,6 @@ impl Wallet {
     )
   }
 
-  pub(crate) fn get_rune_balance_in_output(&self, output: &OutPoint, rune: Rune) -> Result<u128> {
-    Ok(
-      self
-        .get_runes_balances_for_output(output)?
-        .iter()
-        .map(|(spaced_rune, pile)| {
-          if spaced_rune.rune == rune {
-            pile.amount
-          } else {
-            0
-          }
-        })
-        .sum(),
-    )
-  }
-
   pub(crate) fn get_rune(
     &self,
     rune: Rune,
@@ -292,6 +299,21 @@ impl Wallet {
     self.settings.chain()
   }
 
+  pub(crate) fn watch_only(&self) -> bool {
+    self.watch_only
+  }
+
+  // @br-indexer: watch-only wallets hold no private keys, so anything that
+  // needs a signature must be handed off as a PSBT instead
+  pub(crate) fn ensure_can_sign(&self) -> Result {
+    ensure!(
+      !self.watch_only,
+      "wallet is watch-only and cannot sign transactions, use `--psbt-out` to create PSBTs to be signed elsewhere"
+    );
+
+    Ok(())
+  }
+
   pub(crate) fn integration_test(&self) -> bool {
     self.settings.integration_test()
   }
@@ -358,6 +380,45 @@ impl Wallet {
     self.send_etching(rune, &entry)
   }
 
+  // @br-indexer: PSBT for `transaction` that keeps the witnesses ord already
+  // made, like reveal script path spends, as final witnesses, and includes
+  // every spent output so that other signers can sign the remaining inputs.
+  // Inputs may spend outputs of `parents`, which need not be broadcast yet.
+  pub(crate) fn partially_signed_psbt(
+    &self,
+    transaction: &Transaction,
+    parents: &[&Transaction],
+  ) -> Result<String> {
+    let mut unsigned_tx = transaction.clone();
+
+    for txin in &mut unsigned_tx.input {
+      txin.witness = Witness::new();
+    }
+
+    let mut psbt = Psbt::from_unsigned_tx(unsigned_tx)?;
+
+    for (input, txin) in psbt.inputs.iter_mut().zip(&transaction.input) {
+      let outpoint = txin.previous_output;
+
+      input.witness_utxo = parents
+        .iter()
+        .find(|parent| parent.txid() == outpoint.txid)
+        .and_then(|parent| {
+          parent
+            .output
+            .get(usize::try_from(outpoint.vout).unwrap())
+            .cloned()
+        })
+        .or_else(|| self.utxos.get(&outpoint).cloned());
+
+      if !txin.witness.is_empty() {
+        input.final_script_witness = Some(txin.witness.clone());
+      }
+    }
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(psbt.serialize()))
+  }
+
   pub(crate) fn send_etching(&self, rune: Rune, entry: &EtchingEntry) -> Result<batch::Output> {
     match self.bitcoin_client().send_raw_transaction(&entry.reveal) {
       Ok(txid) => txid,
@@ -471,6 +532,88 @@ impl Wallet {
     Ok(())
   }
 
+  // @br-indexer: import public descriptors into a wallet with private keys
+  // disabled. Takes either one descriptor with a `<0;1>` multipath key, or a
+  // receive descriptor followed by a change descriptor.
+  pub(crate) fn initialize_watch_only(
+    name: String,
+    settings: &Settings,
+    descriptors: &[String],
+    timestamp: Timestamp,
+  ) -> Result {
+    let network = settings.chain().network();
+
+    let secp = Secp256k1::new();
+
+    let mut parsed = Vec::new();
+
+    for descriptor in descriptors {
+      let (descriptor, key_map) =
+        miniscript::Descriptor::<DescriptorPublicKey>::parse_descriptor(&secp, descriptor)
+          .with_context(|| format!("invalid descriptor `{descriptor}`"))?;
+
+      ensure!(
+        key_map.is_empty(),
+        "descriptor contains private keys, watch-only wallets only take public descriptors"
+      );
+
+      if descriptor.is_multipath() {
+        parsed.extend(descriptor.into_single_descriptors()?);
+      } else {
+        parsed.push(descriptor);
+      }
+    }
+
+    let [receive, change] = <[_; 2]>::try_from(parsed).map_err(|parsed| {
+      anyhow!(
+        "expected a receive and a change descriptor, got {} descriptors",
+        parsed.len()
+      )
+    })?;
+
+    for descriptor in [&receive, &change] {
+      ensure!(
+        matches!(descriptor, miniscript::Descriptor::Tr(tr) if tr.taptree().is_none())
+          && descriptor.has_wildcard(),
+        "descriptor `{descriptor}` is not a ranged key path taproot descriptor `tr(KEY/*)`"
+      );
+
+      ensure!(
+        descriptor.for_each_key(|key| match key {
+          DescriptorPublicKey::XPub(xpub) =>
+            (xpub.xkey.network == Network::Bitcoin) == (network == Network::Bitcoin),
+          DescriptorPublicKey::MultiXPub(xpub) =>
+            (xpub.xkey.network == Network::Bitcoin) == (network == Network::Bitcoin),
+          DescriptorPublicKey::Single(_) => true,
+        }),
+        "descriptor `{descriptor}` is not for {network}"
+      );
+    }
+
+    let client = Self::check_version(settings.bitcoin_rpc_client(None)?)?;
+
+    client.create_wallet(&name, Some(true), Some(true), None, None)?;
+
+    settings
+      .bitcoin_rpc_client(Some(name.clone()))?
+      .import_descriptors(
+        [(receive, false), (change, true)]
+          .into_iter()
+          .map(|(descriptor, internal)| ImportDescriptors {
+            descriptor: descriptor.to_string(),
+            timestamp,
+            active: Some(true),
+            range: None,
+            next_index: None,
+            internal: Some(internal),
+            label: None,
+          })
+          .collect(),
+      )?;
+
+    Ok(())
+  }
+
   fn derive_and_import_descriptor(
     name: String,
     settings: &Settings,
@@ -534,6 +677,40 @@ impl Wallet {
     )
   }
 
+  // @br-indexer: looks for a pending etching without constructing the
+  // wallet, which needs bitcoind and ord server, or creating its database.
+  // Returns `None` if the database is held open by another ord process, such
+  // as `ord wallet etch-watch`.
+  pub(crate) fn has_pending_etching(
+    wallet_name: &str,
+    settings: &Settings,
+    rune: Rune,
+  ) -> Result<Option<bool>> {
+    let path = settings
+      .data_dir()
+      .join("wallets")
+      .join(format!("{wallet_name}.redb"));
+
+    if !path.exists() {
+      return Ok(Some(false));
+    }
+
+    let database = match Database::open(&path) {
+      Ok(database) => database,
+      Err(DatabaseError::DatabaseAlreadyOpen) => return Ok(None),
+      Err(err) => {
+        return Err(err)
+          .with_context(|| format!("failed to open wallet database `{}`", path.display()))
+      }
+    };
+
+    let rtx = database.begin_read()?;
+
+    Ok(Some(
+      rtx.open_table(RUNE_TO_ETCHING)?.get(rune.0)?.is_some(),
+    ))
+  }
+
   pub(crate) fn open_database(wallet_name: &String, settings: &Settings) -> Result<Database> {
     let path = settings
       .data_dir()
@@ -582,13 +759,24 @@ impl Wallet {
     {
       Ok(database) => {
         {
-          let schema_version = database
+          let mut schema_version = database
             .begin_read()?
             .open_table(STATISTICS)?
             .get(&Statistic::Schema.key())?
             .map(|x| x.value())
             .unwrap_or(0);
 
+          // @br-indexer: schema 2 adds the reveal key table
+          if schema_version == 1 {
+            let tx = database.begin_write()?;
+            tx.open_table(RUNE_TO_REVEAL_KEY)?;
+            tx.open_table(STATISTICS)?
+              .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
+            tx.commit()?;
+
+            schema_version = SCHEMA_VERSION;
+          }
+
           match schema_version.cmp(&SCHEMA_VERSION) {
             cmp::Ordering::Less =>
               bail!(
@@ -616,6 +804,8 @@ impl Wallet {
 
         tx.open_table(RUNE_TO_ETCHING)?;
 
+        tx.open_table(RUNE_TO_REVEAL_KEY)?;
+
         tx.open_table(STATISTICS)?
           .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
 
@@ -635,6 +825,7 @@ impl Wallet {
     commit: &Transaction,
     reveal: &Transaction,
     output: batch::Output,
+    reveal_key: Option<&SecretKey>,
   ) -> Result {
     let wtx = self.database.begin_write()?;
 
@@ -648,6 +839,17 @@ impl Wallet {
       .store(),
     )?;
 
+    // @br-indexer: the key that signs the reveal's script path spend of the
+    // commit output, kept so that `ord wallet etch-watch` can re-sign a
+    // fee-bumped reveal. The key is stored unencrypted, and anyone who can read
+    // the wallet database can spend the commit output with it, so it is only
+    // passed when the etching was made with `--save-reveal-key`.
+    if let Some(reveal_key) = reveal_key {
+      wtx
+        .open_table(RUNE_TO_REVEAL_KEY)?
+        .insert(rune.0, &reveal_key.secret_bytes())?;
+    }
+
     wtx.commit()?;
 
     Ok(())
@@ -664,10 +866,23 @@ impl Wallet {
     )
   }
 
+  pub(crate) fn load_reveal_key(&self, rune: Rune) -> Result<Option<SecretKey>> {
+    let rtx = self.database.begin_read()?;
+
+    Ok(
+      rtx
+        .open_table(RUNE_TO_REVEAL_KEY)?
+        .get(rune.0)?
+        .map(|key| SecretKey::from_slice(key.value()))
+        .transpose()?,
+    )
+  }
+
   pub(crate) fn clear_etching(&self, rune: Rune) -> Result {
     let wtx = self.database.begin_write()?;
 
     wtx.open_table(RUNE_TO_ETCHING)?.remove(rune.0)?;
+    wtx.open_table(RUNE_TO_REVEAL_KEY)?.remove(rune.0)?;
     wtx.commit()?;
 
     Ok(())
@@ -35,6 +35,10 @@ pub struct Output {
   pub commit_psbt: Option<String>,
   pub inscriptions: Vec<InscriptionInfo>,
   pub parent: Option<InscriptionId>,
+  // @br-indexer: `parent` is the first of `parents`, kept for existing
+  // consumers of single-parent output
+  #[serde(default)]
+  pub parents: Vec<InscriptionId>,
   pub reveal: Txid,
   pub reveal_broadcast: bool,
   pub reveal_psbt: Option<String>,
@@ -86,7 +90,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint: Some(satpoint(1, 0)),
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -132,7 +136,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint: Some(satpoint(1, 0)),
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -177,7 +181,7 @@ mod tests {
 
     let error = batch::Plan {
       satpoint,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -229,7 +233,7 @@ mod tests {
 
     assert!(batch::Plan {
       satpoint,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -279,7 +283,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(fee_rate).unwrap(),
@@ -367,7 +371,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_info: vec![parent_info.clone()],
       inscriptions: vec![child_inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(fee_rate).unwrap(),
@@ -454,7 +458,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(commit_fee_rate).unwrap(),
@@ -513,7 +517,7 @@ mod tests {
 
     let error = batch::Plan {
       satpoint,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -554,7 +558,7 @@ mod tests {
 
     let batch::Transactions { reveal_tx, .. } = batch::Plan {
       satpoint,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -635,7 +639,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_info: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -688,6 +692,92 @@ mod tests {
     );
   }
 
+  #[test]
+  fn batch_inscribe_with_multiple_parents() {
+    let utxos = vec![
+      (outpoint(1), tx_out(10_000, address())),
+      (outpoint(2), tx_out(20_000, address())),
+      (outpoint(3), tx_out(50_000, address())),
+    ];
+
+    let parent_info = [(1, 3), (2, 0)]
+      .into_iter()
+      .map(|(n, change_index)| ParentInfo {
+        destination: change(change_index),
+        id: inscription_id(n),
+        location: SatPoint {
+          outpoint: outpoint(n.into()),
+          offset: 0,
+        },
+        tx_out: TxOut {
+          script_pubkey: change(0).script_pubkey(),
+          value: u64::from(n) * 10_000,
+        },
+      })
+      .collect::<Vec<ParentInfo>>();
+
+    let parents = parent_info
+      .iter()
+      .map(|info| info.id)
+      .collect::<Vec<InscriptionId>>();
+
+    let mut wallet_inscriptions = BTreeMap::new();
+    for info in &parent_info {
+      wallet_inscriptions.insert(info.location, vec![info.id]);
+    }
+
+    let batch::Transactions { reveal_tx, .. } = batch::Plan {
+      parent_info: parent_info.clone(),
+      inscriptions: vec![
+        InscriptionTemplate {
+          parents: parents.clone(),
+          ..default()
+        }
+        .into(),
+        InscriptionTemplate {
+          parents: parents.clone(),
+          ..default()
+        }
+        .into(),
+      ],
+      destinations: vec![recipient(), recipient()],
+      postages: vec![Amount::from_sat(10_000); 2],
+      mode: batch::Mode::SeparateOutputs,
+      ..default()
+    }
+    .create_batch_transactions(
+      wallet_inscriptions,
+      Chain::Signet,
+      BTreeSet::new(),
+      BTreeSet::new(),
+      utxos.into_iter().collect(),
+      [change(1), change(2)],
+      change(2),
+    )
+    .unwrap();
+
+    assert_eq!(reveal_tx.input.len(), 3);
+    assert_eq!(reveal_tx.output.len(), 4);
+
+    for (i, info) in parent_info.iter().enumerate() {
+      assert_eq!(reveal_tx.input[i].previous_output, info.location.outpoint);
+      assert_eq!(
+        reveal_tx.output[i].script_pubkey,
+        info.destination.script_pubkey()
+      );
+      assert_eq!(reveal_tx.output[i].value, info.tx_out.value);
+    }
+
+    assert!(reveal_tx.input[0].witness.is_empty());
+    assert!(reveal_tx.input[1].witness.is_empty());
+    assert!(!reveal_tx.input[2].witness.is_empty());
+
+    for output in &reveal_tx.output[2..] {
+      assert_eq!(output.script_pubkey, recipient().script_pubkey());
+      assert_eq!(output.value, 10_000);
+    }
+  }
+
   #[test]
   fn batch_inscribe_satpoints_with_parent() {
     let utxos = vec![
@@ -762,7 +852,7 @@ mod tests {
       ..
     } = batch::Plan {
       reveal_satpoints: reveal_satpoints.clone(),
-      parent_info: Some(parent_info.clone()),
+      parent_info: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -864,7 +954,7 @@ mod tests {
 
     let error = batch::Plan {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_info: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: 4.0.try_into().unwrap(),
@@ -941,7 +1031,7 @@ mod tests {
 
     let _ = batch::Plan {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_info: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: 4.0.try_into().unwrap(),
@@ -980,7 +1070,7 @@ mod tests {
 
     let error = batch::Plan {
       satpoint: None,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: 1.0.try_into().unwrap(),
@@ -1034,7 +1124,7 @@ mod tests {
 
     let batch::Transactions { reveal_tx, .. } = batch::Plan {
       satpoint: None,
-      parent_info: None,
+      parent_info: Vec::new(),
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -1119,7 +1209,7 @@ mod tests {
       ..
     } = batch::Plan {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_info: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -6,6 +6,8 @@ pub struct File {
   pub inscriptions: Vec<Entry>,
   pub mode: Mode,
   pub parent: Option<InscriptionId>,
+  #[serde(default)]
+  pub parents: Vec<InscriptionId>,
   pub postage: Option<u64>,
   #[serde(default)]
   pub reinscribe: bool,
@@ -103,11 +105,17 @@ impl File {
     Ok(batchfile)
   }
 
+  // @br-indexer: `parent` predates `parents` and is kept so that existing
+  // batchfiles still load. If both are given, `parent` comes first.
+  pub(crate) fn parents(&self) -> Vec<InscriptionId> {
+    self.parent.iter().chain(&self.parents).copied().collect()
+  }
+
   pub(crate) fn inscriptions(
     &self,
     wallet: &Wallet,
     utxos: &BTreeMap<OutPoint, TxOut>,
-    parent_value: Option<u64>,
+    parent_value: u64,
     compress: bool,
   ) -> Result<(
     Vec<Inscription>,
@@ -119,7 +127,7 @@ impl File {
     let mut reveal_satpoints = Vec::new();
     let mut postages = Vec::new();
 
-    let mut pointer = parent_value.unwrap_or_default();
+    let mut pointer = parent_value;
 
     for (i, entry) in self.inscriptions.iter().enumerate() {
       if let Some(delegate) = entry.delegate {
@@ -135,7 +143,7 @@ impl File {
         entry.delegate,
         entry.metadata()?,
         entry.metaprotocol.clone(),
-        self.parent.into_iter().collect(),
+        self.parents(),
         entry.file.clone(),
         Some(pointer),
         self
@@ -371,6 +379,7 @@ inscriptions:
             .parse()
             .unwrap()
         ),
+        parents: Vec::new(),
         postage: Some(12345),
         reinscribe: true,
         sat: None,
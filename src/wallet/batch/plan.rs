@@ -9,12 +9,14 @@ pub struct Plan {
   pub(crate) mode: Mode,
   pub(crate) no_backup: bool,
   pub(crate) no_limit: bool,
-  pub(crate) parent_info: Option<ParentInfo>,
+  pub(crate) parent_info: Vec<ParentInfo>,
   pub(crate) postages: Vec<Amount>,
+  pub(crate) psbt_out: bool,
   pub(crate) reinscribe: bool,
   pub(crate) reveal_fee_rate: FeeRate,
   pub(crate) reveal_satpoints: Vec<(SatPoint, TxOut)>,
   pub(crate) satpoint: Option<SatPoint>,
+  pub(crate) save_reveal_key: bool,
 }
 
 impl Default for Plan {
@@ -28,12 +30,14 @@ impl Default for Plan {
       mode: Mode::SharedOutput,
       no_backup: false,
       no_limit: false,
-      parent_info: None,
+      parent_info: Vec::new(),
       postages: vec![Amount::from_sat(10_000)],
+      psbt_out: false,
       reinscribe: false,
       reveal_fee_rate: 1.0.try_into().unwrap(),
       reveal_satpoints: Vec::new(),
       satpoint: None,
+      save_reveal_key: false,
     }
   }
 }
@@ -51,6 +55,7 @@ impl Plan {
       commit_vout,
       reveal_tx,
       recovery_key_pair,
+      reveal_key_pair,
       total_fees,
       rune,
     } = self.create_batch_transactions(
@@ -64,16 +69,7 @@ impl Plan {
     )?;
 
     if self.dry_run {
-      let commit_psbt = wallet
-        .bitcoin_client()
-        .wallet_process_psbt(
-          &base64::engine::general_purpose::STANDARD
-            .encode(Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?.serialize()),
-          Some(false),
-          None,
-          None,
-        )?
-        .psbt;
+      let commit_psbt = Self::commit_psbt(wallet, &commit_tx)?;
 
       let reveal_psbt = Psbt::from_unsigned_tx(Self::remove_witnesses(reveal_tx.clone()))?;
 
@@ -89,6 +85,55 @@ impl Plan {
       ))));
     }
 
+    // @br-indexer: hand both transactions off for signing elsewhere. The
+    // reveal keeps ord's signature of the commit output, and etchings are
+    // saved so that `ord wallet resume` can reveal them once the commit
+    // matures.
+    if self.psbt_out {
+      if !self.no_backup {
+        Self::backup_recovery_key(wallet, recovery_key_pair)?;
+      }
+
+      let commit_psbt = Self::commit_psbt(wallet, &commit_tx)?;
+
+      let reveal_psbt = wallet.partially_signed_psbt(&reveal_tx, &[&commit_tx])?;
+
+      if let Some(ref rune_info) = rune {
+        wallet.save_etching(
+          &rune_info.rune.rune,
+          &Self::remove_witnesses(commit_tx.clone()),
+          &reveal_tx,
+          self.output(
+            commit_tx.txid(),
+            None,
+            reveal_tx.txid(),
+            false,
+            None,
+            total_fees,
+            self.inscriptions.clone(),
+            rune.clone(),
+          ),
+          self
+            .save_reveal_key
+            .then(|| reveal_key_pair.secret_key())
+            .as_ref(),
+        )?;
+      }
+
+      return Ok(Some(Box::new(self.output(
+        commit_tx.txid(),
+        Some(commit_psbt),
+        reveal_tx.txid(),
+        false,
+        Some(reveal_psbt),
+        total_fees,
+        self.inscriptions.clone(),
+        rune,
+      ))));
+    }
+
+    wallet.ensure_can_sign()?;
+
     let signed_commit_tx = wallet
       .bitcoin_client()
       .sign_raw_transaction_with_wallet(&commit_tx, None, None)?
@@ -151,6 +196,10 @@ impl Plan {
           self.inscriptions.clone(),
           rune.clone(),
         ),
+        self
+          .save_reveal_key
+          .then(|| reveal_key_pair.secret_key())
+          .as_ref(),
       )?;
 
       Ok(Some(Box::new(
@@ -182,6 +231,21 @@ impl Plan {
     }
   }
 
+  fn commit_psbt(wallet: &Wallet, commit_tx: &Transaction) -> Result<String> {
+    Ok(
+      wallet
+        .bitcoin_client()
+        .wallet_process_psbt(
+          &base64::engine::general_purpose::STANDARD
+            .encode(Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?.serialize()),
+          Some(false),
+          None,
+          None,
+        )?
+        .psbt,
+    )
+  }
+
   fn remove_witnesses(mut transaction: Transaction) -> Transaction {
     for txin in transaction.input.iter_mut() {
       txin.witness = Witness::new();
@@ -201,25 +265,21 @@ impl Plan {
     inscriptions: Vec<Inscription>,
     rune: Option<RuneInfo>,
   ) -> Output {
+    let parents = self
+      .parent_info
+      .iter()
+      .map(|info| info.id)
+      .collect::<Vec<InscriptionId>>();
+
+    let parent_outputs = u32::try_from(parents.len()).unwrap();
+
     let mut inscriptions_output = Vec::new();
     for i in 0..inscriptions.len() {
       let index = u32::try_from(i).unwrap();
 
       let vout = match self.mode {
-        Mode::SharedOutput | Mode::SameSat => {
-          if self.parent_info.is_some() {
-            1
-          } else {
-            0
-          }
-        }
-        Mode::SeparateOutputs | Mode::SatPoints => {
-          if self.parent_info.is_some() {
-            index + 1
-          } else {
-            index
-          }
-        }
+        Mode::SharedOutput | Mode::SameSat => parent_outputs,
+        Mode::SeparateOutputs | Mode::SatPoints => index + parent_outputs,
       };
 
       let offset = match self.mode {
@@ -252,7 +312,8 @@ impl Plan {
       commit,
       commit_psbt,
       inscriptions: inscriptions_output,
-      parent: self.parent_info.clone().map(|info| info.id),
+      parent: parents.first().copied(),
+      parents,
       reveal,
       reveal_broadcast,
       reveal_psbt,
@@ -271,9 +332,15 @@ impl Plan {
     commit_change: [Address; 2],
     reveal_change: Address,
   ) -> Result<Transactions> {
-    if let Some(parent_info) = &self.parent_info {
+    if !self.parent_info.is_empty() {
+      let parents = self
+        .parent_info
+        .iter()
+        .map(|info| info.id)
+        .collect::<Vec<InscriptionId>>();
+
       for inscription in &self.inscriptions {
-        assert_eq!(inscription.parents(), vec![parent_info.id]);
+        assert_eq!(inscription.parents(), parents);
       }
     }
 
@@ -396,12 +463,12 @@ impl Plan {
     let mut reveal_inputs = Vec::new();
     let mut reveal_outputs = Vec::new();
 
-    if let Some(ParentInfo {
+    for ParentInfo {
       location,
       id: _,
       destination,
       tx_out,
-    }) = self.parent_info.clone()
+    } in self.parent_info.clone()
     {
       reveal_inputs.push(location.outpoint);
       reveal_outputs.push(TxOut {
@@ -505,7 +572,7 @@ impl Plan {
       runestone = None;
     }
 
-    let commit_input = usize::from(self.parent_info.is_some()) + self.reveal_satpoints.len();
+    let commit_input = self.parent_info.len() + self.reveal_satpoints.len();
 
     let (_reveal_tx, reveal_fee) = Self::build_reveal_transaction(
       commit_input,
@@ -571,8 +638,8 @@ impl Plan {
 
     let mut prevouts = Vec::new();
 
-    if let Some(parent_info) = self.parent_info.clone() {
-      prevouts.push(parent_info.tx_out);
+    for parent_info in &self.parent_info {
+      prevouts.push(parent_info.tx_out.clone());
     }
 
     if self.mode == Mode::SatPoints {
@@ -669,6 +736,7 @@ impl Plan {
       commit_tx: unsigned_commit_tx,
       commit_vout: vout,
       recovery_key_pair,
+      reveal_key_pair: key_pair,
       reveal_tx,
       rune,
       total_fees,
@@ -676,6 +744,11 @@ impl Plan {
   }
 
   fn backup_recovery_key(wallet: &Wallet, recovery_key_pair: TweakedKeyPair) -> Result {
+    ensure!(
+      !wallet.watch_only(),
+      "watch-only wallets cannot back up the commit tx recovery key, use `--no-backup` to skip the backup"
+    );
+
     let recovery_private_key = PrivateKey::new(
       recovery_key_pair.to_inner().secret_key(),
       wallet.chain().network(),
@@ -6,6 +6,7 @@ pub(crate) struct Transactions {
   pub(crate) commit_tx: Transaction,
   pub(crate) commit_vout: usize,
   pub(crate) recovery_key_pair: TweakedKeyPair,
+  pub(crate) reveal_key_pair: UntweakedKeyPair,
   pub(crate) reveal_tx: Transaction,
   pub(crate) total_fees: u64,
 }
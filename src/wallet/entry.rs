@@ -77,6 +77,7 @@ mod tests {
       commit_psbt: None,
       inscriptions: Vec::new(),
       parent: None,
+      parents: Vec::new(),
       reveal: txid,
       reveal_broadcast: true,
       reveal_psbt: None,
@@ -0,0 +1,491 @@
+//! Rune-aware coin selection.
+//!
+//! Runic outputs often carry more than one rune, and every rune in a selected
+//! input must end up somewhere. `RuneSelector` picks the fewest runic inputs
+//! that cover the outgoing amounts, and reports the full balances of the
+//! selected inputs, including runes which are only carried along, so that the
+//! caller can leave them in change.
+//!
+//! Selection is greedy. Each round picks the candidate that fully covers the
+//! most outstanding runes, then the one that covers the largest share of what
+//! is still needed. Ties prefer candidates that carry fewer unrelated runes,
+//! leave less excess, and hold fewer sats, in that order.
+//!
+//! `check_allocation` replays the rune allocation rules against a finished
+//! transaction, and refuses transactions that are cenotaphs or that would burn
+//! any part of the input balances, either by allocating them to an OP_RETURN
+//! output or by leaving them without a destination.
+
+use {super::*, ordinals::Flaw};
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+  Burn { id: RuneId, amount: u128 },
+  Cenotaph(Option<Flaw>),
+  InsufficientBalance { rune: Rune, available: u128 },
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Error::Burn { id, amount } => {
+        write!(f, "transaction would burn {amount} of rune {id}")
+      }
+      Error::Cenotaph(Some(flaw)) => write!(f, "transaction would be a cenotaph: {flaw}"),
+      Error::Cenotaph(None) => write!(f, "transaction would be a cenotaph"),
+      Error::InsufficientBalance { rune, available } => {
+        write!(
+          f,
+          "insufficient `{rune}` balance, only {available} in wallet"
+        )
+      }
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Selection {
+  pub inputs: Vec<OutPoint>,
+  pub balances: BTreeMap<Rune, u128>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RuneSelector {
+  candidates: BTreeMap<OutPoint, (u64, BTreeMap<Rune, u128>)>,
+  outgoing: BTreeMap<Rune, u128>,
+}
+
+impl RuneSelector {
+  /// `candidates` maps spendable runic outputs to their value and rune
+  /// balances, `outgoing` holds the total amount of each rune to send
+  pub fn new(
+    candidates: BTreeMap<OutPoint, (u64, BTreeMap<Rune, u128>)>,
+    outgoing: BTreeMap<Rune, u128>,
+  ) -> Self {
+    Self {
+      candidates,
+      outgoing: outgoing
+        .into_iter()
+        .filter(|(_rune, amount)| *amount > 0)
+        .collect(),
+    }
+  }
+
+  pub fn select(mut self) -> Result<Selection, Error> {
+    for (rune, amount) in &self.outgoing {
+      let available = self
+        .candidates
+        .values()
+        .map(|(_value, balances)| balances.get(rune).copied().unwrap_or_default())
+        .fold(0u128, u128::saturating_add);
+
+      if available < *amount {
+        return Err(Error::InsufficientBalance {
+          rune: *rune,
+          available,
+        });
+      }
+    }
+
+    let mut remaining = self.outgoing.clone();
+    let mut selection = Selection::default();
+
+    while !remaining.is_empty() {
+      let outpoint = self
+        .candidates
+        .iter()
+        .map(|(outpoint, (value, balances))| (Self::score(&remaining, *value, balances), *outpoint))
+        .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+        .map(|(_score, outpoint)| outpoint)
+        .unwrap();
+
+      let (_value, balances) = self.candidates.remove(&outpoint).unwrap();
+
+      for (rune, balance) in balances {
+        *selection.balances.entry(rune).or_default() += balance;
+
+        if let Some(amount) = remaining.get_mut(&rune) {
+          *amount = amount.saturating_sub(balance);
+
+          if *amount == 0 {
+            remaining.remove(&rune);
+          }
+        }
+      }
+
+      selection.inputs.push(outpoint);
+    }
+
+    Ok(selection)
+  }
+
+  /// Candidates that score higher are selected first. Fields are compared in
+  /// order, with penalties negated so that smaller penalties score higher.
+  fn score(
+    remaining: &BTreeMap<Rune, u128>,
+    value: u64,
+    balances: &BTreeMap<Rune, u128>,
+  ) -> (usize, f64, i64, f64, i64) {
+    let mut covered = 0;
+    let mut progress = 0.0;
+    let mut excess = 0.0;
+
+    for (rune, amount) in remaining {
+      let balance = balances.get(rune).copied().unwrap_or_default();
+
+      if balance >= *amount {
+        covered += 1;
+        excess += (balance - amount) as f64;
+      }
+
+      progress += balance.min(*amount) as f64 / *amount as f64;
+    }
+
+    let unrelated = balances
+      .iter()
+      .filter(|(rune, balance)| **balance > 0 && !remaining.contains_key(rune))
+      .count();
+
+    (
+      covered,
+      progress,
+      -i64::try_from(unrelated).unwrap(),
+      -excess,
+      -i64::try_from(value).unwrap_or(i64::MAX),
+    )
+  }
+}
+
+/// Fails if `transaction` is a cenotaph, or if any part of `balances`, the rune
+/// balances of its inputs, would be burned
+pub fn check_allocation(
+  transaction: &Transaction,
+  balances: &BTreeMap<RuneId, u128>,
+) -> Result<(), Error> {
+  let runestone = match Runestone::decipher(transaction) {
+    Some(Artifact::Cenotaph(cenotaph)) => return Err(Error::Cenotaph(cenotaph.flaw)),
+    Some(Artifact::Runestone(runestone)) => runestone,
+    None => Runestone::default(),
+  };
+
+  let destinations = transaction
+    .output
+    .iter()
+    .enumerate()
+    .filter_map(|(output, tx_out)| (!tx_out.script_pubkey.is_op_return()).then_some(output))
+    .collect::<Vec<usize>>();
+
+  let mut unallocated = balances.clone();
+  let mut allocated: Vec<BTreeMap<RuneId, u128>> = vec![BTreeMap::new(); transaction.output.len()];
+
+  for Edict { id, amount, output } in runestone.edicts {
+    let output = usize::try_from(output).unwrap();
+
+    let Some(balance) = unallocated.get_mut(&id) else {
+      continue;
+    };
+
+    let mut allocate = |balance: &mut u128, amount: u128, output: usize| {
+      if amount > 0 {
+        *balance -= amount;
+        *allocated[output].entry(id).or_default() += amount;
+      }
+    };
+
+    if output == transaction.output.len() {
+      if destinations.is_empty() {
+        continue;
+      }
+
+      if amount == 0 {
+        let amount = *balance / destinations.len() as u128;
+        let remainder = usize::try_from(*balance % destinations.len() as u128).unwrap();
+
+        for (i, output) in destinations.iter().enumerate() {
+          allocate(
+            balance,
+            if i < remainder { amount + 1 } else { amount },
+            *output,
+          );
+        }
+      } else {
+        for output in &destinations {
+          allocate(balance, amount.min(*balance), *output);
+        }
+      }
+    } else {
+      let amount = if amount == 0 {
+        *balance
+      } else {
+        amount.min(*balance)
+      };
+
+      allocate(balance, amount, output);
+    }
+  }
+
+  let default_output = runestone
+    .pointer
+    .map(|pointer| usize::try_from(pointer).unwrap())
+    .or_else(|| destinations.first().copied());
+
+  for (id, balance) in unallocated {
+    if balance == 0 {
+      continue;
+    }
+
+    match default_output {
+      Some(output) => *allocated[output].entry(id).or_default() += balance,
+      None => {
+        return Err(Error::Burn {
+          id,
+          amount: balance,
+        })
+      }
+    }
+  }
+
+  for (output, balances) in allocated.into_iter().enumerate() {
+    if transaction.output[output].script_pubkey.is_op_return() {
+      if let Some((id, amount)) = balances.into_iter().find(|(_id, amount)| *amount > 0) {
+        return Err(Error::Burn { id, amount });
+      }
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::Error, super::*, bitcoin::WPubkeyHash};
+
+  fn candidates(
+    outputs: &[(u32, u64, &[(u128, u128)])],
+  ) -> BTreeMap<OutPoint, (u64, BTreeMap<Rune, u128>)> {
+    outputs
+      .iter()
+      .map(|(vout, value, balances)| {
+        (
+          OutPoint {
+            txid: Txid::all_zeros(),
+            vout: *vout,
+          },
+          (
+            *value,
+            balances
+              .iter()
+              .map(|(rune, amount)| (Rune(*rune), *amount))
+              .collect(),
+          ),
+        )
+      })
+      .collect()
+  }
+
+  fn outgoing(amounts: &[(u128, u128)]) -> BTreeMap<Rune, u128> {
+    amounts
+      .iter()
+      .map(|(rune, amount)| (Rune(*rune), *amount))
+      .collect()
+  }
+
+  fn inputs(selection: &Selection) -> Vec<u32> {
+    selection
+      .inputs
+      .iter()
+      .map(|outpoint| outpoint.vout)
+      .collect()
+  }
+
+  fn transaction(outputs: &[ScriptBuf]) -> Transaction {
+    Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: outputs
+        .iter()
+        .map(|script_pubkey| TxOut {
+          value: 0,
+          script_pubkey: script_pubkey.clone(),
+        })
+        .collect(),
+    }
+  }
+
+  fn change() -> ScriptBuf {
+    ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::all_zeros())
+  }
+
+  const ID: RuneId = RuneId { block: 1, tx: 1 };
+  const OTHER: RuneId = RuneId { block: 2, tx: 1 };
+
+  #[test]
+  fn single_covering_output_is_preferred_over_several_smaller_ones() {
+    let selection = RuneSelector::new(
+      candidates(&[
+        (0, 10_000, &[(0, 60)]),
+        (1, 10_000, &[(0, 50)]),
+        (2, 10_000, &[(0, 100)]),
+      ]),
+      outgoing(&[(0, 100)]),
+    )
+    .select()
+    .unwrap();
+
+    assert_eq!(inputs(&selection), [2]);
+    assert_eq!(selection.balances, outgoing(&[(0, 100)]));
+  }
+
+  #[test]
+  fn remainder_is_covered_with_least_excess() {
+    let selection = RuneSelector::new(
+      candidates(&[
+        (0, 10_000, &[(0, 60)]),
+        (1, 10_000, &[(0, 50)]),
+        (2, 10_000, &[(0, 45)]),
+      ]),
+      outgoing(&[(0, 100)]),
+    )
+    .select()
+    .unwrap();
+
+    assert_eq!(inputs(&selection), [0, 2]);
+    assert_eq!(selection.balances, outgoing(&[(0, 105)]));
+  }
+
+  #[test]
+  fn outputs_carrying_several_outgoing_runes_minimize_inputs() {
+    let selection = RuneSelector::new(
+      candidates(&[
+        (0, 10_000, &[(0, 100)]),
+        (1, 10_000, &[(1, 100)]),
+        (2, 10_000, &[(0, 100), (1, 100)]),
+      ]),
+      outgoing(&[(0, 100), (1, 100)]),
+    )
+    .select()
+    .unwrap();
+
+    assert_eq!(inputs(&selection), [2]);
+  }
+
+  #[test]
+  fn outputs_without_unrelated_runes_are_preferred() {
+    let selection = RuneSelector::new(
+      candidates(&[(0, 10_000, &[(0, 100), (1, 5)]), (1, 10_000, &[(0, 100)])]),
+      outgoing(&[(0, 100)]),
+    )
+    .select()
+    .unwrap();
+
+    assert_eq!(inputs(&selection), [1]);
+  }
+
+  #[test]
+  fn co_carried_runes_are_reported() {
+    let selection = RuneSelector::new(
+      candidates(&[(0, 10_000, &[(0, 100), (1, 5)])]),
+      outgoing(&[(0, 10)]),
+    )
+    .select()
+    .unwrap();
+
+    assert_eq!(selection.balances, outgoing(&[(0, 100), (1, 5)]));
+  }
+
+  #[test]
+  fn insufficient_balance_is_an_error() {
+    assert_eq!(
+      RuneSelector::new(
+        candidates(&[(0, 10_000, &[(0, 60)]), (1, 10_000, &[(1, 500)])]),
+        outgoing(&[(0, 100)]),
+      )
+      .select(),
+      Err(Error::InsufficientBalance {
+        rune: Rune(0),
+        available: 60,
+      })
+    );
+  }
+
+  #[test]
+  fn leftover_balances_go_to_first_non_op_return_output() {
+    let runestone = Runestone {
+      edicts: vec![Edict {
+        id: ID,
+        amount: 10,
+        output: 2,
+      }],
+      ..default()
+    };
+
+    check_allocation(
+      &transaction(&[runestone.encipher(), change(), change()]),
+      &[(ID, 100), (OTHER, 5)].into(),
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn edicts_to_op_return_outputs_are_burns() {
+    let runestone = Runestone {
+      edicts: vec![Edict {
+        id: ID,
+        amount: 10,
+        output: 0,
+      }],
+      ..default()
+    };
+
+    assert_eq!(
+      check_allocation(
+        &transaction(&[runestone.encipher(), change()]),
+        &[(ID, 100)].into(),
+      ),
+      Err(Error::Burn { id: ID, amount: 10 })
+    );
+  }
+
+  #[test]
+  fn leftover_balances_without_destination_are_burns() {
+    let runestone = Runestone {
+      edicts: vec![Edict {
+        id: ID,
+        amount: 10,
+        output: 1,
+      }],
+      ..default()
+    };
+
+    assert_eq!(
+      check_allocation(
+        &transaction(&[runestone.encipher(), ScriptBuf::new_op_return(&[])]),
+        &[(ID, 100), (OTHER, 5)].into(),
+      ),
+      Err(Error::Burn { id: ID, amount: 90 })
+    );
+  }
+
+  #[test]
+  fn cenotaphs_are_refused() {
+    let runestone = Runestone {
+      edicts: vec![Edict {
+        id: ID,
+        amount: 10,
+        output: 5,
+      }],
+      ..default()
+    };
+
+    assert_eq!(
+      check_allocation(
+        &transaction(&[runestone.encipher(), change()]),
+        &[(ID, 100)].into(),
+      ),
+      Err(Error::Cenotaph(Some(Flaw::EdictOutput)))
+    );
+  }
+}
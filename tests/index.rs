@@ -1,4 +1,7 @@
-use super::*;
+use {
+  super::*,
+  ord::index::{export::ExportInfo, snapshot::SnapshotInfo},
+};
 
 #[test]
 fn run_is_an_alias_for_update() {
@@ -90,3 +93,131 @@ fn export_inscription_number_to_id_tsv() {
     &ord::Object::InscriptionId(inscription),
   );
 }
+
+#[test]
+fn export_tables_as_json_lines() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let export = tempdir.path().join("export");
+
+  let info = CommandBuilder::new(format!("index export --output {}", export.display()))
+    .core(&core)
+    .run_and_deserialize_output::<ExportInfo>();
+
+  assert_eq!(info.from_height, 0);
+  assert_eq!(info.to_height, 3);
+  assert_eq!(info.tables["inscriptions"], 1);
+
+  let inscriptions = fs::read_to_string(export.join("inscriptions.jsonl")).unwrap();
+
+  let row: serde_json::Value = serde_json::from_str(inscriptions.lines().next().unwrap()).unwrap();
+
+  assert_eq!(row["id"], inscription.to_string());
+  assert_eq!(row["number"], 0);
+  assert_eq!(row["content_type"], "text/plain;charset=utf-8");
+  assert_eq!(row["parents"], serde_json::json!([]));
+
+  let info = CommandBuilder::new(format!(
+    "index export --output {} --from-height 3 --format columnar",
+    export.display(),
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<ExportInfo>();
+
+  assert_eq!(info.tables["inscriptions"], 0);
+
+  assert_eq!(
+    fs::read_to_string(export.join("inscriptions.columns.jsonl")).unwrap(),
+    "",
+  );
+}
+
+#[test]
+fn export_requires_tsv_or_output() {
+  CommandBuilder::new("index export --from-height 1")
+    .expected_exit_code(2)
+    .stderr_regex(".*--output <OUTPUT>.*")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot_create_and_load() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+  core.mine_blocks(3);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let snapshot = tempdir.path().join("snapshot");
+
+  let created = CommandBuilder::new(format!(
+    "--regtest --index-runes --no-index-inscriptions --index {} index snapshot create {}",
+    tempdir.path().join("a.redb").display(),
+    snapshot.display(),
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<SnapshotInfo>();
+
+  assert_eq!(created.height, 3);
+
+  let index = tempdir.path().join("b.redb");
+
+  let loaded = CommandBuilder::new(format!(
+    "--regtest --index-runes --no-index-inscriptions --index {} index snapshot load --hash {} {}",
+    index.display(),
+    created.hash,
+    snapshot.display(),
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<SnapshotInfo>();
+
+  assert_eq!(loaded, created);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--regtest --index-runes --no-index-inscriptions --index {} index update",
+    index.display(),
+  ))
+  .core(&core)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot_load_rejects_other_chain() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+  core.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let snapshot = tempdir.path().join("snapshot");
+
+  let created = CommandBuilder::new(format!(
+    "--regtest --index-runes --no-index-inscriptions --index {} index snapshot create {}",
+    tempdir.path().join("a.redb").display(),
+    snapshot.display(),
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<SnapshotInfo>();
+
+  let core = mockcore::builder().network(Network::Signet).build();
+
+  CommandBuilder::new(format!(
+    "--signet --index-runes --no-index-inscriptions --index {} index snapshot load --hash {} {}",
+    tempdir.path().join("b.redb").display(),
+    created.hash,
+    snapshot.display(),
+  ))
+  .core(&core)
+  .expected_exit_code(1)
+  .expected_stderr("error: snapshot is for regtest but ord is on signet\n")
+  .run_and_extract_stdout();
+}
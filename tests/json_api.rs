@@ -549,6 +549,7 @@ fn get_runes() {
         timestamp: 10,
         turbo: false,
       },
+      holders: 1,
       id: RuneId { block: 10, tx: 1 },
       mintable: false,
       parent: Some(InscriptionId {
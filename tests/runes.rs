@@ -1,4 +1,7 @@
-use {super::*, ord::subcommand::runes::Output};
+use {
+  super::*,
+  ord::{subcommand::runes::Output, templates::indexer::RuneCheckJson},
+};
 
 #[test]
 fn flag_is_required() {
@@ -141,3 +144,117 @@ fn two_runes() {
     }
   );
 }
+
+#[test]
+fn check_requires_rune_index() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  CommandBuilder::new("--regtest runes check AAAAAAAAAAAAA")
+    .core(&core)
+    .expected_exit_code(1)
+    .expected_stderr("error: `ord runes check` requires index created with `--index-runes` flag\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn check_etched_rune() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let check = CommandBuilder::new("--index-runes --regtest runes check AAAAAAAAAAAAA")
+    .core(&core)
+    .run_and_deserialize_output::<RuneCheckJson>();
+
+  assert!(check.available);
+  assert_eq!(check.etched, None);
+  assert_eq!(check.pending_etching, Some(false));
+
+  etch(&core, &ord, Rune(RUNE));
+
+  let check = CommandBuilder::new("--index-runes --regtest runes check AAAAAAAAAAAAA")
+    .core(&core)
+    .run_and_deserialize_output::<RuneCheckJson>();
+
+  assert!(!check.available);
+  assert_eq!(check.etched, Some(RuneId { block: 7, tx: 1 }));
+}
+
+#[test]
+fn check_pending_etching() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let batchfile = batch::File {
+    etching: Some(batch::Etching {
+      divisibility: 0,
+      rune: SpacedRune {
+        rune: Rune(RUNE),
+        spacers: 0,
+      },
+      supply: "1000".parse().unwrap(),
+      premine: "1000".parse().unwrap(),
+      symbol: '¢',
+      ..default()
+    }),
+    inscriptions: vec![batch::Entry {
+      file: Some("inscription.jpeg".into()),
+      ..default()
+    }],
+    ..default()
+  };
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let output = CommandBuilder::new(
+    "--regtest --index-runes wallet batch --psbt-out --fee-rate 0 --batch batch.yaml",
+  )
+  .temp_dir(tempdir.clone())
+  .write("batch.yaml", serde_yaml::to_string(&batchfile).unwrap())
+  .write("inscription.jpeg", "inscription")
+  .core(&core)
+  .ord(&ord)
+  .spawn()
+  .child
+  .wait_with_output()
+  .unwrap();
+
+  assert!(output.status.success());
+
+  let run_check = || {
+    let output = CommandBuilder::new("--index-runes --regtest runes check AAAAAAAAAAAAA")
+      .temp_dir(tempdir.clone())
+      .core(&core)
+      .spawn()
+      .child
+      .wait_with_output()
+      .unwrap();
+
+    assert!(output.status.success());
+
+    serde_json::from_slice::<RuneCheckJson>(&output.stdout).unwrap()
+  };
+
+  let check = run_check();
+
+  assert!(!check.available);
+  assert_eq!(check.etched, None);
+  assert_eq!(check.pending_etching, Some(true));
+  assert!(check.mempool.is_empty());
+
+  // while another process holds the wallet database, such as `ord wallet
+  // etch-watch`, pending etchings can't be checked
+  let _database = redb::Database::open(tempdir.path().join("regtest/wallets/ord.redb")).unwrap();
+
+  let check = run_check();
+
+  assert!(check.available);
+  assert_eq!(check.pending_etching, None);
+}
@@ -20,16 +20,21 @@ fn default() {
   "height_limit": null,
   "hidden": \[\],
   "index": ".*index\.redb",
+  "index_addresses": false,
   "index_cache_size": \d+,
+  "index_events": false,
+  "index_from_blk_files": false,
   "index_runes": false,
   "index_sats": false,
   "index_spent_sats": false,
+  "index_threads": \d+,
   "index_transactions": false,
   "integration_test": false,
   "no_index_inscriptions": false,
   "server_password": null,
   "server_url": null,
-  "server_username": null
+  "server_username": null,
+  "webhooks": null
 \}
 "#,
     )
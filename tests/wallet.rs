@@ -3,9 +3,11 @@ use super::*;
 mod authentication;
 mod balance;
 mod batch_command;
+mod broadcast;
 mod cardinals;
 mod create;
 mod dump;
+mod etch_watch;
 mod inscribe;
 mod inscriptions;
 mod label;
@@ -168,6 +168,135 @@ fn batch_inscribe_with_multiple_inscriptions_with_parent() {
   assert_eq!(request.headers().get("content-type").unwrap(), "audio/wav");
 }
 
+#[test]
+fn batch_inscribe_with_multiple_parents() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  let mut parents = Vec::new();
+
+  for file in ["parent-a.png", "parent-b.png"] {
+    parents.push(
+      CommandBuilder::new(format!("wallet inscribe --fee-rate 5.0 --file {file}"))
+        .write(file, [1; 520])
+        .core(&core)
+        .ord(&ord)
+        .run_and_deserialize_output::<Batch>()
+        .inscriptions[0]
+        .id,
+    );
+
+    core.mine_blocks(1);
+  }
+
+  let output = CommandBuilder::new("wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      format!(
+        "parent: {}
+parents:
+- {}
+mode: separate-outputs
+inscriptions:
+- file: inscription.txt
+  metadata:
+    title: Hello
+- delegate: {}
+",
+        parents[0], parents[1], parents[0],
+      ),
+    )
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(output.parent, Some(parents[0]));
+  assert_eq!(output.parents, parents);
+
+  for (i, inscription) in output.inscriptions.iter().enumerate() {
+    assert_eq!(
+      inscription.location.outpoint.vout,
+      u32::try_from(i).unwrap() + 2
+    );
+  }
+
+  core.mine_blocks(1);
+
+  for inscription in &output.inscriptions {
+    let response = ord.json_request(format!("/inscription/{}", inscription.id));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      serde_json::from_str::<api::Inscription>(&response.text().unwrap())
+        .unwrap()
+        .parents,
+      parents,
+    );
+  }
+
+  for parent in &parents {
+    let response = ord.json_request(format!("/r/children/{parent}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      serde_json::from_str::<api::Children>(&response.text().unwrap())
+        .unwrap()
+        .ids,
+      output
+        .inscriptions
+        .iter()
+        .map(|inscription| inscription.id)
+        .collect::<Vec<InscriptionId>>(),
+    );
+  }
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", output.inscriptions[0].id),
+    r".*<dt>metadata</dt>\s*<dd>\s*<dl><dt>title</dt><dd>Hello</dd></dl>\s*</dd>.*",
+  );
+
+  let request = ord.request(format!("/content/{}", output.inscriptions[1].id));
+  assert_eq!(request.status(), 200);
+  assert_eq!(request.bytes().unwrap().as_ref(), [1; 520]);
+}
+
+#[test]
+fn batch_inscribe_with_duplicate_parents_fails() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let parent = CommandBuilder::new("wallet inscribe --fee-rate 5.0 --file parent.png")
+    .write("parent.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>()
+    .inscriptions[0]
+    .id;
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      format!("parents:\n- {parent}\n- {parent}\nmode: separate-outputs\ninscriptions:\n- file: inscription.txt\n"),
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(format!("error: duplicate parent {parent}\n"))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
 #[test]
 fn batch_inscribe_respects_dry_run_flag() {
   let core = mockcore::spawn();
@@ -0,0 +1,210 @@
+use {super::*, ord::subcommand::wallet::broadcast::Output};
+
+#[test]
+fn inscribe_with_psbt_out_then_broadcast() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let inscribe =
+    CommandBuilder::new("wallet inscribe --psbt-out --file degenerate.png --fee-rate 1")
+      .write("degenerate.png", [1; 520])
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  assert!(!inscribe.reveal_broadcast);
+  assert!(core.mempool().is_empty());
+
+  let commit = inscribe.commit_psbt.unwrap();
+  let reveal = inscribe.reveal_psbt.unwrap();
+
+  let output = CommandBuilder::new(format!("wallet broadcast --psbt {commit} --psbt {reveal}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.txids, [inscribe.commit, inscribe.reveal]);
+  assert_eq!(core.mempool().len(), 2);
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/inscription/{}", inscribe.inscriptions[0].id),
+    ".*<h1>Inscription 0</h1>.*",
+  );
+}
+
+#[test]
+fn broadcast_requires_psbt() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new("wallet broadcast")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(".*the following required arguments were not provided.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn broadcast_rejects_invalid_psbt() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new("wallet broadcast --psbt foo")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex("error: PSBT 1 is not valid base64\n.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn etching_reveal_is_held_until_commit_matures() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let batchfile = batch::File {
+    etching: Some(batch::Etching {
+      divisibility: 0,
+      rune: SpacedRune {
+        rune: Rune(RUNE),
+        spacers: 0,
+      },
+      supply: "1000".parse().unwrap(),
+      premine: "1000".parse().unwrap(),
+      symbol: '¢',
+      ..default()
+    }),
+    inscriptions: vec![batch::Entry {
+      file: Some("inscription.jpeg".into()),
+      ..default()
+    }],
+    ..default()
+  };
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let batch = serde_json::from_str::<Batch>(&run_in(
+    &tempdir,
+    CommandBuilder::new(
+      "--regtest --index-runes wallet batch --psbt-out --fee-rate 0 --batch batch.yaml",
+    )
+    .temp_dir(tempdir.clone())
+    .write("batch.yaml", serde_yaml::to_string(&batchfile).unwrap())
+    .write("inscription.jpeg", "inscription")
+    .core(&core)
+    .ord(&ord),
+  ))
+  .unwrap();
+
+  assert!(core.mempool().is_empty());
+
+  let commit = batch.commit_psbt.unwrap();
+  let reveal = batch.reveal_psbt.unwrap();
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet broadcast --psbt {commit} --psbt {reveal}"
+  ))
+  .temp_dir(tempdir.clone())
+  .core(&core)
+  .ord(&ord)
+  .spawn()
+  .child
+  .wait_with_output()
+  .unwrap();
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr).unwrap(),
+    "error: commitment of rune AAAAAAAAAAAAA has not matured, \
+    reveal it with `ord wallet resume --psbt-out` once it has\n",
+  );
+
+  assert!(core.mempool().is_empty());
+
+  run_in(
+    &tempdir,
+    CommandBuilder::new(format!(
+      "--regtest --index-runes wallet broadcast --psbt {commit}"
+    ))
+    .core(&core)
+    .ord(&ord),
+  );
+
+  core.mine_blocks(6);
+
+  let resume = serde_json::from_str::<ord::subcommand::wallet::resume::ResumeOutput>(&run_in(
+    &tempdir,
+    CommandBuilder::new("--regtest --index-runes wallet resume --psbt-out")
+      .core(&core)
+      .ord(&ord),
+  ))
+  .unwrap();
+
+  let etching = resume.etchings.first().unwrap();
+
+  assert!(!etching.reveal_broadcast);
+  assert!(core.mempool().is_empty());
+
+  let output = serde_json::from_str::<Output>(&run_in(
+    &tempdir,
+    CommandBuilder::new(format!(
+      "--regtest --index-runes wallet broadcast --psbt {}",
+      etching.reveal_psbt.clone().unwrap()
+    ))
+    .core(&core)
+    .ord(&ord),
+  ))
+  .unwrap();
+
+  assert_eq!(output.txids, [batch.reveal]);
+
+  core.mine_blocks(1);
+
+  let resume = CommandBuilder::new("--regtest --index-runes wallet resume")
+    .temp_dir(tempdir)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::wallet::resume::ResumeOutput>();
+
+  assert!(resume.etchings.is_empty());
+
+  ord.assert_response_regex(
+    "/rune/AAAAAAAAAAAAA",
+    ".*<dt>premine</dt>\\s*<dd>1000\\xA0¢</dd>.*",
+  );
+}
+
+// runs a command in a data directory shared with later commands, which
+// `CommandBuilder::run` can't do since it takes ownership of the directory
+pub(super) fn run_in(tempdir: &Arc<TempDir>, command: CommandBuilder) -> String {
+  let output = command
+    .temp_dir(tempdir.clone())
+    .spawn()
+    .child
+    .wait_with_output()
+    .unwrap();
+
+  assert!(
+    output.status.success(),
+    "{}",
+    str::from_utf8(&output.stderr).unwrap()
+  );
+
+  String::from_utf8(output.stdout).unwrap()
+}
@@ -91,3 +91,168 @@ fn create_with_different_name() {
 
   assert!(core.wallets().contains("inscription-wallet"));
 }
+
+fn watch_only_descriptor(network: Network) -> String {
+  use bitcoin::{
+    bip32::{ExtendedPrivKey, ExtendedPubKey},
+    secp256k1::Secp256k1,
+  };
+
+  let secp = Secp256k1::new();
+
+  let xpub = ExtendedPubKey::from_priv(
+    &secp,
+    &ExtendedPrivKey::new_master(network, &[0; 32]).unwrap(),
+  );
+
+  format!("tr({xpub}/<0;1>/*)")
+}
+
+#[test]
+fn create_watch_only_wallet_imports_public_descriptors() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new(format!(
+    "wallet create --watch-only --descriptor {}",
+    watch_only_descriptor(Network::Bitcoin)
+  ))
+  .core(&core)
+  .run_and_extract_stdout();
+
+  assert!(core.wallets().contains("ord"));
+
+  assert_eq!(core.descriptors().len(), 2);
+  assert_regex_match!(
+    &core.descriptors()[0],
+    r"tr\(xpub[[:alnum:]]*/0/\*\)#[[:alnum:]]{8}"
+  );
+  assert_regex_match!(
+    &core.descriptors()[1],
+    r"tr\(xpub[[:alnum:]]*/1/\*\)#[[:alnum:]]{8}"
+  );
+}
+
+#[test]
+fn create_watch_only_wallet_rejects_private_descriptors() {
+  let core = mockcore::spawn();
+
+  let xprv = bitcoin::bip32::ExtendedPrivKey::new_master(Network::Bitcoin, &[0; 32]).unwrap();
+
+  CommandBuilder::new(format!(
+    "wallet create --watch-only --descriptor tr({xprv}/0/*) --descriptor tr({xprv}/1/*)"
+  ))
+  .core(&core)
+  .expected_stderr(
+    "error: descriptor contains private keys, watch-only wallets only take public descriptors\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  assert!(!core.wallets().contains("ord"));
+}
+
+#[test]
+fn create_watch_only_wallet_rejects_descriptors_for_other_networks() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new(format!(
+    "wallet create --watch-only --descriptor {}",
+    watch_only_descriptor(Network::Testnet)
+  ))
+  .core(&core)
+  .stderr_regex("error: descriptor `tr\\(tpub.*/0/\\*\\)#.*` is not for bitcoin\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn create_watch_only_wallet_requires_receive_and_change_descriptors() {
+  let core = mockcore::spawn();
+
+  CommandBuilder::new(format!(
+    "wallet create --watch-only --descriptor {}",
+    watch_only_descriptor(Network::Bitcoin).replace("<0;1>", "0")
+  ))
+  .core(&core)
+  .expected_stderr("error: expected a receive and a change descriptor, got 1 descriptors\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn watch_only_wallet_creates_psbts_instead_of_signing() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  CommandBuilder::new(format!(
+    "wallet create --watch-only --descriptor {}",
+    watch_only_descriptor(Network::Bitcoin)
+  ))
+  .core(&core)
+  .run_and_extract_stdout();
+
+  core.mine_blocks(1);
+
+  assert_eq!(
+    CommandBuilder::new("wallet balance")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<ord::subcommand::wallet::balance::Output>()
+      .cardinal,
+    50 * COIN_VALUE
+  );
+
+  CommandBuilder::new("wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc")
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(
+      "error: wallet is watch-only and cannot sign transactions, \
+      use `--psbt-out` to create PSBTs to be signed elsewhere\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  let send = CommandBuilder::new(
+    "wallet send --psbt-out --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::subcommand::wallet::send::Output>();
+
+  assert!(!send.psbt.is_empty());
+  assert!(core.mempool().is_empty());
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "foo")
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(
+      "error: wallet is watch-only and cannot sign transactions, \
+      use `--psbt-out` to create PSBTs to be signed elsewhere\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet inscribe --psbt-out --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "foo")
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr(
+      "error: watch-only wallets cannot back up the commit tx recovery key, \
+      use `--no-backup` to skip the backup\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  let inscribe =
+    CommandBuilder::new("wallet inscribe --psbt-out --no-backup --fee-rate 1 --file foo.txt")
+      .write("foo.txt", "foo")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Batch>();
+
+  assert!(inscribe.commit_psbt.is_some());
+  assert!(inscribe.reveal_psbt.is_some());
+  assert!(core.mempool().is_empty());
+}
@@ -0,0 +1,439 @@
+use {
+  super::{broadcast::run_in, *},
+  ord::subcommand::wallet::etch_watch::{Event, Output, State},
+  std::process::Child,
+};
+
+// etches RUNE with its reveal held back, broadcasts the commit, and mines
+// until the commit is one block away from maturing
+fn commit_etching(
+  core: &mockcore::Handle,
+  ord: &TestServer,
+  tempdir: &Arc<TempDir>,
+  save_reveal_key: bool,
+) -> Batch {
+  create_wallet(core, ord);
+
+  core.mine_blocks(1);
+
+  let batchfile = batch::File {
+    etching: Some(batch::Etching {
+      divisibility: 0,
+      rune: SpacedRune {
+        rune: Rune(RUNE),
+        spacers: 0,
+      },
+      supply: "1000".parse().unwrap(),
+      premine: "1000".parse().unwrap(),
+      symbol: '¢',
+      ..default()
+    }),
+    inscriptions: vec![batch::Entry {
+      file: Some("inscription.jpeg".into()),
+      ..default()
+    }],
+    ..default()
+  };
+
+  let batch = serde_json::from_str::<Batch>(&run_in(
+    tempdir,
+    CommandBuilder::new(format!(
+      "--regtest --index-runes wallet batch --psbt-out --fee-rate 1 --batch batch.yaml{}",
+      if save_reveal_key {
+        " --save-reveal-key"
+      } else {
+        ""
+      }
+    ))
+    .temp_dir(tempdir.clone())
+    .write("batch.yaml", serde_yaml::to_string(&batchfile).unwrap())
+    .write("inscription.jpeg", "inscription")
+    .core(core)
+    .ord(ord),
+  ))
+  .unwrap();
+
+  run_in(
+    tempdir,
+    CommandBuilder::new(format!(
+      "--regtest --index-runes wallet broadcast --psbt {}",
+      batch.commit_psbt.clone().unwrap()
+    ))
+    .core(core)
+    .ord(ord),
+  );
+
+  core.mine_blocks(4);
+
+  batch
+}
+
+fn next_event(child: &mut Child) -> Event {
+  let mut buffer = String::new();
+
+  BufReader::new(child.stderr.as_mut().unwrap())
+    .read_line(&mut buffer)
+    .unwrap();
+
+  serde_json::from_str(&buffer).unwrap()
+}
+
+fn competing_etching(core: &mockcore::Handle) -> Txid {
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(core.height().try_into().unwrap(), 0, 0, Witness::new())],
+    outputs: 1,
+    op_return: Some(
+      Runestone {
+        etching: Some(ordinals::Etching {
+          rune: Some(Rune(RUNE)),
+          ..default()
+        }),
+        ..default()
+      }
+      .encipher(),
+    ),
+    fee: 50_000,
+    ..default()
+  })
+}
+
+#[test]
+fn etch_watch_reveals_and_bumps_contested_reveal() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let batch = commit_etching(&core, &ord, &tempdir, true);
+
+  let mut spawn = CommandBuilder::new(
+    "--regtest --index-runes wallet etch-watch --max-fee-rate 1000 --exit-when-idle",
+  )
+  .temp_dir(tempdir.clone())
+  .core(&core)
+  .ord(&ord)
+  .spawn();
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Waiting);
+  assert_eq!(event.commit, batch.commit);
+  assert_eq!(event.reveal, batch.reveal);
+  assert_eq!(event.rune.rune, Rune(RUNE));
+
+  // fund the competing etching from an output the watcher's wallet doesn't
+  // know about, so that its bump can't conflict with it
+  core.mine_blocks_with_subsidy(1, 100_000);
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Revealed);
+  assert_eq!(event.reveal, batch.reveal);
+
+  let competitor = competing_etching(&core);
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Bumped);
+  assert_ne!(event.reveal, batch.reveal);
+  assert!(event.fee_rate.unwrap() > 100.0);
+
+  let bumped = event.reveal;
+
+  let mempool = core
+    .mempool()
+    .iter()
+    .map(bitcoin::Transaction::txid)
+    .collect::<Vec<Txid>>();
+
+  assert_eq!(mempool, [competitor, bumped]);
+
+  core.mine_blocks(1);
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Confirmed, "{:?}", event.error);
+  assert_eq!(event.reveal, bumped);
+
+  let output = spawn.child.wait_with_output().unwrap();
+
+  assert!(output.status.success());
+
+  let output = serde_json::from_slice::<Output>(&output.stdout).unwrap();
+
+  assert_eq!(output.confirmed.len(), 1);
+
+  let etching = &output.confirmed[0];
+
+  assert_eq!(etching.reveal, bumped);
+  assert_eq!(etching.inscriptions[0].id.txid, bumped);
+  assert_eq!(
+    etching.rune.as_ref().unwrap().location.unwrap().txid,
+    bumped
+  );
+}
+
+#[test]
+fn etch_watch_does_not_bump_without_saved_reveal_key() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let batch = commit_etching(&core, &ord, &tempdir, false);
+
+  let mut spawn = CommandBuilder::new(
+    "--regtest --index-runes wallet etch-watch --max-fee-rate 1000 --exit-when-idle",
+  )
+  .temp_dir(tempdir.clone())
+  .core(&core)
+  .ord(&ord)
+  .spawn();
+
+  assert_eq!(next_event(&mut spawn.child).state, State::Waiting);
+
+  core.mine_blocks_with_subsidy(1, 100_000);
+
+  assert_eq!(next_event(&mut spawn.child).state, State::Revealed);
+
+  competing_etching(&core);
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Failed);
+  assert_eq!(event.reveal, batch.reveal);
+  assert_eq!(
+    event.error.unwrap(),
+    format!(
+      "no reveal key saved for rune {}, its reveal transaction cannot be bumped, etch with `ord wallet batch --save-reveal-key` to allow bumping",
+      Rune(RUNE),
+    ),
+  );
+
+  core.mine_blocks(1);
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Confirmed);
+  assert_eq!(event.reveal, batch.reveal);
+
+  assert!(spawn.child.wait().unwrap().success());
+}
+
+#[test]
+fn etch_watch_does_not_bump_past_max_fee_rate() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let batch = commit_etching(&core, &ord, &tempdir, true);
+
+  core.mine_blocks_with_subsidy(1, 100_000);
+
+  let mut spawn = CommandBuilder::new(
+    "--regtest --index-runes wallet etch-watch --max-fee-rate 10 --exit-when-idle --log log.jsonl",
+  )
+  .temp_dir(tempdir.clone())
+  .core(&core)
+  .ord(&ord)
+  .spawn();
+
+  while core.mempool().is_empty() {
+    thread::sleep(Duration::from_millis(10));
+  }
+
+  competing_etching(&core);
+
+  let log = tempdir.path().join("log.jsonl");
+
+  while !fs::read_to_string(&log)
+    .unwrap_or_default()
+    .contains("contested")
+  {
+    thread::sleep(Duration::from_millis(10));
+  }
+
+  assert!(core
+    .mempool()
+    .iter()
+    .any(|transaction| transaction.txid() == batch.reveal));
+
+  core.mine_blocks(1);
+
+  assert!(spawn.child.wait().unwrap().success());
+
+  let events = fs::read_to_string(&log)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str::<Event>(line).unwrap())
+    .collect::<Vec<Event>>();
+
+  assert_eq!(
+    events
+      .iter()
+      .map(|event| event.state)
+      .collect::<Vec<State>>(),
+    [State::Revealed, State::Contested, State::Confirmed],
+  );
+
+  assert!(events.iter().all(|event| event.reveal == batch.reveal));
+}
+
+#[test]
+fn etch_watch_stops_when_rune_is_etched_by_another_transaction() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  core.mine_blocks(1);
+
+  // commit to the rune from an output the watcher's wallet won't spend, one
+  // block ahead of the wallet's own commit
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0, Witness::new())],
+    p2tr: true,
+    ..default()
+  });
+
+  let batch = commit_etching(&core, &ord, &tempdir, true);
+
+  let tapscript = bitcoin::script::Builder::new()
+    .push_slice(bitcoin::script::PushBytesBuf::try_from(Rune(RUNE).commitment()).unwrap())
+    .into_script();
+
+  let mut witness = Witness::new();
+  witness.push(tapscript);
+  witness.push([]);
+
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(2, 1, 0, witness)],
+    op_return: Some(
+      Runestone {
+        etching: Some(ordinals::Etching {
+          rune: Some(Rune(RUNE)),
+          ..default()
+        }),
+        ..default()
+      }
+      .encipher(),
+    ),
+    outputs: 1,
+    ..default()
+  });
+
+  core.mine_blocks(1);
+
+  ord.sync_server();
+
+  let mut spawn = CommandBuilder::new(
+    "--regtest --index-runes wallet etch-watch --max-fee-rate 1000 --exit-when-idle",
+  )
+  .temp_dir(tempdir.clone())
+  .core(&core)
+  .ord(&ord)
+  .spawn();
+
+  let event = next_event(&mut spawn.child);
+  assert_eq!(event.state, State::Lost, "{:?}", event.error);
+  assert_eq!(event.commit, batch.commit);
+  assert_eq!(event.reveal, batch.reveal);
+
+  let output = spawn.child.wait_with_output().unwrap();
+
+  assert!(output.status.success());
+
+  assert!(serde_json::from_slice::<Output>(&output.stdout)
+    .unwrap()
+    .confirmed
+    .is_empty());
+
+  assert!(core
+    .mempool()
+    .iter()
+    .all(|transaction| transaction.txid() != batch.reveal));
+}
+
+#[test]
+fn etch_watch_exits_when_idle() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let output = CommandBuilder::new(
+    "--regtest --index-runes wallet etch-watch --max-fee-rate 10 --exit-when-idle",
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert!(output.confirmed.is_empty());
+}
+
+#[test]
+fn wallet_databases_without_reveal_keys_are_migrated() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  run_in(
+    &tempdir,
+    CommandBuilder::new("--regtest wallet create")
+      .core(&core)
+      .ord(&ord),
+  );
+
+  let etch_watch = || {
+    run_in(
+      &tempdir,
+      CommandBuilder::new(
+        "--regtest --index-runes wallet etch-watch --max-fee-rate 10 --exit-when-idle",
+      )
+      .core(&core)
+      .ord(&ord),
+    )
+  };
+
+  etch_watch();
+
+  let path = tempdir.path().join("regtest/wallets/ord.redb");
+
+  let statistics = redb::TableDefinition::<u64, u64>::new("STATISTICS");
+
+  // roll the wallet database back to schema 1, which predates reveal keys
+  {
+    let database = redb::Database::open(&path).unwrap();
+    let wtx = database.begin_write().unwrap();
+    assert!(wtx
+      .delete_table(redb::TableDefinition::<u128, &[u8; 32]>::new(
+        "RUNE_TO_REVEAL_KEY"
+      ))
+      .unwrap());
+    wtx.open_table(statistics).unwrap().insert(0, 1).unwrap();
+    wtx.commit().unwrap();
+  }
+
+  etch_watch();
+
+  let database = redb::Database::open(&path).unwrap();
+  let rtx = database.begin_read().unwrap();
+
+  assert_eq!(
+    rtx
+      .open_table(statistics)
+      .unwrap()
+      .get(0)
+      .unwrap()
+      .unwrap()
+      .value(),
+    2
+  );
+
+  assert!(rtx
+    .list_tables()
+    .unwrap()
+    .any(|table| redb::TableHandle::name(&table) == "RUNE_TO_REVEAL_KEY"));
+}
@@ -655,6 +655,53 @@ fn inscribe_with_parent_inscription_and_fee_rate() {
   );
 }
 
+#[test]
+fn inscribe_with_multiple_parents() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  let mut parents = Vec::new();
+
+  for _ in 0..2 {
+    parents.push(
+      CommandBuilder::new("wallet inscribe --fee-rate 1 --file parent.png")
+        .write("parent.png", [1; 520])
+        .core(&core)
+        .ord(&ord)
+        .run_and_deserialize_output::<Batch>()
+        .inscriptions[0]
+        .id,
+    );
+
+    core.mine_blocks(1);
+  }
+
+  let child = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --parent {} --parent {} --file child.png",
+    parents[0], parents[1],
+  ))
+  .write("child.png", [1; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  assert_eq!(child.parents, parents);
+  assert_eq!(child.inscriptions[0].location.outpoint.vout, 2);
+
+  core.mine_blocks(1);
+
+  for parent in parents {
+    ord.assert_response_regex(
+      format!("/inscription/{}", child.inscriptions[0].id),
+      format!(".*<dt>parents</dt>.*<a href=/inscription/{parent}>.*"),
+    );
+  }
+}
+
 #[test]
 fn reinscribe_with_flag() {
   let core = mockcore::spawn();
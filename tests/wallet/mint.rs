@@ -1,4 +1,7 @@
-use {super::*, ord::decimal::Decimal, ord::subcommand::wallet::mint};
+use {
+  super::*, base64::Engine, bitcoin::psbt::Psbt, ord::decimal::Decimal,
+  ord::subcommand::wallet::mint,
+};
 
 #[test]
 fn minting_rune_and_fails_if_after_end() {
@@ -576,3 +579,163 @@ fn minting_is_allowed_when_mint_begins_next_block() {
     }
   );
 }
+
+fn etch_open_mint(core: &mockcore::Handle, ord: &TestServer, cap: u128) {
+  batch(
+    core,
+    ord,
+    batch::File {
+      etching: Some(batch::Etching {
+        divisibility: 1,
+        rune: SpacedRune {
+          rune: Rune(RUNE),
+          spacers: 0,
+        },
+        premine: "0".parse().unwrap(),
+        symbol: '¢',
+        supply: Decimal {
+          value: 1111 * cap,
+          scale: 1,
+        },
+        terms: Some(batch::Terms {
+          cap,
+          offset: None,
+          amount: "111.1".parse().unwrap(),
+          height: None,
+        }),
+        turbo: false,
+      }),
+      inscriptions: vec![batch::Entry {
+        file: Some("inscription.jpeg".into()),
+        ..default()
+      }],
+      ..default()
+    },
+  );
+}
+
+#[test]
+fn minting_rune_with_count_chains_transactions() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  core.mine_blocks(1);
+
+  create_wallet(&core, &ord);
+
+  etch_open_mint(&core, &ord, 10);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet mint --fee-rate 1 --rune {} --count 3 --chained",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<mint::BatchOutput>();
+
+  assert_eq!(output.mints.len(), 3);
+  assert_eq!(output.error, None);
+
+  core.mine_blocks(1);
+
+  for window in output.mints.windows(2) {
+    assert_eq!(
+      core.tx_by_id(window[1].txid).input[0].previous_output,
+      OutPoint {
+        txid: window[0].txid,
+        vout: 2,
+      }
+    );
+  }
+
+  let balances = CommandBuilder::new("--regtest --index-runes balances")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::balances::Output>();
+
+  pretty_assert_eq!(
+    balances.runes[&output.rune],
+    output
+      .mints
+      .iter()
+      .map(|mint| (
+        OutPoint {
+          txid: mint.txid,
+          vout: 1
+        },
+        output.pile
+      ))
+      .collect()
+  );
+}
+
+#[test]
+fn minting_rune_with_count_reports_partial_success() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  core.mine_blocks(1);
+
+  create_wallet(&core, &ord);
+
+  etch_open_mint(&core, &ord, 2);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet mint --fee-rate 1 --rune {} --count 3",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<mint::BatchOutput>();
+
+  assert_eq!(output.mints.len(), 2);
+  assert_eq!(
+    output.error,
+    Some("rune AAAAAAAAAAAAA limited to 2 mints".into())
+  );
+
+  core.mine_blocks(1);
+
+  assert_ne!(
+    core.tx_by_id(output.mints[0].txid).input[0].previous_output,
+    core.tx_by_id(output.mints[1].txid).input[0].previous_output,
+  );
+}
+
+#[test]
+fn minting_rune_dry_run_returns_psbts_without_broadcasting() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  core.mine_blocks(1);
+
+  create_wallet(&core, &ord);
+
+  etch_open_mint(&core, &ord, 10);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet mint --fee-rate 1 --rune {} --count 2 --chained --dry-run",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<mint::BatchOutput>();
+
+  assert_eq!(output.mints.len(), 2);
+  assert!(core.mempool().is_empty());
+
+  for mint in &output.mints {
+    let psbt = Psbt::deserialize(
+      &base64::engine::general_purpose::STANDARD
+        .decode(mint.psbt.as_ref().unwrap())
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(psbt.unsigned_tx.txid(), mint.txid);
+    assert!(psbt.inputs[0].witness_utxo.is_some());
+  }
+}
@@ -209,3 +209,54 @@ fn sending_rune_does_not_send_inscription() {
   .expected_stderr("error: not enough cardinal utxos\n")
   .run_and_extract_stdout();
 }
+
+#[test]
+fn sending_rune_selects_fewest_runic_inputs() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  etch(&core, &ord, Rune(RUNE));
+
+  let address = CommandBuilder::new("--regtest wallet receive")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::wallet::receive::Output>()
+    .addresses
+    .into_iter()
+    .next()
+    .unwrap()
+    .assume_checked();
+
+  let split = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet send --fee-rate 1 {address} 900:{}",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet send --fee-rate 1 bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw 850:{}",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let runic_inputs = core
+    .tx_by_id(output.txid)
+    .input
+    .iter()
+    .filter(|input| input.previous_output.txid == split.txid)
+    .map(|input| input.previous_output.vout)
+    .collect::<Vec<u32>>();
+
+  assert_eq!(runic_inputs, [2]);
+}
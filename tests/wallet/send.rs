@@ -1110,3 +1110,136 @@ fn error_messages_use_spaced_runes() {
     .expected_stderr("error: rune `FOO` has not been etched\n")
     .run_and_extract_stdout();
 }
+
+#[test]
+fn sending_runes_from_plan_settles_every_destination_in_one_transaction() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let a = etch(&core, &ord, Rune(RUNE));
+  let b = etch(&core, &ord, Rune(RUNE + 1));
+
+  let output =
+    CommandBuilder::new("--chain regtest --index-runes wallet send --fee-rate 1 --plan plan.yaml")
+      .write(
+        "plan.yaml",
+        format!(
+          "
+- rune: {}
+  amount: 100
+  address: bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw
+- rune: {}
+  amount: 300
+  address: bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k
+- rune: {}
+  amount: 200
+  address: bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k
+",
+          Rune(RUNE + 1),
+          Rune(RUNE),
+          Rune(RUNE + 1),
+        ),
+      )
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<ord::subcommand::wallet::send::PlanOutput>();
+
+  assert_eq!(output.sends.len(), 3);
+
+  core.mine_blocks(1);
+
+  let tx = core.tx_by_id(output.txid);
+
+  pretty_assert_eq!(
+    Runestone::decipher(&tx).unwrap(),
+    Artifact::Runestone(Runestone {
+      edicts: vec![
+        Edict {
+          id: a.id,
+          amount: 300,
+          output: 3,
+        },
+        Edict {
+          id: b.id,
+          amount: 100,
+          output: 2,
+        },
+        Edict {
+          id: b.id,
+          amount: 200,
+          output: 3,
+        },
+      ],
+      ..default()
+    }),
+  );
+
+  assert_eq!(
+    tx.output[2].script_pubkey,
+    "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey()
+  );
+
+  assert_eq!(
+    tx.output[3].script_pubkey,
+    "bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey()
+  );
+
+  let balances = CommandBuilder::new("--regtest --index-runes balances")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::balances::Output>();
+
+  let change = OutPoint {
+    txid: output.txid,
+    vout: 1,
+  };
+
+  assert_eq!(
+    balances.runes[&SpacedRune::new(Rune(RUNE), 0)][&change].amount,
+    700
+  );
+
+  assert_eq!(
+    balances.runes[&SpacedRune::new(Rune(RUNE + 1), 0)][&change].amount,
+    700
+  );
+}
+
+#[test]
+fn sending_runes_from_csv_plan_checks_total_balance_per_rune() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  etch(&core, &ord, Rune(RUNE));
+
+  CommandBuilder::new("--chain regtest --index-runes wallet send --fee-rate 1 --plan plan.csv")
+    .write(
+      "plan.csv",
+      format!(
+        "rune,amount,address
+{rune},600,bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw
+{rune},600,bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k
+",
+        rune = Rune(RUNE),
+      ),
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: insufficient `AAAAAAAAAAAAA` balance, only 1000\u{A0}¢ in wallet\n")
+    .run_and_extract_stdout();
+}